@@ -14,7 +14,13 @@ use crate::core::validation::Validator;
 use crate::display::output::{OutputManager, Status};
 
 /// Create a new assignment using the template system
-pub fn create_assignment(course_id: &str, title: &str) -> Result<()> {
+pub fn create_assignment(
+    course_id: &str,
+    title: &str,
+    group: Option<&str>,
+    vars: &[String],
+    package: Option<&str>,
+) -> Result<()> {
     let config = get_config()?;
 
     // Validate course ID
@@ -75,12 +81,33 @@ pub fn create_assignment(course_id: &str, title: &str) -> Result<()> {
     }
 
     // Generate content using the template system
-    match TemplateBuilder::new(course_id, &config)?
+    let mut builder = TemplateBuilder::new(course_id, &config)?
         .with_reference(TemplateReference::assignment())
-        .with_title(title)
-        .with_sections(config.note_preferences.assignment_sections.clone())
-        .build()
+        .with_title(title);
+
+    // Course settings replace the global assignment sections
+    if !config
+        .course_overrides(course_id)
+        .sections
+        .contains_key("assignment")
     {
+        builder = builder.with_sections(config.note_preferences.assignment_sections.clone());
+    }
+
+    if let Some(group) = group {
+        builder = builder.with_group(group);
+    }
+
+    for var in vars {
+        let (key, value) = Validator::parse_variable_assignment(var)?;
+        builder = builder.with_variable(&key, &value);
+    }
+
+    if let Some(package) = package {
+        builder = builder.with_package(package);
+    }
+
+    match builder.build() {
         Ok(content) => {
             // Write file
             if let Err(e) = fs::write(&file_path, content) {
@@ -179,12 +206,11 @@ pub fn list_recent_assignments(course_id: &str, limit: usize) -> Result<()> {
         Ok(entries) => {
             for entry in entries.flatten() {
                 let path = entry.path();
-                if path.extension().is_some_and(|ext| ext == "typ") {
-                    if let Ok(metadata) = entry.metadata() {
-                        if let Ok(modified) = metadata.modified() {
-                            files.push((path.to_string_lossy().to_string(), modified));
-                        }
-                    }
+                if path.extension().is_some_and(|ext| ext == "typ")
+                    && let Ok(metadata) = entry.metadata()
+                    && let Ok(modified) = metadata.modified()
+                {
+                    files.push((path.to_string_lossy().to_string(), modified));
                 }
             }
         }
@@ -198,7 +224,7 @@ pub fn list_recent_assignments(course_id: &str, limit: usize) -> Result<()> {
     }
 
     // Sort by modification time (newest first)
-    files.sort_by(|a, b| b.1.cmp(&a.1));
+    files.sort_by_key(|file| std::cmp::Reverse(file.1));
 
     let assignments: Vec<String> = files
         .into_iter()
@@ -578,14 +604,14 @@ fn get_assignment_stats_for_directory(
         if entry.path().extension().is_some_and(|ext| ext == "typ") {
             count += 1;
 
-            if let Ok(metadata) = entry.metadata() {
-                if let Ok(modified) = metadata.modified() {
-                    match most_recent {
-                        None => most_recent = Some(modified),
-                        Some(prev_time) => {
-                            if modified > prev_time {
-                                most_recent = Some(modified);
-                            }
+            if let Ok(metadata) = entry.metadata()
+                && let Ok(modified) = metadata.modified()
+            {
+                match most_recent {
+                    None => most_recent = Some(modified),
+                    Some(prev_time) => {
+                        if modified > prev_time {
+                            most_recent = Some(modified);
                         }
                     }
                 }
@@ -1,59 +1,147 @@
 use anyhow::Result;
 use colored::*;
 use serde_json::Value;
-
-use crate::config::{Config, TemplateRepository, get_config, update_author, update_editor};
+use std::collections::BTreeMap;
+
+use crate::config::bundle::{self, ImportMode};
+use crate::config::json_path::{self, navigate_json_path, update_json_path};
+use crate::config::layers::{self, find_workspace_config};
+use crate::config::lock::ConfigLock;
+use crate::config::metadata::CONFIG_VERSION;
+use crate::config::migrations;
+use crate::config::profiles::PROFILE_SECTIONS;
+use crate::config::schema;
+use crate::config::{
+    AutoUpdatePolicy, Config, ConfigLayer, Profile, TemplateProvider, TemplateRepository,
+    get_config, update_author, update_editor,
+};
 use crate::display::output::{OutputManager, Status};
 
-pub fn show_config() -> Result<()> {
-    let config = get_config()?;
+pub fn show_config(origin: bool) -> Result<()> {
+    let (config, layers) = Config::load_layers()?;
 
     println!("{} Current Configuration:", "⚙️".blue());
     println!();
 
+    let mut origins = layers.origins(&config)?;
+    if origin {
+        println!("Layers (later ones take precedence):");
+        for layer in layers.layers() {
+            println!("  {}", format_layer(layer));
+        }
+        println!();
+    } else {
+        // Overridden values are always flagged
+        origins.retain(|_, layer| layer.is_override());
+    }
+
     // Serialize to JSON Value for smart traversal
     let json_value = serde_json::to_value(&config)?;
 
     // Display the config recursively with smart formatting
-    display_value(&json_value, 0, "");
+    display_value(&json_value, 0, "", "", Some(&origins));
+
+    if origins.values().any(ConfigLayer::is_override) {
+        println!();
+        println!(
+            "{}",
+            "Values marked with an environment variable or --set are overridden for this run only and not saved"
+                .dimmed()
+        );
+    }
 
     Ok(())
 }
 
+fn format_layer(layer: &ConfigLayer) -> ColoredString {
+    color_by_layer(layer, layer.to_string())
+}
+
+fn color_by_layer(layer: &ConfigLayer, text: String) -> ColoredString {
+    match layer {
+        ConfigLayer::Default => text.bright_black(),
+        ConfigLayer::Global(_) => text.blue(),
+        ConfigLayer::Profile(_) => text.cyan(),
+        ConfigLayer::Workspace(_) => text.magenta(),
+        ConfigLayer::Environment(_) | ConfigLayer::CommandLine(_) => text.yellow().bold(),
+    }
+}
+
 /// Recursively display JSON values with smart formatting and colors
-fn display_value(value: &Value, indent: usize, key: &str) {
+///
+/// Values listed in `origins` are followed by the layer they come from.
+fn display_value(
+    value: &Value,
+    indent: usize,
+    key: &str,
+    path: &str,
+    origins: Option<&BTreeMap<String, ConfigLayer>>,
+) {
     let indent_str = "  ".repeat(indent);
+    let origin = origins
+        .and_then(|origins| origins.get(path))
+        .map(|layer| format!(" {}", color_by_layer(layer, format!("({})", layer.label()))))
+        .unwrap_or_default();
+    let child_path = |child: &str| {
+        if path.is_empty() {
+            child.to_string()
+        } else {
+            format!("{}.{}", path, child)
+        }
+    };
 
     match value {
         Value::Object(map) => {
             if !key.is_empty() {
-                println!("{}{}", indent_str, key.bright_cyan().bold());
+                if map.is_empty() {
+                    println!(
+                        "{}{}: {}{}",
+                        indent_str,
+                        key.bright_cyan().bold(),
+                        "{}".bright_black(),
+                        origin
+                    );
+                } else {
+                    println!("{}{}", indent_str, key.bright_cyan().bold());
+                }
             }
             for (k, v) in map {
-                display_value(v, indent + 1, k);
+                display_value(v, indent + 1, k, &child_path(k), origins);
             }
         }
         Value::Array(arr) => {
             if arr.is_empty() {
-                println!("{}{}: {}", indent_str, key.green(), "[]".bright_black());
+                println!(
+                    "{}{}: {}{}",
+                    indent_str,
+                    key.green(),
+                    "[]".bright_black(),
+                    origin
+                );
             } else {
-                println!("{}{}", indent_str, key.green().bold());
+                println!("{}{}{}", indent_str, key.green().bold(), origin);
                 for (i, item) in arr.iter().enumerate() {
                     if let Value::Object(_) = item {
                         println!("{}  {}:", indent_str, format!("[{i}]").yellow());
-                        display_value(item, indent + 1, "");
+                        display_value(item, indent + 1, "", "", None);
                     } else {
                         print!("{indent_str}  - ");
-                        display_value(item, 0, "");
+                        display_value(item, 0, "", "", None);
                     }
                 }
             }
         }
         Value::String(s) => {
-            println!("{}{}: {}", indent_str, key.green(), s.yellow());
+            println!("{}{}: {}{}", indent_str, key.green(), s.yellow(), origin);
         }
         Value::Number(n) => {
-            println!("{}{}: {}", indent_str, key.green(), n.to_string().cyan());
+            println!(
+                "{}{}: {}{}",
+                indent_str,
+                key.green(),
+                n.to_string().cyan(),
+                origin
+            );
         }
         Value::Bool(b) => {
             let colored_bool = if *b {
@@ -61,10 +149,16 @@ fn display_value(value: &Value, indent: usize, key: &str) {
             } else {
                 "false".bright_red()
             };
-            println!("{}{}: {}", indent_str, key.green(), colored_bool);
+            println!("{}{}: {}{}", indent_str, key.green(), colored_bool, origin);
         }
         Value::Null => {
-            println!("{}{}: {}", indent_str, key.green(), "null".bright_black());
+            println!(
+                "{}{}: {}{}",
+                indent_str,
+                key.green(),
+                "null".bright_black(),
+                origin
+            );
         }
     }
 }
@@ -82,15 +176,17 @@ pub fn get_config_value(key: &str) -> Result<()> {
 }
 
 pub fn set_config_value(key: &str, value: &str) -> Result<()> {
-    let mut config = get_config()?;
-    let mut json_value = serde_json::to_value(&config)?;
+    Config::update(|config| {
+        let mut json_value = serde_json::to_value(&*config)?;
 
-    // Update the value using dot notation
-    update_json_path(&mut json_value, key, value)?;
+        // Update the value using dot notation
+        update_json_path(&mut json_value, key, value)?;
 
-    // Deserialize back to Config and save
-    config = serde_json::from_value(json_value)?;
-    config.save()?;
+        // Deserialize back to Config
+        *config = serde_json::from_value(json_value)?;
+        Ok(())
+    })?;
+    let (config, layers) = Config::load_layers()?;
 
     println!(
         "{} Configuration updated: {} = {}",
@@ -98,6 +194,22 @@ pub fn set_config_value(key: &str, value: &str) -> Result<()> {
         key.cyan(),
         value.yellow()
     );
+
+    match layers.origins(&config)?.get(key) {
+        Some(ConfigLayer::Workspace(path)) => OutputManager::print_status(
+            Status::Warning,
+            &format!(
+                "{} is also set in {}, which takes precedence in this directory",
+                key,
+                path.display()
+            ),
+        ),
+        Some(layer) if layer.is_override() => OutputManager::print_status(
+            Status::Warning,
+            &format!("{} is overridden by {} for this run", key, layer.label()),
+        ),
+        _ => {}
+    }
     Ok(())
 }
 
@@ -153,15 +265,27 @@ pub fn edit_config() -> Result<()> {
 }
 
 pub fn list_config_keys() -> Result<()> {
-    let config = get_config()?;
-    let json_value = serde_json::to_value(&config)?;
-
     println!("{} Available Configuration Keys:", "🔑".blue());
-    println!();
 
-    let keys = collect_json_keys(&json_value, "");
-    for key in keys {
-        println!("  {}", key.green());
+    let mut section = "";
+    for config_key in schema::CONFIG_KEYS {
+        let key_section = config_key.key.split('.').next().unwrap_or_default();
+        if key_section != section && config_key.key.contains('.') {
+            println!();
+        }
+        section = key_section;
+
+        let value_type = if config_key.optional {
+            format!("{}, optional", config_key.value_type.describe())
+        } else {
+            config_key.value_type.describe()
+        };
+        println!(
+            "  {} {}",
+            config_key.display_key().green(),
+            format!("({})", value_type).bright_black()
+        );
+        println!("      {}", config_key.description);
     }
 
     println!();
@@ -171,6 +295,10 @@ pub fn list_config_keys() -> Result<()> {
         "  Set value: {}",
         "noter config set <key> <value>".bright_white()
     );
+    println!(
+        "  Unset an optional value: {}",
+        "noter config set <key> null".bright_white()
+    );
     println!();
     println!("Example:");
     println!("  {}", "noter config get author".bright_white());
@@ -182,7 +310,31 @@ pub fn list_config_keys() -> Result<()> {
         "  {}",
         "noter config set templates.auto_update true".bright_white()
     );
+    println!(
+        "  {}",
+        "noter config set search.file_extensions typ,md".bright_white()
+    );
+
+    Ok(())
+}
+
+pub fn export_config_schema(output: Option<&str>) -> Result<()> {
+    let schema = serde_json::to_string_pretty(&schema::json_schema())?;
 
+    match output {
+        Some(path) => {
+            std::fs::write(path, format!("{}\n", schema))?;
+            OutputManager::print_status(
+                Status::Success,
+                &format!("Config schema written to {}", path),
+            );
+            println!(
+                "Add {} to config.json for completion in your editor",
+                format!("\"$schema\": \"{}\"", path).bright_white()
+            );
+        }
+        None => println!("{}", schema),
+    }
     Ok(())
 }
 
@@ -195,7 +347,8 @@ pub fn interactive_config() -> Result<()> {
     println!("Press Enter to keep the current value, or type a new value.");
     println!();
 
-    let mut config = get_config()?;
+    let config = get_config()?;
+    let mut answers = config.clone();
 
     // Author
     print!("Author name [{}]: ", config.author.green());
@@ -203,7 +356,7 @@ pub fn interactive_config() -> Result<()> {
     let mut input = String::new();
     io::stdin().read_line(&mut input)?;
     if !input.trim().is_empty() {
-        config.author = input.trim().to_string();
+        answers.author = input.trim().to_string();
     }
 
     // Preferred Editor
@@ -219,7 +372,7 @@ pub fn interactive_config() -> Result<()> {
     input.clear();
     io::stdin().read_line(&mut input)?;
     if !input.trim().is_empty() {
-        config.preferred_editor = Some(input.trim().to_string());
+        answers.preferred_editor = Some(input.trim().to_string());
     }
 
     // Notes directory
@@ -228,7 +381,7 @@ pub fn interactive_config() -> Result<()> {
     input.clear();
     io::stdin().read_line(&mut input)?;
     if !input.trim().is_empty() {
-        config.paths.notes_dir = input.trim().to_string();
+        answers.paths.notes_dir = input.trim().to_string();
     }
 
     // Auto open file
@@ -245,7 +398,7 @@ pub fn interactive_config() -> Result<()> {
     io::stdin().read_line(&mut input)?;
     let trimmed = input.trim().to_lowercase();
     if !trimmed.is_empty() {
-        config.note_preferences.auto_open_file = matches!(trimmed.as_str(), "y" | "yes" | "true");
+        answers.note_preferences.auto_open_file = matches!(trimmed.as_str(), "y" | "yes" | "true");
     }
 
     // Template auto-update
@@ -262,7 +415,7 @@ pub fn interactive_config() -> Result<()> {
     io::stdin().read_line(&mut input)?;
     let trimmed = input.trim().to_lowercase();
     if !trimmed.is_empty() {
-        config.templates.auto_update = matches!(trimmed.as_str(), "y" | "yes" | "true");
+        answers.templates.auto_update = matches!(trimmed.as_str(), "y" | "yes" | "true");
     }
 
     // Obsidian integration
@@ -279,11 +432,32 @@ pub fn interactive_config() -> Result<()> {
     io::stdin().read_line(&mut input)?;
     let trimmed = input.trim().to_lowercase();
     if !trimmed.is_empty() {
-        config.obsidian_integration.enabled = matches!(trimmed.as_str(), "y" | "yes" | "true");
+        answers.obsidian_integration.enabled = matches!(trimmed.as_str(), "y" | "yes" | "true");
     }
 
-    // Save configuration
-    config.save()?;
+    // Save the changed answers; the config isn't locked while waiting for
+    // input, so unchanged ones must not overwrite changes made meanwhile
+    Config::update(|current| {
+        if answers.author != config.author {
+            current.author = answers.author;
+        }
+        if answers.preferred_editor != config.preferred_editor {
+            current.preferred_editor = answers.preferred_editor;
+        }
+        if answers.paths.notes_dir != config.paths.notes_dir {
+            current.paths.notes_dir = answers.paths.notes_dir;
+        }
+        if answers.note_preferences.auto_open_file != config.note_preferences.auto_open_file {
+            current.note_preferences.auto_open_file = answers.note_preferences.auto_open_file;
+        }
+        if answers.templates.auto_update != config.templates.auto_update {
+            current.templates.auto_update = answers.templates.auto_update;
+        }
+        if answers.obsidian_integration.enabled != config.obsidian_integration.enabled {
+            current.obsidian_integration.enabled = answers.obsidian_integration.enabled;
+        }
+        Ok(())
+    })?;
 
     println!();
     println!("{} Configuration saved successfully!", "✅".green());
@@ -313,130 +487,6 @@ pub fn interactive_config() -> Result<()> {
     Ok(())
 }
 
-/// Navigate JSON value using dot notation path
-fn navigate_json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
-    let parts: Vec<&str> = path.split('.').collect();
-    let mut current = value;
-
-    for part in parts {
-        match current {
-            Value::Object(map) => {
-                current = map.get(part)?;
-            }
-            _ => return None,
-        }
-    }
-
-    Some(current)
-}
-
-/// Update JSON value using dot notation path
-fn update_json_path(value: &mut Value, path: &str, new_value: &str) -> Result<()> {
-    let parts: Vec<&str> = path.split('.').collect();
-
-    if parts.is_empty() {
-        return Err(anyhow::anyhow!("Invalid key path"));
-    }
-
-    let mut current = value;
-
-    // Navigate to the parent of the target
-    for part in &parts[..parts.len() - 1] {
-        match current {
-            Value::Object(map) => {
-                current = map
-                    .get_mut(*part)
-                    .ok_or_else(|| anyhow::anyhow!("Key '{}' not found", part))?;
-            }
-            _ => {
-                return Err(anyhow::anyhow!(
-                    "Cannot navigate through non-object at '{}'",
-                    part
-                ));
-            }
-        }
-    }
-
-    // Update the target value
-    let last_key = parts[parts.len() - 1];
-    match current {
-        Value::Object(map) => {
-            let old_value = map
-                .get(last_key)
-                .ok_or_else(|| anyhow::anyhow!("Key '{}' not found", last_key))?;
-
-            // Parse new_value based on the type of old_value
-            let parsed_value = match old_value {
-                Value::String(_) => Value::String(new_value.to_string()),
-                Value::Number(_) => {
-                    if let Ok(n) = new_value.parse::<i64>() {
-                        serde_json::json!(n)
-                    } else if let Ok(n) = new_value.parse::<f64>() {
-                        serde_json::json!(n)
-                    } else {
-                        return Err(anyhow::anyhow!("Invalid number format: {}", new_value));
-                    }
-                }
-                Value::Bool(_) => {
-                    let b = match new_value.to_lowercase().as_str() {
-                        "true" | "1" | "yes" | "y" => true,
-                        "false" | "0" | "no" | "n" => false,
-                        _ => return Err(anyhow::anyhow!("Invalid boolean format: {}", new_value)),
-                    };
-                    Value::Bool(b)
-                }
-                Value::Array(_) => {
-                    // Try to parse as JSON array
-                    serde_json::from_str(new_value)
-                        .map_err(|_| anyhow::anyhow!("Invalid array format: {}", new_value))?
-                }
-                Value::Object(_) => {
-                    // Try to parse as JSON object
-                    serde_json::from_str(new_value)
-                        .map_err(|_| anyhow::anyhow!("Invalid object format: {}", new_value))?
-                }
-                Value::Null => Value::String(new_value.to_string()),
-            };
-
-            map.insert(last_key.to_string(), parsed_value);
-            Ok(())
-        }
-        _ => Err(anyhow::anyhow!(
-            "Cannot set value on non-object at '{}'",
-            last_key
-        )),
-    }
-}
-
-/// Collect all JSON keys recursively
-fn collect_json_keys(value: &Value, prefix: &str) -> Vec<String> {
-    let mut keys = Vec::new();
-
-    if let Value::Object(map) = value {
-        for (key, val) in map {
-            let full_key = if prefix.is_empty() {
-                key.clone()
-            } else {
-                format!("{}.{}", prefix, key)
-            };
-
-            match val {
-                Value::Object(_) => {
-                    keys.extend(collect_json_keys(val, &full_key));
-                }
-                Value::Array(_) => {
-                    keys.extend(collect_json_keys(val, &full_key));
-                }
-                _ => {
-                    keys.push(full_key);
-                }
-            }
-        }
-    }
-
-    keys
-}
-
 /// Format a JSON value for inline display
 fn format_value_inline(value: &Value) -> String {
     match value {
@@ -485,78 +535,67 @@ pub fn set_editor(editor: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn add_template_repository(
-    name: &str,
-    repository: &str,
-    version: Option<&str>,
-    template_path: Option<&str>,
-) -> Result<()> {
-    let mut config = get_config()?;
-
-    // Check if repository already exists
-    if config
-        .templates
-        .custom_repositories
-        .iter()
-        .any(|r| r.name == name)
-    {
-        return Err(anyhow::anyhow!(
-            "Template repository '{}' already exists",
-            name
-        ));
-    }
-
-    let template_repo = TemplateRepository {
-        name: name.to_string(),
-        repository: repository.to_string(),
-        version: version.map(|v| v.to_string()),
-        branch: None,
-        template_path: template_path.map(|p| p.to_string()),
-        enabled: true,
-    };
+pub fn add_template_repository(template_repo: TemplateRepository) -> Result<()> {
+    Config::update(|config| {
+        // Check if repository already exists
+        if config
+            .templates
+            .custom_repositories
+            .iter()
+            .any(|r| r.name == template_repo.name)
+        {
+            return Err(anyhow::anyhow!(
+                "Template repository '{}' already exists",
+                template_repo.name
+            ));
+        }
 
-    config.templates.custom_repositories.push(template_repo);
-    config.save()?;
+        config
+            .templates
+            .custom_repositories
+            .push(template_repo.clone());
+        Ok(())
+    })?;
 
     println!(
-        "{} Added template repository: {} ({})",
+        "{} Added template repository: {} ({}, {})",
         "✅".green(),
-        name.green(),
-        repository.yellow()
+        template_repo.name.green(),
+        template_repo.repository.yellow(),
+        template_repo.provider
     );
     Ok(())
 }
 
 pub fn remove_template_repository(name: &str) -> Result<()> {
-    let mut config = get_config()?;
-
-    let initial_len = config.templates.custom_repositories.len();
-    config
-        .templates
-        .custom_repositories
-        .retain(|r| r.name != name);
-
-    if config.templates.custom_repositories.len() == initial_len {
-        return Err(anyhow::anyhow!("Template repository '{}' not found", name));
-    }
+    Config::update(|config| {
+        let initial_len = config.templates.custom_repositories.len();
+        config
+            .templates
+            .custom_repositories
+            .retain(|r| r.name != name);
 
-    config.save()?;
+        if config.templates.custom_repositories.len() == initial_len {
+            return Err(anyhow::anyhow!("Template repository '{}' not found", name));
+        }
+        Ok(())
+    })?;
     println!("{} Removed template repository: {}", "🗑️".red(), name);
     Ok(())
 }
 
 pub fn enable_template_repository(name: &str, enabled: bool) -> Result<()> {
-    let mut config = get_config()?;
-
-    let repo = config
-        .templates
-        .custom_repositories
-        .iter_mut()
-        .find(|r| r.name == name)
-        .ok_or_else(|| anyhow::anyhow!("Template repository '{}' not found", name))?;
-
-    repo.enabled = enabled;
-    config.save()?;
+    Config::update(|config| {
+        let repo = config
+            .templates
+            .custom_repositories
+            .iter_mut()
+            .find(|r| r.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Template repository '{}' not found", name))?;
+
+        repo.enabled = enabled;
+        Ok(())
+    })?;
 
     let status = if enabled { "enabled" } else { "disabled" };
     let emoji = if enabled { "✅" } else { "❌" };
@@ -564,6 +603,31 @@ pub fn enable_template_repository(name: &str, enabled: bool) -> Result<()> {
     Ok(())
 }
 
+pub fn allow_template_hooks(name: &str, allowed: bool) -> Result<()> {
+    Config::update(|config| {
+        let repo = config
+            .templates
+            .custom_repositories
+            .iter_mut()
+            .find(|r| r.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Template repository '{}' not found", name))?;
+
+        repo.allow_hooks = allowed;
+        Ok(())
+    })?;
+
+    if allowed {
+        println!(
+            "{} Processing hooks from '{}' are now allowed to run commands",
+            "⚠️".yellow(),
+            name
+        );
+    } else {
+        println!("{} Processing hooks from '{}' disabled", "🔒".blue(), name);
+    }
+    Ok(())
+}
+
 pub fn list_template_repositories() -> Result<()> {
     let config = get_config()?;
 
@@ -583,12 +647,30 @@ pub fn list_template_repositories() -> Result<()> {
                 repo.name.green(),
                 repo.repository.yellow()
             );
+            if repo.provider != TemplateProvider::default() || repo.base_url.is_some() {
+                println!(
+                    "    Provider: {}{}",
+                    repo.provider,
+                    repo.base_url
+                        .as_deref()
+                        .map(|url| format!(" ({})", url))
+                        .unwrap_or_default()
+                );
+            }
             if let Some(version) = &repo.version {
                 println!("    Version: {}", version);
             }
+            if let Some(rev) = &repo.rev {
+                println!("    Commit: {}", rev);
+            } else if let Some(branch) = &repo.branch {
+                println!("    Branch: {}", branch);
+            }
             if let Some(path) = &repo.template_path {
                 println!("    Template Path: {}", path);
             }
+            if repo.allow_hooks {
+                println!("    Hooks: {}", "allowed".yellow());
+            }
         }
     }
 
@@ -599,19 +681,39 @@ pub fn list_template_repositories() -> Result<()> {
     Ok(())
 }
 
-pub fn set_template_auto_update(enabled: bool) -> Result<()> {
-    let mut config = get_config()?;
-    config.templates.auto_update = enabled;
-    config.save()?;
+pub fn set_template_auto_update(
+    enabled: bool,
+    policy: Option<AutoUpdatePolicy>,
+    interval_hours: Option<u64>,
+) -> Result<()> {
+    let config = Config::update(|config| {
+        config.templates.auto_update = enabled;
+        if let Some(policy) = policy {
+            config.templates.auto_update_policy = policy;
+        }
+        if let Some(interval_hours) = interval_hours {
+            config.templates.update_check_interval_hours = interval_hours;
+        }
+        Ok(config.clone())
+    })?;
 
     let status = if enabled { "enabled" } else { "disabled" };
     println!("{} Template auto-update {}", "🔄".blue(), status);
+    if enabled {
+        println!(
+            "  Policy: {}, checking at most every {} hour(s)",
+            config.templates.auto_update_policy.to_string().cyan(),
+            config.templates.update_check_interval_hours
+        );
+    }
     Ok(())
 }
 
 pub fn reset_config() -> Result<()> {
-    let default_config = Config::default();
-    default_config.save()?;
+    Config::update(|config| {
+        *config = Config::default();
+        Ok(())
+    })?;
     println!("{} Configuration reset to defaults", "🔄".blue());
     Ok(())
 }
@@ -620,6 +722,12 @@ pub fn show_config_path() -> Result<()> {
     let path = Config::config_file_path()?;
     println!("{} Config file location:", "📁".blue());
     println!("{}", path.display());
+
+    if let Some(workspace_path) = find_workspace_config(&std::env::current_dir()?) {
+        println!();
+        println!("{} Workspace config (takes precedence):", "📁".blue());
+        println!("{}", workspace_path.display());
+    }
     Ok(())
 }
 
@@ -639,7 +747,193 @@ pub fn check_config() -> Result<()> {
     Ok(())
 }
 
-pub fn migrate_config() -> Result<()> {
+pub fn create_profile(name: &str, from: Option<&str>) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow::anyhow!(
+            "Invalid profile name '{}': use letters, digits, '-' and '_'",
+            name
+        ));
+    }
+
+    Config::update(|config| {
+        if config.profiles.contains_key(name) {
+            return Err(anyhow::anyhow!("Profile '{}' already exists", name));
+        }
+
+        let profile = match from {
+            Some(from) => find_profile(config, from)?.clone(),
+            None => Profile::default(),
+        };
+        config.profiles.insert(name.to_string(), profile);
+        Ok(())
+    })?;
+
+    OutputManager::print_status(Status::Success, &format!("Created profile '{}'", name));
+    println!();
+    println!("Next steps:");
+    println!(
+        "  Set its values: {}",
+        format!("noter --profile {} config set paths.notes_dir <dir>", name).bright_white()
+    );
+    println!(
+        "  Add courses:    {}",
+        format!("noter --profile {} courses add <code> <name>", name).bright_white()
+    );
+    println!(
+        "  Use by default: {}",
+        format!("noter config profile switch {}", name).bright_white()
+    );
+    Ok(())
+}
+
+pub fn switch_profile(name: Option<&str>) -> Result<()> {
+    Config::update(|config| {
+        if let Some(name) = name {
+            find_profile(config, name)?;
+        }
+        config.metadata.active_profile = name.map(str::to_string);
+        Ok(())
+    })?;
+
+    match name {
+        Some(name) => {
+            OutputManager::print_status(Status::Success, &format!("Switched to profile '{}'", name))
+        }
+        None => OutputManager::print_status(Status::Success, "Switched to the base config"),
+    }
+    Ok(())
+}
+
+pub fn list_profiles() -> Result<()> {
+    let (config, layers) = Config::load_layers()?;
+
+    if config.profiles.is_empty() {
+        println!("{} No profiles defined", "ℹ️".blue());
+        println!(
+            "Create one with: {}",
+            "noter config profile create <name>".bright_white()
+        );
+        return Ok(());
+    }
+
+    println!("{} Profiles:", "👤".blue());
+    let mut names: Vec<_> = config.profiles.keys().collect();
+    names.sort();
+    for name in names {
+        let summary = config.profiles[name].summary();
+        if layers.profile() == Some(name.as_str()) {
+            println!(
+                "  {} {} {}",
+                "*".green(),
+                name.green().bold(),
+                format!("({})", summary).bright_black()
+            );
+        } else {
+            println!("    {} {}", name, format!("({})", summary).bright_black());
+        }
+    }
+
+    if layers.profile().is_none() {
+        println!();
+        println!("No profile is active; the base config is used");
+    }
+    Ok(())
+}
+
+pub fn diff_profiles(profile: &str, other: Option<&str>) -> Result<()> {
+    let (_, layers) = Config::load_layers()?;
+    let global = layers
+        .global()
+        .ok_or_else(|| anyhow::anyhow!("No config file found"))?;
+
+    let base = profile_config(global, other)?;
+    let changed = profile_config(global, Some(profile))?;
+
+    let base_name = match other {
+        Some(other) => format!("profile '{}'", other),
+        None => "the base config".to_string(),
+    };
+    println!(
+        "{} Profile '{}' compared with {}:",
+        "🔍".blue(),
+        profile,
+        base_name
+    );
+
+    if json_path::changes(&base, &changed).is_empty() {
+        println!("  No differences");
+    } else {
+        display_changes(&base, &changed);
+    }
+    Ok(())
+}
+
+pub fn delete_profile(name: &str) -> Result<()> {
+    Config::update(|config| {
+        find_profile(config, name)?;
+        config.profiles.remove(name);
+        if config.metadata.active_profile.as_deref() == Some(name) {
+            config.metadata.active_profile = None;
+        }
+        Ok(())
+    })?;
+
+    println!("{} Removed profile: {}", "🗑️".red(), name);
+    Ok(())
+}
+
+fn find_profile<'a>(config: &'a Config, name: &str) -> Result<&'a Profile> {
+    config.profiles.get(name).ok_or_else(|| {
+        let mut names: Vec<_> = config.profiles.keys().cloned().collect();
+        names.sort();
+        if names.is_empty() {
+            anyhow::anyhow!("Profile '{}' not found. No profiles are defined", name)
+        } else {
+            anyhow::anyhow!(
+                "Profile '{}' not found. Available profiles: {}",
+                name,
+                names.join(", ")
+            )
+        }
+    })
+}
+
+/// The sections a profile can override, as they are with a profile applied
+/// to the global config file (`None`: the base config)
+fn profile_config(global: &Value, profile: Option<&str>) -> Result<Value> {
+    let mut merged = global.clone();
+    if let Some(profile) = profile {
+        let value = navigate_json_path(global, &format!("profiles.{}", profile))
+            .ok_or_else(|| anyhow::anyhow!("Profile '{}' not found", profile))?;
+        layers::apply_profile(&mut merged, value);
+    }
+
+    let config = serde_json::to_value(serde_json::from_value::<Config>(merged)?)?;
+    Ok(Value::Object(
+        PROFILE_SECTIONS
+            .iter()
+            .filter_map(|section| Some((section.to_string(), config.get(*section)?.clone())))
+            .collect(),
+    ))
+}
+
+/// Print the values that differ between two configs, by dot path
+fn display_changes(before: &Value, after: &Value) {
+    for (key, old, new) in json_path::changes(before, after) {
+        if let Some(old) = old {
+            println!("  {}", format!("- {}: {}", key, old).red());
+        }
+        if let Some(new) = new {
+            println!("  {}", format!("+ {}: {}", key, new).green());
+        }
+    }
+}
+
+pub fn migrate_config(dry_run: bool) -> Result<()> {
     println!("{} Checking config migration status...", "🔄".blue());
 
     let config_path = Config::config_file_path()?;
@@ -652,17 +946,139 @@ pub fn migrate_config() -> Result<()> {
         return Ok(());
     }
 
-    // Load config - this will automatically trigger migration if needed
-    let config = Config::load()?;
+    // Keep other noter processes from migrating or changing the file meanwhile
+    let _lock = ConfigLock::acquire()?;
+    let value = layers::read_config_file(&config_path)?;
+    if !migrations::needs_migration(&value) {
+        let config = Config::load()?;
+        println!("{} Config is up to date!", "✅".green());
+        println!("  Version: {}", migrations::file_version(&value));
+
+        if !config.metadata.migration_notes.is_empty() {
+            println!("\n{} Migration notes:", "📝".blue());
+            println!("  {}", config.metadata.migration_notes);
+        }
+        return Ok(());
+    }
+
+    let outcome = migrations::migrate(value.clone(), &chrono::Utc::now().to_rfc3339())?;
+
+    println!();
+    println!("Migration steps:");
+    for step in &outcome.steps {
+        println!(
+            "  {} -> {}: {}",
+            step.from.red(),
+            step.to.green(),
+            step.description
+        );
+    }
 
-    println!("{} Config is up to date!", "✅".green());
-    println!("  Version: {}", config.metadata.config_version);
+    println!();
+    println!("Changes to {}:", config_path.display());
+    display_changes(&value, &outcome.value);
+    println!();
 
-    if !config.metadata.migration_notes.is_empty() {
-        println!("\n{} Migration notes:", "📝".blue());
-        println!("  {}", config.metadata.migration_notes);
+    if dry_run {
+        OutputManager::print_status(
+            Status::Info,
+            "Dry run: nothing was written. Run without --dry-run to migrate",
+        );
+        return Ok(());
     }
 
+    let (_, backup_path) = Config::write_migration(&config_path, value)?;
+    OutputManager::print_status(
+        Status::Success,
+        &format!("Config migrated to version {}", CONFIG_VERSION),
+    );
+    println!("  Backup: {}", backup_path.display());
+
+    Ok(())
+}
+
+pub fn export_config(output: Option<&str>, redact_paths: bool) -> Result<()> {
+    let config = get_config()?;
+    let bundle = bundle::export_bundle(&serde_json::to_value(&config)?, redact_paths);
+
+    let Some(path) = output else {
+        println!("{}", serde_json::to_string_pretty(&bundle)?);
+        return Ok(());
+    };
+
+    let path = std::path::Path::new(path);
+    std::fs::write(path, layers::format_config(path, &bundle)? + "\n")?;
+    OutputManager::print_status(
+        Status::Success,
+        &format!("Config exported to {}", path.display()),
+    );
+    println!(
+        "  {} course(s), {} template repository(s), {} group(s)",
+        config.courses.len(),
+        config.templates.custom_repositories.len(),
+        config.groups.len()
+    );
+    if !redact_paths {
+        println!(
+            "  Paths are included; use {} to leave them out",
+            "--redact-paths".bright_white()
+        );
+    }
+    println!(
+        "Import it with: {}",
+        format!("noter config import {}", path.display()).bright_white()
+    );
+    Ok(())
+}
+
+pub fn import_config(file: &str, mode: ImportMode, skip_confirmation: bool) -> Result<()> {
+    let path = std::path::Path::new(file);
+    let imported = layers::read_config_file(path)?;
+
+    let current = serde_json::to_value(get_config()?)?;
+    let updated = bundle::import_bundle(&current, imported.clone(), mode)?;
+
+    if json_path::changes(&current, &updated).is_empty() {
+        println!(
+            "{} Nothing to import: your config already has these settings",
+            "✅".green()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} Changes from {} ({}):",
+        "📥".blue(),
+        path.display(),
+        mode
+    );
+    display_changes(&current, &updated);
+    println!();
+
+    if !skip_confirmation {
+        use std::io::{self, Write};
+        print!("Apply these changes? [y/N]: ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    // Apply to the config as it is now, in case it changed while asking
+    Config::update(|config| {
+        let value = bundle::import_bundle(&serde_json::to_value(&*config)?, imported, mode)?;
+        *config = serde_json::from_value(value)?;
+        Ok(())
+    })?;
+
+    OutputManager::print_status(
+        Status::Success,
+        &format!("Imported config from {}", path.display()),
+    );
     Ok(())
 }
 
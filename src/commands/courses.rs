@@ -2,8 +2,12 @@
 //!
 //! Thin command layer that delegates to core business logic.
 
-use crate::config::get_config;
+use crate::config::{Config, get_config};
 use crate::core::courses::{CourseManager, get_common_courses};
+use crate::core::template::config::TemplateConfig;
+use crate::core::template::context::TemplateContext;
+use crate::core::template::discovery::TemplateDiscovery;
+use crate::core::template::engine::TemplateEngine;
 use crate::core::validation::Validator;
 use crate::display::formatters::Formatters;
 use crate::display::output::{OutputManager, Status};
@@ -32,10 +36,10 @@ pub fn list_courses() -> Result<()> {
 pub fn add_course(course_id: &str, course_name: &str) -> Result<()> {
     Validator::validate_course_id(course_id)?;
 
-    let mut config = get_config()?;
-    let mut manager = CourseManager::new(&mut config);
+    let result =
+        Config::update(|config| Ok(CourseManager::new(config).add_course(course_id, course_name)))?;
 
-    match manager.add_course(course_id, course_name) {
+    match result {
         Ok(()) => {
             OutputManager::print_status(
                 Status::Success,
@@ -62,10 +66,9 @@ pub fn add_course(course_id: &str, course_name: &str) -> Result<()> {
 pub fn remove_course(course_id: &str) -> Result<()> {
     Validator::validate_course_id(course_id)?;
 
-    let mut config = get_config()?;
-    let mut manager = CourseManager::new(&mut config);
+    let result = Config::update(|config| Ok(CourseManager::new(config).remove_course(course_id)))?;
 
-    match manager.remove_course(course_id) {
+    match result {
         Ok(course_name) => {
             OutputManager::print_status(
                 Status::Success,
@@ -94,6 +97,258 @@ pub fn remove_course(course_id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Show the template settings a course uses, after applying its overrides
+pub fn show_course(course_id: &str) -> Result<()> {
+    Validator::validate_course_id(course_id)?;
+    let mut config = get_config()?;
+    let overrides = config.course_overrides(course_id);
+
+    let course_name = config.get_course_name(course_id);
+    OutputManager::print_section(
+        &format!(
+            "{} - {}",
+            course_id,
+            if course_name.is_empty() {
+                "not in your course list"
+            } else {
+                &course_name
+            }
+        ),
+        Some("📚"),
+    );
+
+    // The course settings are shown even if no template package can be loaded
+    let note_package = match TemplateDiscovery::resolve_template_package(&config, "note", None) {
+        Ok(package) if package.templates.is_empty() => {
+            println!(
+                "  {}",
+                "No template package installed, showing the course settings only. Install one with 'noter template update'"
+                    .dimmed()
+            );
+            package
+        }
+        Ok(package) => package,
+        Err(e) => {
+            println!(
+                "  {} {}",
+                "⚠️".yellow(),
+                format!("Template packages could not be loaded: {:#}", e).red()
+            );
+            TemplateConfig::default()
+        }
+    };
+    let context = TemplateContext::builder()
+        .with_course_id(course_id)
+        .with_config(config.clone())
+        .with_template_config(note_package.clone())
+        .with_template_name("note")
+        .with_title("")
+        .build()?;
+
+    // Documents other than lecture notes also list the course's group
+    let mut group_context = context.clone();
+    match group_context.set_authors(&config, overrides.group.as_deref(), &overrides.co_authors) {
+        Ok(()) => {
+            let authors: Vec<String> = group_context
+                .authors
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            println!(
+                "  {}: {}",
+                if authors.len() > 1 {
+                    "Authors"
+                } else {
+                    "Author"
+                },
+                authors.join(", ").bright_white()
+            );
+        }
+        Err(e) => {
+            println!("  Authors: {} {}", "⚠️".yellow(), format!("{:#}", e).red());
+            // Show the template settings without the broken group
+            if let Some(course_overrides) = config.course_overrides.get_mut(course_id) {
+                course_overrides.group = None;
+            }
+        }
+    }
+    if let Some(group) = &overrides.group {
+        println!(
+            "  Group: {} {}",
+            group.yellow(),
+            "(course setting, not used for lecture notes)".dimmed()
+        );
+    }
+    match &overrides.title_format {
+        Some(format) => {
+            let mut titled = context.clone();
+            match titled.apply_title_format(&config) {
+                Ok(()) => println!(
+                    "  Title: {} {}",
+                    titled.title.bright_white(),
+                    format!("(format \"{}\")", format).dimmed()
+                ),
+                Err(e) => println!("  Title: {} {}", "⚠️".yellow(), format!("{:#}", e).red()),
+            }
+        }
+        None => println!("  Title: {}", "default".dimmed()),
+    }
+    println!(
+        "  Course Type: {}",
+        TemplateDiscovery::resolve_course_type(
+            std::slice::from_ref(&note_package),
+            course_id,
+            "general"
+        )
+        .green()
+    );
+
+    let mut template_names = vec!["note".to_string(), "assignment".to_string()];
+    for name in overrides.variants.keys().chain(overrides.sections.keys()) {
+        if !template_names.contains(name) {
+            template_names.push(name.clone());
+        }
+    }
+
+    for template_name in &template_names {
+        display_course_template(&config, course_id, template_name)?;
+    }
+
+    println!();
+    if overrides.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "No course settings. Add them under \"course_overrides\" → \"{}\" with 'noter config edit'",
+                course_id
+            )
+            .dimmed()
+        );
+    } else {
+        println!(
+            "{}",
+            format!(
+                "Course settings are under \"course_overrides\" → \"{}\" in the config file",
+                course_id
+            )
+            .dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Print the variant, sections and parameters a template uses for a course
+fn display_course_template(config: &Config, course_id: &str, template_name: &str) -> Result<()> {
+    let package = TemplateDiscovery::resolve_template_package(config, template_name, None)
+        .unwrap_or_default();
+    let overrides = config.course_overrides(course_id);
+    let setting = "(course setting)".dimmed();
+
+    let Some(definition) = package
+        .templates
+        .iter()
+        .find(|definition| definition.name == template_name)
+    else {
+        println!();
+        println!(
+            "  {} {}",
+            template_name.yellow(),
+            "(not defined by any installed package)".red()
+        );
+        if let Some(variant) = overrides.variants.get(template_name) {
+            println!("    Variant: {} {}", variant.yellow(), setting);
+        }
+        if let Some(sections) = overrides.sections.get(template_name) {
+            println!("    Sections: {} {}", sections.join(", "), setting);
+        }
+        for (name, value) in &overrides.parameters {
+            println!("    Parameter {}: {} {}", name.cyan(), value, setting);
+        }
+        return Ok(());
+    };
+
+    let context = TemplateContext::builder()
+        .with_course_id(course_id)
+        .with_config(config.clone())
+        .with_template_config(package.clone())
+        .with_template_name(template_name)
+        .with_title("")
+        .build()?;
+
+    println!();
+    println!(
+        "  {} {}",
+        definition.display_name.bright_white(),
+        format!(
+            "({}, {} {})",
+            template_name, package.metadata.name, package.metadata.version
+        )
+        .dimmed()
+    );
+
+    let variant = match &context.metadata.default_variant {
+        Some(name) => {
+            let variant = TemplateDiscovery::find_variants_for_template(
+                std::slice::from_ref(&package),
+                template_name,
+            )
+            .into_iter()
+            .find(|variant| variant.name == *name)
+            .cloned();
+            match &variant {
+                Some(_) => println!("    Variant: {} {}", name.yellow(), setting),
+                None => println!(
+                    "    Variant: {} {}",
+                    name.yellow(),
+                    "(course setting, not defined by the package)".red()
+                ),
+            }
+            variant
+        }
+        None => {
+            let (course_type, variant) =
+                TemplateDiscovery::auto_selected_variant(&package, template_name, course_id);
+            match &variant {
+                Some(variant) => println!(
+                    "    Variant: {} {}",
+                    variant.name.yellow(),
+                    format!("(auto-selected for {})", course_type).dimmed()
+                ),
+                None => println!("    Variant: {}", "base template".dimmed()),
+            }
+            variant
+        }
+    };
+
+    if context.sections.is_empty() {
+        let sections = TemplateEngine::get_template_sections(definition, variant.as_ref());
+        println!(
+            "    Sections: {} {}",
+            sections.join(", "),
+            "(template default)".dimmed()
+        );
+    } else {
+        println!("    Sections: {} {}", context.sections.join(", "), setting);
+    }
+
+    for parameter in &definition.parameters {
+        let value = match context.get_variable(&parameter.name) {
+            Some(value) if overrides.parameters.contains_key(&parameter.name) => {
+                format!("{} {}", value, setting)
+            }
+            Some(value) => value.clone(),
+            None => match &parameter.default {
+                Some(default) => format!("{} {}", default, "(template default)".dimmed()),
+                None => "not set".red().to_string(),
+            },
+        };
+        println!("    Parameter {}: {}", parameter.name.cyan(), value);
+    }
+
+    Ok(())
+}
+
 pub fn browse_common_courses() -> Result<()> {
     let config = get_config()?;
     let user_courses: std::collections::HashSet<String> = config.courses.keys().cloned().collect();
@@ -199,15 +199,26 @@ fn show_activity_summary_section(activity_summary: &crate::core::status::Activit
     println!();
     println!("📈 Recent Activity:");
 
-    if activity_summary.total_notes == 0 && activity_summary.total_assignments == 0 {
+    if activity_summary.total_notes == 0
+        && activity_summary.total_assignments == 0
+        && activity_summary.total_documents == 0
+    {
         println!("  No activity (run setup first)");
         return;
     }
 
     println!(
-        "  Total files: {} notes, {} assignments",
+        "  Total files: {} notes, {} assignments{}",
         activity_summary.total_notes.to_string().green(),
-        activity_summary.total_assignments.to_string().blue()
+        activity_summary.total_assignments.to_string().blue(),
+        if activity_summary.total_documents > 0 {
+            format!(
+                ", {} other documents",
+                activity_summary.total_documents.to_string().cyan()
+            )
+        } else {
+            String::new()
+        }
     );
 
     if let Some(ref recent) = activity_summary.most_recent_activity {
@@ -252,13 +263,20 @@ fn show_course_health_section(course_health: &[crate::core::status::CourseHealth
             _ => format!("{} days ago", health_info.days_since_last_activity).red(),
         };
 
+        let documents = if health_info.documents_count > 0 {
+            format!(", {} other", health_info.documents_count)
+        } else {
+            String::new()
+        };
+
         println!(
-            "  {} {} - {} ({} notes, {} assignments, last: {})",
+            "  {} {} - {} ({} notes, {} assignments{}, last: {})",
             health_indicator,
             health_info.course_id.yellow(),
             health_info.course_name.dimmed(),
             health_info.notes_count,
             health_info.assignments_count,
+            documents,
             last_activity
         );
     }
@@ -300,10 +318,11 @@ fn count_course_directories(notes_dir: &str) -> Result<usize> {
         for entry in entries.flatten() {
             if entry.path().is_dir() {
                 // Check if it looks like a course code (5 digits)
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.len() == 5 && name.chars().all(|c| c.is_ascii_digit()) {
-                        count += 1;
-                    }
+                if let Some(name) = entry.file_name().to_str()
+                    && name.len() == 5
+                    && name.chars().all(|c| c.is_ascii_digit())
+                {
+                    count += 1;
                 }
             }
         }
@@ -16,29 +16,91 @@ pub mod setup;
 pub mod templates;
 pub mod typst;
 
-use crate::{AssignmentAction, Commands, ConfigAction, CourseAction, SetupAction, TemplateAction};
+use crate::config::TemplateRepository;
+use crate::config::bundle::ImportMode;
+use crate::core::template::engine::TemplateReference;
+use crate::{
+    AssignmentAction, Commands, ConfigAction, CourseAction, ProfileAction, SetupAction,
+    TemplateAction, TemplateCacheAction,
+};
 
 #[cfg(feature = "dev-tools")]
 use crate::DevAction;
 
 /// Execute a command with proper error context
 pub fn execute_command(command: &Commands) -> Result<()> {
-    match command {
+    // Template and config commands manage updates themselves
+    let updates = if matches!(
+        command,
+        Commands::Template { .. } | Commands::Config { .. } | Commands::Setup { .. }
+    ) {
+        Vec::new()
+    } else {
+        templates::run_automatic_update_check()
+    };
+
+    let result = match command {
         Commands::Note {
             course_id,
             title,
             variant,
             sections,
             no_open,
-        } => notes::create_note(course_id, title, variant, sections, no_open)
-            .with_context(|| format!("Failed to create note for course {}", course_id)),
-        Commands::Assignment { course_id, title } => {
-            assignments::create_assignment(course_id, title).with_context(|| {
-                format!(
-                    "Failed to create assignment '{}' for course {}",
-                    title, course_id
-                )
-            })
+            vars,
+            package,
+        } => notes::create_note(
+            course_id,
+            title,
+            variant,
+            sections,
+            no_open,
+            vars,
+            package.as_deref(),
+        )
+        .with_context(|| format!("Failed to create note for course {}", course_id)),
+        Commands::Assignment {
+            course_id,
+            title,
+            group,
+            vars,
+            package,
+        } => assignments::create_assignment(
+            course_id,
+            title,
+            group.as_deref(),
+            vars,
+            package.as_deref(),
+        )
+        .with_context(|| {
+            format!(
+                "Failed to create assignment '{}' for course {}",
+                title, course_id
+            )
+        }),
+        Commands::New {
+            template,
+            course_id,
+            title,
+            variant,
+            sections,
+            no_open,
+            vars,
+            package,
+        } => {
+            let template_ref = match variant {
+                Some(variant) => TemplateReference::new(template).with_variant(variant),
+                None => TemplateReference::new(template),
+            };
+            notes::create_document(
+                &template_ref,
+                course_id,
+                title.as_deref(),
+                sections.as_deref(),
+                *no_open,
+                vars,
+                package.as_deref(),
+            )
+            .with_context(|| format!("Failed to create {} for course {}", template, course_id))
         }
         Commands::Compile {
             filepath,
@@ -102,7 +164,12 @@ pub fn execute_command(command: &Commands) -> Result<()> {
         Commands::Dev { action } => {
             execute_dev_action(action).with_context(|| "Failed to execute dev command")
         }
-    }
+    };
+
+    // Installing updates waits until the command is done, so a slow network
+    // never holds up e.g. creating a note
+    templates::install_automatic_updates(&updates);
+    result
 }
 
 fn execute_setup_action(action: &SetupAction) -> Result<()> {
@@ -128,7 +195,17 @@ fn execute_assignment_action(action: &AssignmentAction) -> Result<()> {
 fn execute_template_action(action: &TemplateAction) -> Result<()> {
     match action {
         TemplateAction::Status => templates::template_status(),
+        TemplateAction::List {
+            course_type,
+            package,
+        } => templates::list_templates(course_type.as_deref(), package.as_deref()),
+        TemplateAction::Show {
+            template,
+            course,
+            package,
+        } => templates::show_template(template, course.as_deref(), package.as_deref()),
         TemplateAction::Update => templates::update_template(),
+        TemplateAction::Sync => templates::sync_templates(),
         TemplateAction::Reinstall => templates::reinstall_template(),
         TemplateAction::Create {
             course_id,
@@ -138,16 +215,76 @@ fn execute_template_action(action: &TemplateAction) -> Result<()> {
         } => {
             templates::create_custom_template(course_id, title, template_type, sections.as_deref())
         }
+        TemplateAction::Install { source, link } => {
+            templates::install_local_template(source, *link)
+        }
+        TemplateAction::NewPackage {
+            name,
+            path,
+            install,
+        } => templates::new_template_package(name, path.as_deref(), *install),
+        TemplateAction::Lint { file } => templates::lint_note(file),
+        TemplateAction::Rollback {
+            version,
+            package,
+            yes,
+        } => templates::rollback_template(version.as_deref(), package.as_deref(), *yes),
+        TemplateAction::MigrateNotes {
+            to,
+            package,
+            dry_run,
+            compile,
+        } => templates::migrate_notes(to.as_deref(), package.as_deref(), *dry_run, *compile),
+        TemplateAction::Preview {
+            template,
+            variant,
+            course,
+            package,
+            all,
+            no_open,
+        } => {
+            if *all {
+                templates::preview_all_templates(course, package.as_deref(), *no_open)
+            } else {
+                templates::preview_template(
+                    template.as_deref().unwrap_or_default(),
+                    variant.as_deref(),
+                    course,
+                    package.as_deref(),
+                    *no_open,
+                )
+            }
+        }
+        TemplateAction::Cache { action } => execute_template_cache_action(action),
+    }
+}
+
+fn execute_template_cache_action(action: &TemplateCacheAction) -> Result<()> {
+    match action {
+        TemplateCacheAction::List => templates::list_template_cache(),
+        TemplateCacheAction::Prune { keep } => templates::prune_template_cache(*keep),
+        TemplateCacheAction::Verify => templates::verify_template_cache(),
+    }
+}
+
+fn execute_profile_action(action: &ProfileAction) -> Result<()> {
+    match action {
+        ProfileAction::Create { name, from } => config::create_profile(name, from.as_deref()),
+        ProfileAction::Switch { name } => config::switch_profile(name.as_deref()),
+        ProfileAction::List => config::list_profiles(),
+        ProfileAction::Diff { profile, other } => config::diff_profiles(profile, other.as_deref()),
+        ProfileAction::Delete { name } => config::delete_profile(name),
     }
 }
 
 fn execute_config_action(action: &ConfigAction) -> Result<()> {
     match action {
-        ConfigAction::Show => config::show_config(),
+        ConfigAction::Show { origin } => config::show_config(*origin),
         ConfigAction::Get { key } => config::get_config_value(key),
         ConfigAction::Set { key, value } => config::set_config_value(key, value),
         ConfigAction::Edit => config::edit_config(),
         ConfigAction::ListKeys => config::list_config_keys(),
+        ConfigAction::Schema { output } => config::export_config_schema(output.as_deref()),
         ConfigAction::Interactive => config::interactive_config(),
         ConfigAction::SetAuthor { name } => config::set_author(name),
         ConfigAction::SetEditor { editor } => config::set_editor(editor),
@@ -155,26 +292,64 @@ fn execute_config_action(action: &ConfigAction) -> Result<()> {
             name,
             repository,
             version,
+            branch,
+            rev,
             template_path,
-        } => config::add_template_repository(
-            name,
-            repository,
-            version.as_deref(),
-            template_path.as_deref(),
-        ),
+            provider,
+            base_url,
+        } => config::add_template_repository(TemplateRepository {
+            name: name.clone(),
+            repository: repository.clone(),
+            provider: provider.parse()?,
+            base_url: base_url
+                .as_ref()
+                .map(|u| u.trim_end_matches('/').to_string()),
+            version: version.clone(),
+            branch: branch.clone(),
+            rev: rev.clone(),
+            template_path: template_path.clone(),
+            ..TemplateRepository::default()
+        }),
         ConfigAction::RemoveTemplateRepo { name } => config::remove_template_repository(name),
         ConfigAction::EnableTemplateRepo { name, enabled } => {
             config::enable_template_repository(name, *enabled)
         }
-        ConfigAction::ListTemplateRepos => config::list_template_repositories(),
-        ConfigAction::SetTemplateAutoUpdate { enabled } => {
-            config::set_template_auto_update(*enabled)
+        ConfigAction::AllowTemplateHooks { name, allowed } => {
+            config::allow_template_hooks(name, *allowed)
         }
+        ConfigAction::ListTemplateRepos => config::list_template_repositories(),
+        ConfigAction::SetTemplateAutoUpdate {
+            enabled,
+            policy,
+            interval_hours,
+        } => config::set_template_auto_update(
+            *enabled,
+            policy.as_deref().map(str::parse).transpose()?,
+            *interval_hours,
+        ),
         ConfigAction::Reset => config::reset_config(),
         ConfigAction::Path => config::show_config_path(),
         ConfigAction::Check => config::check_config(),
         ConfigAction::Cleanse { yes } => config::cleanse_config(*yes),
-        ConfigAction::Migrate => config::migrate_config(),
+        ConfigAction::Profile { action } => execute_profile_action(action),
+        ConfigAction::Migrate { dry_run } => config::migrate_config(*dry_run),
+        ConfigAction::Export {
+            output,
+            redact_paths,
+        } => config::export_config(output.as_deref(), *redact_paths),
+        ConfigAction::Import {
+            file,
+            merge: _,
+            replace,
+            yes,
+        } => {
+            let mode = if *replace {
+                ImportMode::Replace
+            } else {
+                ImportMode::Merge
+            };
+            config::import_config(file, mode, *yes)
+        }
     }
 }
 
@@ -186,6 +361,7 @@ fn execute_course_action(action: &CourseAction) -> Result<()> {
             course_name,
         } => courses::add_course(course_id, course_name),
         CourseAction::Remove { course_id } => courses::remove_course(course_id),
+        CourseAction::Show { course_id } => courses::show_course(course_id),
         CourseAction::Browse => courses::browse_common_courses(),
     }
 }
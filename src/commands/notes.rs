@@ -6,7 +6,9 @@ use crate::config::get_config;
 use crate::core::directories::DirectoryScanner;
 use crate::core::files::FileOperations;
 use crate::core::status::StatusManager;
-use crate::core::template::{builder::TemplateBuilder, engine::TemplateReference};
+use crate::core::template::{
+    builder::TemplateBuilder, discovery::TemplateDiscovery, engine::TemplateReference,
+};
 use crate::core::validation::Validator;
 use crate::display::output::{OutputManager, Status};
 use anyhow::Result;
@@ -20,38 +22,41 @@ pub fn create_note(
     variant: &Option<String>,
     sections: &Option<String>,
     no_open: &bool,
+    vars: &[String],
+    package: Option<&str>,
 ) -> Result<()> {
     let config = get_config()?;
 
     OutputManager::print_status(Status::Loading, "Creating lecture note...");
 
-    // Generate the title as an owned String to avoid borrowing issues
-    let note_title = match title {
-        Some(title) => title.clone(),
-        None => format!("Lecture - {}", chrono::Local::now().format("%B %d, %Y")),
-    };
-
     // Generate content using builder
-    let mut builder = TemplateBuilder::new(course_id, &config)?
-        .with_title(&note_title)
-        .with_reference(match variant {
-            Some(variant) => TemplateReference::lecture().with_variant(variant),
-            None => TemplateReference::lecture(),
-        });
+    let mut builder = TemplateBuilder::new(course_id, &config)?;
+    builder = match title {
+        Some(title) => builder.with_title(title),
+        None => builder.with_default_title(&format!(
+            "Lecture - {}",
+            chrono::Local::now().format("%B %d, %Y")
+        )),
+    };
+    builder = builder.with_reference(match variant {
+        Some(variant) => TemplateReference::lecture().with_variant(variant),
+        None => TemplateReference::lecture(),
+    });
 
     builder = match sections {
         None => builder,
-        Some(sects) => {
-            let sections_to_use = sects
-                .split(",")
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-
-            builder.with_sections(sections_to_use)
-        }
+        Some(sects) => builder.with_sections(parse_sections(sects)),
     };
 
+    for var in vars {
+        let (key, value) = Validator::parse_variable_assignment(var)?;
+        builder = builder.with_variable(&key, &value);
+    }
+
+    if let Some(package) = package {
+        builder = builder.with_package(package);
+    }
+
     // Build the template content
     let content = builder.build()?;
 
@@ -70,6 +75,95 @@ pub fn create_note(
     Ok(())
 }
 
+/// Create a document from any template, e.g. `noter new lab-report 02101`
+///
+/// The document is written to the template's output directory inside the
+/// course directory.
+pub fn create_document(
+    template_ref: &TemplateReference,
+    course_id: &str,
+    title: Option<&str>,
+    sections: Option<&str>,
+    no_open: bool,
+    vars: &[String],
+    package: Option<&str>,
+) -> Result<()> {
+    Validator::validate_course_id(course_id)?;
+    let config = get_config()?;
+
+    let template_config =
+        TemplateDiscovery::resolve_template_package(&config, &template_ref.name, package)?;
+    let definition = template_config
+        .templates
+        .iter()
+        .find(|definition| definition.name == template_ref.name)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Template '{}' not found (available: {})",
+                template_ref.name,
+                template_config
+                    .templates
+                    .iter()
+                    .map(|definition| definition.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+    let output_dir = definition.output_directory()?;
+
+    OutputManager::print_status(
+        Status::Loading,
+        &format!("Creating {}...", definition.display_name.to_lowercase()),
+    );
+
+    let mut builder = TemplateBuilder::new(course_id, &config)?;
+    builder = match title {
+        Some(title) => builder.with_title(title),
+        None => builder.with_default_title(&format!(
+            "{} - {}",
+            definition.display_name,
+            chrono::Local::now().format("%B %d, %Y")
+        )),
+    };
+    builder = builder
+        .with_reference(template_ref.clone())
+        .with_package(&template_config.metadata.name);
+
+    if let Some(sections) = sections {
+        builder = builder.with_sections(parse_sections(sections));
+    }
+
+    for var in vars {
+        let (key, value) = Validator::parse_variable_assignment(var)?;
+        builder = builder.with_variable(&key, &value);
+    }
+
+    let content = builder.build()?;
+
+    let type_ = template_ref
+        .variant
+        .as_deref()
+        .unwrap_or(&template_ref.name);
+    let filename = FileOperations::generate_filename(course_id, type_, title);
+    let filepath = Path::new(&config.paths.notes_dir)
+        .join(course_id)
+        .join(output_dir)
+        .join(filename);
+
+    FileOperations::create_file_with_content_and_open(&filepath, &content, &config, !no_open)?;
+    OutputManager::print_status(Status::Success, &format!("Created {}", filepath.display()));
+
+    Ok(())
+}
+
+fn parse_sections(sections: &str) -> Vec<String> {
+    sections
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
 pub fn open_recent(course_id: &str) -> Result<()> {
     Validator::validate_course_id(course_id)?;
     let config = get_config()?;
@@ -137,7 +231,7 @@ pub fn list_recent(course_id: &str) -> Result<()> {
     let mut files = DirectoryScanner::scan_directory_for_files(&course_dir, &["typ"])?;
 
     // Sort by modification time (most recent first)
-    files.sort_by(|a, b| b.modified.cmp(&a.modified));
+    files.sort_by_key(|file| std::cmp::Reverse(file.modified));
 
     if files.is_empty() {
         println!("  No notes found");
@@ -5,7 +5,7 @@
 use anyhow::Result;
 use colored::Colorize;
 
-use crate::config::get_config;
+use crate::config::{Config, get_config};
 use crate::core::setup::{SetupConfig, SetupManager};
 use crate::display::output::{OutputManager, Status};
 use crate::display::prompts::PromptManager;
@@ -42,18 +42,16 @@ pub fn setup_repository() -> Result<()> {
     let user_prefs = PromptManager::setup_wizard()?;
     let setup_config = prompt_setup_options()?;
 
-    let mut config = get_config()?;
-
     // Apply user preferences to config
-    if !user_prefs.author.is_empty() {
-        config.author = user_prefs.author;
-    }
-    config.preferred_editor = user_prefs.editor;
-    config.note_preferences.auto_open_file = user_prefs.auto_open;
-    config.note_preferences.include_date_in_title = user_prefs.include_date;
-
-    // Save the updated config
-    config.save()?;
+    let config = Config::update(|config| {
+        if !user_prefs.author.is_empty() {
+            config.author = user_prefs.author;
+        }
+        config.preferred_editor = user_prefs.editor;
+        config.note_preferences.auto_open_file = user_prefs.auto_open;
+        config.note_preferences.include_date_in_title = user_prefs.include_date;
+        Ok(config.clone())
+    })?;
 
     OutputManager::print_status(Status::Loading, "Setting up DTU notes repository...");
 
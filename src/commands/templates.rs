@@ -7,12 +7,24 @@ use colored::Colorize;
 use std::fs;
 use std::path::Path;
 
-use crate::config::{Config, get_config};
-use crate::core::template::config::{TemplateConfig, TemplateVariant};
+use crate::config::{AutoUpdatePolicy, Config, LocalTemplatePackage, get_config};
+use crate::core::files::FileOperations;
+use crate::core::template::cache::TemplateCache;
+use crate::core::template::config::{
+    ParameterType, TemplateConfig, TemplateDefinition, TemplateParameter, TemplateVariant,
+};
 use crate::core::template::fetcher::Fetcher;
+use crate::core::template::lockfile::LockFile;
+use crate::core::template::scaffold::PackageScaffold;
+use crate::core::template::updates::{AvailableUpdate, UpdateChecker};
+use crate::core::template::validation::{TemplateValidator, ValidationSeverity};
+use crate::core::template::versions::{ImportRewrite, ImportRewriter, VersionHistory};
 use crate::core::template::{
-    builder::TemplateBuilder, discovery::TemplateDiscovery, engine::TemplateReference,
+    builder::TemplateBuilder,
+    discovery::{AvailableTemplate, TemplateDiscovery, TemplateSource},
+    engine::TemplateReference,
 };
+use crate::core::typst::TypstCompiler;
 use crate::core::validation::Validator;
 use crate::display::output::{OutputManager, Status};
 
@@ -22,7 +34,9 @@ pub fn template_status() -> Result<()> {
     OutputManager::print_status(Status::Loading, "Checking template status...");
 
     display_template_discovery_status(&config);
+    display_template_conflicts(&config);
     display_github_template_status(&config);
+    display_local_packages(&config);
     display_command_examples();
 
     Ok(())
@@ -46,6 +60,37 @@ fn display_template_discovery_status(config: &Config) {
     }
 }
 
+/// Report template names defined by several packages and which one is used
+fn display_template_conflicts(config: &Config) {
+    let Ok(preferred) = TemplateDiscovery::load_preferred_configs(config) else {
+        return;
+    };
+
+    let conflicts = TemplateDiscovery::find_template_conflicts(&preferred);
+    if conflicts.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{} Template Conflicts:", "⚠️".yellow());
+    for (template, packages) in conflicts {
+        println!(
+            "  {} is defined by {}",
+            template.yellow(),
+            packages.join(", ").bright_white()
+        );
+        println!(
+            "    Using: {} {}",
+            packages[0].green(),
+            "(change with 'templates.preference_order' or --package)".dimmed()
+        );
+    }
+    println!(
+        "  Preference order: {}",
+        config.templates.preference_order.join(" > ").dimmed()
+    );
+}
+
 fn display_template_system_header() {
     println!();
     println!("{} Template System Status", "📋".blue());
@@ -189,7 +234,8 @@ fn display_github_template_status(config: &Config) {
             if statuses.is_empty() {
                 println!("  No template repositories configured");
             } else {
-                display_installed_templates(statuses);
+                let lock = LockFile::load(&LockFile::path_for(config)).ok().flatten();
+                display_installed_templates(config, statuses, lock.as_ref());
             }
         }
         Err(e) => {
@@ -201,7 +247,40 @@ fn display_github_template_status(config: &Config) {
     }
 }
 
-fn display_installed_templates(statuses: Vec<(String, Option<String>)>) {
+fn display_local_packages(config: &Config) {
+    if config.templates.local_packages.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("Local Packages:");
+    for package in &config.templates.local_packages {
+        let installed = Path::new(&config.paths.typst_packages_dir)
+            .join(&package.name)
+            .join(&package.version)
+            .exists();
+        let icon = if installed { "✅" } else { "❌" };
+
+        println!(
+            "  {} {} {}{}",
+            icon,
+            package.name.bright_white(),
+            package.version.bright_blue(),
+            if package.linked {
+                " (linked)".yellow().to_string()
+            } else {
+                String::new()
+            }
+        );
+        println!("    Source: {}", package.source.dimmed());
+    }
+}
+
+fn display_installed_templates(
+    config: &Config,
+    statuses: Vec<(String, Option<String>)>,
+    lock: Option<&LockFile>,
+) {
     println!("Installed Templates:");
     for (repo_name, version_opt) in statuses {
         let (status_icon, status_text, status_color): (
@@ -225,7 +304,39 @@ fn display_installed_templates(statuses: Vec<(String, Option<String>)>) {
             Some(version) => println!("    Version: {}", version.bright_blue()),
             None => println!("    {}", "Run 'noter template update' to install".dimmed()),
         }
+
+        if let Some(locked) = lock.and_then(|lock| lock.find(&repo_name)) {
+            println!("    Locked: {}", locked.tag.bright_blue());
+        }
+
+        if let Some(package_dir) = Fetcher::package_dir_for(config, &repo_name) {
+            display_installed_versions(&package_dir);
+        }
+    }
+}
+
+/// Show the versions kept for rollback, marking the active one
+fn display_installed_versions(package_dir: &Path) {
+    let history = VersionHistory::load(package_dir);
+    if history.versions.len() < 2 {
+        return;
     }
+
+    let current = history.current();
+    let versions: Vec<String> = history
+        .versions
+        .iter()
+        .map(|installed| {
+            if Some(installed.version.as_str()) == current {
+                format!("{} (active)", installed.version)
+                    .green()
+                    .to_string()
+            } else {
+                installed.version.clone()
+            }
+        })
+        .collect();
+    println!("    Installed versions: {}", versions.join(", "));
 }
 
 fn display_command_examples() {
@@ -235,19 +346,37 @@ fn display_command_examples() {
             "noter template update",
             "Update templates to latest version",
         ),
+        (
+            "noter template sync",
+            "Install the versions pinned in noter.lock",
+        ),
         (
             "noter template create 02101 \"My Template\" -t lecture",
             "Create custom template",
         ),
         ("noter template reinstall", "Reinstall all templates"),
+        (
+            "noter template cache list",
+            "Show downloaded template archives",
+        ),
     ]);
 }
 
+fn display_offline_notice(config: &Config) {
+    if config.templates.is_offline() {
+        OutputManager::print_status(
+            Status::Info,
+            "Offline mode: installing from the template cache",
+        );
+    }
+}
+
 /// Update templates to the latest version
 pub fn update_template() -> Result<()> {
     let config = get_config()?;
 
     OutputManager::print_status(Status::Loading, "Checking for template updates...");
+    display_offline_notice(&config);
 
     // Update templates
     let results = Fetcher::update_templates(&config)?;
@@ -267,6 +396,21 @@ pub fn update_template() -> Result<()> {
     }
 
     for result in results {
+        if result.up_to_date {
+            OutputManager::print_status(
+                Status::Info,
+                &format!(
+                    "Already up to date: {} ({})",
+                    result
+                        .lock_entry
+                        .as_ref()
+                        .map_or("template", |entry| entry.name.as_str()),
+                    result.version.green()
+                ),
+            );
+            continue;
+        }
+
         OutputManager::print_status(
             Status::Success,
             &format!(
@@ -286,6 +430,17 @@ pub fn update_template() -> Result<()> {
         );
     }
 
+    OutputManager::print_status(
+        Status::Info,
+        &format!(
+            "Pinned versions in {}",
+            LockFile::path_for(&config)
+                .display()
+                .to_string()
+                .bright_white()
+        ),
+    );
+
     // Verify the update worked by checking template discovery
     println!();
     OutputManager::print_status(Status::Loading, "Verifying template installation...");
@@ -311,11 +466,118 @@ pub fn update_template() -> Result<()> {
     Ok(())
 }
 
+/// Check for template updates when `templates.auto_update` is enabled
+///
+/// Runs at most once per check interval and never fails the calling
+/// command; an unreachable network just skips the check. With the `notify`
+/// policy the updates are announced right away. With `install` they are
+/// returned, so [`install_automatic_updates`] can install them once the
+/// command has finished instead of delaying it.
+pub fn run_automatic_update_check() -> Vec<AvailableUpdate> {
+    let Ok(config) = get_config() else {
+        return Vec::new();
+    };
+    let Some(updates) = UpdateChecker::check_if_due(&config) else {
+        return Vec::new();
+    };
+    if updates.is_empty() {
+        return Vec::new();
+    }
+
+    match config.templates.auto_update_policy {
+        AutoUpdatePolicy::Notify => {
+            OutputManager::print_status(
+                Status::Info,
+                &format!(
+                    "Template update available: {} (run {})",
+                    update_summary(&updates),
+                    "noter template update".bright_white()
+                ),
+            );
+            Vec::new()
+        }
+        AutoUpdatePolicy::Install => updates,
+    }
+}
+
+/// Install the updates found by [`run_automatic_update_check`]
+pub fn install_automatic_updates(updates: &[AvailableUpdate]) {
+    if updates.is_empty() {
+        return;
+    }
+    let Ok(config) = get_config() else {
+        return;
+    };
+
+    let summary = update_summary(updates);
+    OutputManager::print_status(
+        Status::Loading,
+        &format!("Installing template updates: {}", summary),
+    );
+    match Fetcher::update_templates(&config) {
+        Ok(_) => {
+            OutputManager::print_status(Status::Success, &format!("Updated templates: {}", summary))
+        }
+        Err(e) => OutputManager::print_status(
+            Status::Warning,
+            &format!("Automatic template update failed: {}", e),
+        ),
+    }
+}
+
+fn update_summary(updates: &[AvailableUpdate]) -> String {
+    updates
+        .iter()
+        .map(|update| format!("{} {} -> {}", update.name, update.installed, update.latest))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Install the template versions pinned in the lockfile
+pub fn sync_templates() -> Result<()> {
+    let config = get_config()?;
+
+    OutputManager::print_status(
+        Status::Loading,
+        &format!(
+            "Installing templates from {}...",
+            LockFile::path_for(&config).display()
+        ),
+    );
+
+    display_offline_notice(&config);
+    let results = Fetcher::sync_templates(&config)?;
+
+    if results.is_empty() {
+        OutputManager::print_status(Status::Warning, "The lockfile doesn't pin any templates");
+        return Ok(());
+    }
+
+    for result in results {
+        let name = result
+            .lock_entry
+            .as_ref()
+            .map_or("template", |entry| entry.name.as_str());
+
+        OutputManager::print_status(
+            Status::Success,
+            &format!(
+                "Installed {} {} (checksum verified)",
+                name,
+                result.version.green()
+            ),
+        );
+    }
+
+    Ok(())
+}
+
 /// Force reinstall all templates
 pub fn reinstall_template() -> Result<()> {
     let config = get_config()?;
 
     OutputManager::print_status(Status::Loading, "Reinstalling templates...");
+    display_offline_notice(&config);
 
     // This would implement a full reinstall by clearing the templates directory
     // and re-downloading everything
@@ -330,7 +592,7 @@ pub fn reinstall_template() -> Result<()> {
     fs::create_dir_all(templates_dir)?;
 
     // Re-download templates
-    let results = Fetcher::update_templates(&config)?;
+    let results = Fetcher::reinstall_templates(&config)?;
 
     if results.is_empty() {
         OutputManager::print_status(Status::Error, "No templates were installed");
@@ -357,118 +619,1086 @@ pub fn reinstall_template() -> Result<()> {
     Ok(())
 }
 
-/// Create a custom template using the new TemplateBuilder
-pub fn create_custom_template(
-    course_id: &str,
-    title: &str,
-    template_type: &str,
-    sections: Option<&str>,
-) -> Result<()> {
+/// Switch a package back to a previously installed version
+///
+/// Afterwards offers to rewrite `@local` imports of the package in existing
+/// notes to the activated version.
+pub fn rollback_template(version: Option<&str>, package: Option<&str>, yes: bool) -> Result<()> {
     let config = get_config()?;
+    let package_dir = find_rollback_package(&config, package)?;
+    let package_name = package_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut history = VersionHistory::load(&package_dir);
+    let current = history.current().map(str::to_string);
+    let target = match version {
+        Some(version) => version.to_string(),
+        None => history
+            .previous()
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No earlier version of {} is installed to roll back to",
+                    package_name
+                )
+            })?
+            .to_string(),
+    };
 
-    // Validate course ID
-    Validator::validate_course_id(course_id)?;
+    if current.as_deref() == Some(target.as_str()) {
+        OutputManager::print_status(
+            Status::Info,
+            &format!("{} {} is already active", package_name, target),
+        );
+    } else {
+        history.activate(&package_dir, &target)?;
+        history.save(&package_dir)?;
 
-    OutputManager::print_status(
-        Status::Loading,
-        &format!(
-            "Creating custom {} template for {}",
-            template_type.bright_blue(),
-            course_id.yellow()
-        ),
-    );
+        OutputManager::print_status(
+            Status::Success,
+            &format!(
+                "Switched {} {} -> {}",
+                package_name,
+                current.as_deref().unwrap_or("unknown"),
+                target.green()
+            ),
+        );
+        println!("  {}", "New notes now use this version".dimmed());
+    }
 
-    // Parse template reference from template type
-    let template_ref = match template_type.to_lowercase().as_str() {
-        "lecture" | "l" | "note" => TemplateReference::lecture(),
-        "assignment" | "a" => TemplateReference::assignment(),
-        "lab" | "lab-report" => TemplateReference::lab_report(),
-        "thesis" | "project" => TemplateReference::thesis(),
-        custom => TemplateReference::new(custom),
-    };
+    let rewriter = ImportRewriter::new(&package_name, &target)?;
+    let rewrites = ImportRewriter::plan(&[rewriter], Path::new(&config.paths.notes_dir))?;
+    if rewrites.is_empty() {
+        return Ok(());
+    }
 
-    // Build template using new TemplateBuilder
-    let mut builder = TemplateBuilder::new(course_id, &config)?
-        .with_title(title)
-        .with_reference(template_ref);
+    println!();
+    println!(
+        "{} note(s) import another version of {}:",
+        rewrites.len(),
+        package_name.bright_white()
+    );
+    for rewrite in &rewrites {
+        println!(
+            "  {} {}",
+            rewrite.path.display(),
+            format!("({})", rewrite.changes[0].from.join(", ")).dimmed()
+        );
+    }
 
-    // Parse custom sections if provided
-    if let Some(sections_str) = sections {
-        let custom_sections: Vec<String> = sections_str
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
+    if !yes {
+        use std::io::{self, Write};
+        print!("\nRewrite their imports to {}? [y/N]: ", target);
+        io::stdout().flush()?;
 
-        if !custom_sections.is_empty() {
-            builder = builder.with_sections(custom_sections);
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("Imports left unchanged.");
+            return Ok(());
         }
-    } else {
-        // Use default sections based on type
-        let default_sections = match template_type.to_lowercase().as_str() {
-            "assignment" | "a" => config.note_preferences.assignment_sections.clone(),
-            _ => config.note_preferences.lecture_sections.clone(),
-        };
-        builder = builder.with_sections(default_sections);
     }
 
-    // Generate template content using the builder
-    let content = builder.build()?;
+    for rewrite in &rewrites {
+        FileOperations::create_backup(&rewrite.path)?;
+        fs::write(&rewrite.path, &rewrite.content)?;
+    }
+    OutputManager::print_status(
+        Status::Success,
+        &format!("Rewrote imports in {} note(s)", rewrites.len()),
+    );
 
-    // Generate filename
-    let filename = generate_custom_template_filename(course_id, template_type, title);
+    Ok(())
+}
 
-    // Create output directory
-    let output_dir = Path::new(&config.paths.notes_dir)
-        .join(course_id)
-        .join("templates");
+/// Rewrite the `@local` template imports of existing notes
+///
+/// Notes are migrated to the active version of every installed package, or
+/// to `to` for a single package. Each changed note is backed up first.
+pub fn migrate_notes(
+    to: Option<&str>,
+    package: Option<&str>,
+    dry_run: bool,
+    compile: bool,
+) -> Result<()> {
+    let config = get_config()?;
+    let targets = find_migration_targets(&config, to, package)?;
+    let rewriters = targets
+        .iter()
+        .map(|(name, version)| ImportRewriter::new(name, version))
+        .collect::<Result<Vec<_>>>()?;
 
-    if !output_dir.exists() {
-        fs::create_dir_all(&output_dir)?;
+    let notes_dir = Path::new(&config.paths.notes_dir);
+    let rewrites = ImportRewriter::plan(&rewriters, notes_dir)?;
+
+    if rewrites.is_empty() {
+        let versions: Vec<String> = targets
+            .iter()
+            .map(|(name, version)| format!("{} {}", name, version))
+            .collect();
+        OutputManager::print_status(
+            Status::Success,
+            &format!("All notes already import {}", versions.join(", ")),
+        );
+        return Ok(());
     }
 
-    // Write template file
-    let file_path = output_dir.join(&filename);
-    fs::write(&file_path, &content)?;
+    display_import_diff(&rewrites, notes_dir);
+
+    if dry_run {
+        OutputManager::print_status(Status::Info, "Dry run: no notes were changed");
+        return Ok(());
+    }
+
+    if compile {
+        TypstCompiler::check_typst_availability().map_err(|_| {
+            anyhow::anyhow!("Typst was not found; install it or migrate without --compile")
+        })?;
+    }
+
+    for rewrite in &rewrites {
+        FileOperations::create_backup(&rewrite.path)?;
+        fs::write(&rewrite.path, &rewrite.content)?;
+    }
 
     OutputManager::print_status(
         Status::Success,
-        &format!(
-            "Custom template created: {}",
-            file_path.display().to_string().bright_white()
-        ),
+        &format!("Migrated {} note(s)", rewrites.len()),
     );
 
-    // Auto-open if configured
-    if config.note_preferences.auto_open_file {
-        OutputManager::print_status(Status::Info, "Opening in editor...");
+    if !compile {
+        return Ok(());
+    }
 
-        if let Some(editor) = &config.preferred_editor {
-            if std::process::Command::new(editor)
-                .arg(&file_path)
-                .spawn()
-                .is_err()
-            {
-                // Fallback to system default
-                let _ = opener::open(&file_path);
-            }
-        } else {
-            let _ = opener::open(&file_path);
+    println!();
+    OutputManager::print_status(Status::Loading, "Compiling migrated notes...");
+    let mut failures = 0;
+    for rewrite in &rewrites {
+        let filepath = rewrite.path.to_string_lossy();
+        if let Err(e) = TypstCompiler::compile_file(&filepath, &config) {
+            failures += 1;
+            fs::write(&rewrite.path, &rewrite.original)?;
+            println!("  {} {}", "❌".red(), rewrite.path.display());
+            println!("    {}", e.to_string().trim().dimmed());
         }
     }
 
-    // Show what was created
-    println!();
-    println!("Template Details:");
-    println!("  Type: {}", template_type.bright_blue());
-    println!(
-        "  Course: {} - {}",
-        course_id.yellow(),
-        config.get_course_name(course_id).dimmed()
-    );
-    println!("  Title: {}", title.green());
+    if failures > 0 {
+        OutputManager::print_status(
+            Status::Warning,
+            &format!(
+                "{} note(s) failed to compile and were restored to their previous imports",
+                failures
+            ),
+        );
+    } else {
+        OutputManager::print_status(Status::Success, "All migrated notes compile");
+    }
 
-    // Show sections if any
+    Ok(())
+}
+
+/// Packages and versions to migrate imports to
+fn find_migration_targets(
+    config: &Config,
+    to: Option<&str>,
+    package: Option<&str>,
+) -> Result<Vec<(String, String)>> {
+    let mut targets: Vec<(String, String)> = TemplateDiscovery::load_preferred_configs(config)?
+        .into_iter()
+        .map(|template_config| {
+            (
+                template_config.metadata.name,
+                template_config.metadata.version,
+            )
+        })
+        .filter(|(name, _)| package.is_none_or(|package| package == name))
+        .collect();
+
+    if targets.is_empty() {
+        return Err(match package {
+            Some(package) => anyhow::anyhow!("Template package '{}' is not installed", package),
+            None => anyhow::anyhow!("No template packages are installed"),
+        });
+    }
+
+    if let Some(to) = to {
+        if targets.len() > 1 {
+            return Err(anyhow::anyhow!(
+                "Several packages are installed; choose one for --to with --package ({})",
+                targets
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        let name = &targets[0].0;
+        if !Path::new(&config.paths.typst_packages_dir)
+            .join(name)
+            .join(to)
+            .is_dir()
+        {
+            return Err(anyhow::anyhow!(
+                "{} {} is not installed, so migrated notes wouldn't compile",
+                name,
+                to
+            ));
+        }
+        targets[0].1 = to.to_string();
+    }
+
+    Ok(targets)
+}
+
+/// Print the changed import lines of every note and a per-package summary
+fn display_import_diff(rewrites: &[ImportRewrite], notes_dir: &Path) {
+    for rewrite in rewrites {
+        let path = rewrite
+            .path
+            .strip_prefix(notes_dir)
+            .unwrap_or(&rewrite.path);
+        println!("{}", path.display().to_string().bright_white());
+
+        for (old, new) in rewrite.original.lines().zip(rewrite.content.lines()) {
+            if old != new {
+                println!("  {}", format!("- {}", old).red());
+                println!("  {}", format!("+ {}", new).green());
+            }
+        }
+    }
+
+    println!();
+    println!("Summary:");
+    let mut summary: Vec<(String, Vec<String>, String, usize)> = Vec::new();
+    for change in rewrites.iter().flat_map(|rewrite| &rewrite.changes) {
+        match summary
+            .iter_mut()
+            .find(|(package, _, _, _)| *package == change.package)
+        {
+            Some((_, from, _, notes)) => {
+                for version in &change.from {
+                    if !from.contains(version) {
+                        from.push(version.clone());
+                    }
+                }
+                *notes += 1;
+            }
+            None => summary.push((
+                change.package.clone(),
+                change.from.clone(),
+                change.to.clone(),
+                1,
+            )),
+        }
+    }
+
+    for (package, from, to, notes) in summary {
+        println!(
+            "  {} {} -> {} in {} note(s)",
+            package.bright_white(),
+            from.join(", ").red(),
+            to.green(),
+            notes
+        );
+    }
+    println!();
+}
+
+/// List the templates of the installed packages
+pub fn list_templates(course_type: Option<&str>, package: Option<&str>) -> Result<()> {
+    let config = get_config()?;
+    let templates: Vec<AvailableTemplate> = TemplateDiscovery::discover_templates(&config)?
+        .into_iter()
+        .filter(|template| package.is_none_or(|package| template_package_name(template) == package))
+        .filter_map(|mut template| {
+            let Some(course_type) = course_type else {
+                return Some(template);
+            };
+            template
+                .variants
+                .retain(|variant| applies_to_course_type(Some(&variant.course_types), course_type));
+            let applies =
+                applies_to_course_type(template.definition.course_types.as_ref(), course_type);
+            (applies || !template.variants.is_empty()).then_some(template)
+        })
+        .collect();
+
+    if templates.is_empty() {
+        OutputManager::print_status(
+            Status::Warning,
+            &match (course_type, package) {
+                (Some(course_type), _) => {
+                    format!("No templates found for course type '{}'", course_type)
+                }
+                (None, Some(package)) => format!("No templates found in package '{}'", package),
+                (None, None) => {
+                    "No templates installed. Run 'noter template update' to install them"
+                        .to_string()
+                }
+            },
+        );
+        return Ok(());
+    }
+
+    println!();
+    println!("{} Templates", "📋".blue());
+    println!();
+    for template in &templates {
+        let definition = &template.definition;
+        println!(
+            "  {} {} ({}) - {}",
+            "•".bright_green(),
+            definition.display_name.bright_white(),
+            definition.name.yellow(),
+            definition.description.dimmed()
+        );
+        if let Some(info) = &template.package_info {
+            println!("    Package: {} {}", info.name.cyan(), info.version.green());
+        }
+        println!(
+            "    Course Types: {}",
+            format_course_types(definition.course_types.as_ref()).dimmed()
+        );
+        if !template.variants.is_empty() {
+            let variants: Vec<String> = template
+                .variants
+                .iter()
+                .map(|variant| format!("{} ({})", variant.name, variant.course_types.join(", ")))
+                .collect();
+            println!("    Variants: {}", variants.join(", ").dimmed());
+        }
+        if !template.is_accessible {
+            println!(
+                "    {} Template file missing: {}",
+                "⚠️".yellow(),
+                template.file_path
+            );
+        }
+    }
+
+    println!();
+    println!(
+        "{}",
+        "Use 'noter template show <template>' for details".dimmed()
+    );
+    Ok(())
+}
+
+/// Show the details of a template and the variant it uses for courses
+pub fn show_template(template: &str, course: Option<&str>, package: Option<&str>) -> Result<()> {
+    let config = get_config()?;
+    let all_templates = TemplateDiscovery::discover_templates(&config)?;
+
+    let mut matches = all_templates
+        .iter()
+        .filter(|available| available.definition.name == template)
+        .filter(|available| {
+            package.is_none_or(|package| template_package_name(available) == package)
+        });
+
+    let Some(available) = matches.next() else {
+        let mut names: Vec<&str> = all_templates
+            .iter()
+            .map(|available| available.definition.name.as_str())
+            .collect();
+        names.sort();
+        names.dedup();
+        return Err(anyhow::anyhow!(
+            "Template '{}' not found{} (available: {})",
+            template,
+            package
+                .map(|package| format!(" in package '{}'", package))
+                .unwrap_or_default(),
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names.join(", ")
+            }
+        ));
+    };
+    let package_name = template_package_name(available);
+    let definition = &available.definition;
+
+    println!();
+    println!(
+        "{} {} ({})",
+        "📄".blue(),
+        definition.display_name.bright_white(),
+        definition.name.yellow()
+    );
+    println!("  {}", definition.description.dimmed());
+    println!();
+
+    if let Some(info) = &available.package_info {
+        println!("  Package: {} {}", info.name.cyan(), info.version.green());
+    }
+    let package_path = match &available.source {
+        TemplateSource::Local { path } => Some(path.as_str()),
+        _ => available
+            .package_info
+            .as_ref()
+            .map(|info| info.install_path.as_str()),
+    };
+    if let Some(path) = package_path {
+        println!("  Path: {}", path);
+    }
+    println!(
+        "  File: {} {}",
+        definition.file,
+        if available.is_accessible {
+            "✅".green()
+        } else {
+            "❌ missing".red()
+        }
+    );
+    println!("  Function: {}", definition.function.bright_white());
+    println!(
+        "  Course Types: {}",
+        format_course_types(definition.course_types.as_ref())
+    );
+    if !definition.default_sections.is_empty() {
+        println!("  Sections: {}", definition.default_sections.join(", "));
+    }
+    if !definition.parameters.is_empty() {
+        let parameters: Vec<String> = definition
+            .parameters
+            .iter()
+            .map(|parameter| match &parameter.default {
+                Some(default) => format!("{} (default {})", parameter.name, default),
+                None => parameter.name.clone(),
+            })
+            .collect();
+        println!("  Parameters: {}", parameters.join(", "));
+    }
+
+    println!();
+    if available.variants.is_empty() {
+        println!("  Variants: {}", "none".dimmed());
+    } else {
+        println!("  Variants:");
+        for variant in &available.variants {
+            println!(
+                "    {} {} - {}",
+                "•".bright_yellow(),
+                variant.name.yellow(),
+                variant.display_name
+            );
+            println!("      Course Types: {}", variant.course_types.join(", "));
+            if let Some(function) = &variant.function {
+                println!("      Function: {}", function);
+            }
+            if let Some(sections) = &variant.override_sections {
+                println!("      Sections: {}", sections.join(", "));
+            } else if let Some(sections) = &variant.additional_sections {
+                println!("      Extra Sections: {}", sections.join(", "));
+            }
+        }
+    }
+
+    display_auto_selected_variants(&config, definition, package_name, course)?;
+
+    let other_packages: Vec<&str> = all_templates
+        .iter()
+        .filter(|other| other.definition.name == template)
+        .map(template_package_name)
+        .filter(|other| *other != package_name)
+        .collect();
+    if !other_packages.is_empty() {
+        println!();
+        println!(
+            "{}",
+            format!(
+                "Also defined in: {} (use --package to show them)",
+                other_packages.join(", ")
+            )
+            .dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Print the variant `noter note`/`noter assignment` picks for a course, or
+/// for every configured course if none is given
+fn display_auto_selected_variants(
+    config: &Config,
+    definition: &TemplateDefinition,
+    package_name: &str,
+    course: Option<&str>,
+) -> Result<()> {
+    let mut courses: Vec<&str> = match course {
+        Some(course) => vec![course],
+        None => config.courses.keys().map(String::as_str).collect(),
+    };
+    courses.sort();
+
+    println!();
+    if courses.is_empty() {
+        println!(
+            "{}",
+            "Use --course <code> to see which variant is selected for a course".dimmed()
+        );
+        return Ok(());
+    }
+
+    let template_config =
+        TemplateDiscovery::resolve_template_package(config, &definition.name, Some(package_name))?;
+
+    println!("  Auto-selected Variant:");
+    for course in courses {
+        let (course_type, variant) =
+            TemplateDiscovery::auto_selected_variant(&template_config, &definition.name, course);
+        println!(
+            "    {} {}: {}",
+            course.bright_white(),
+            format!("({})", course_type).dimmed(),
+            match variant {
+                Some(variant) => variant.name.yellow(),
+                None => "base template".normal(),
+            }
+        );
+    }
+    Ok(())
+}
+
+fn template_package_name(template: &AvailableTemplate) -> &str {
+    template
+        .package_info
+        .as_ref()
+        .map(|info| info.name.as_str())
+        .unwrap_or_default()
+}
+
+/// Whether a template or variant is meant for a course type
+///
+/// Templates without course types and those listing "all" apply to every type.
+fn applies_to_course_type(course_types: Option<&Vec<String>>, course_type: &str) -> bool {
+    course_types.is_none_or(|types| types.iter().any(|t| t == course_type || t == "all"))
+}
+
+fn format_course_types(course_types: Option<&Vec<String>>) -> String {
+    match course_types {
+        Some(types) if !types.is_empty() => types.join(", "),
+        _ => "all".to_string(),
+    }
+}
+
+/// Render one template with sample data and open the PDF
+pub fn preview_template(
+    template: &str,
+    variant: Option<&str>,
+    course: &str,
+    package: Option<&str>,
+    no_open: bool,
+) -> Result<()> {
+    let config = preview_config()?;
+    let template_config = TemplateDiscovery::resolve_template_package(&config, template, package)?;
+    let definition = template_config
+        .templates
+        .iter()
+        .find(|definition| definition.name == template)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Template '{}' not found in {} (available: {})",
+                template,
+                template_config.metadata.name,
+                template_config
+                    .templates
+                    .iter()
+                    .map(|definition| definition.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+
+    let preview_dir = prepare_preview_dir()?;
+    OutputManager::print_status(
+        Status::Loading,
+        &format!(
+            "Rendering {}{}...",
+            definition.display_name,
+            variant.map(|v| format!(" ({})", v)).unwrap_or_default()
+        ),
+    );
+
+    let source = write_preview_source(
+        &config,
+        &preview_dir,
+        definition,
+        variant,
+        course,
+        &template_config.metadata.name,
+    )?;
+    require_typst(&source)?;
+
+    let pdf = TypstCompiler::compile_file(&source.to_string_lossy(), &config)?;
+    OutputManager::print_status(Status::Success, &format!("Preview rendered: {}", pdf));
+
+    if !no_open {
+        let _ = opener::open(&pdf);
+    }
+    Ok(())
+}
+
+/// Render every template and variant into a gallery directory
+pub fn preview_all_templates(course: &str, package: Option<&str>, no_open: bool) -> Result<()> {
+    let config = preview_config()?;
+    let templates: Vec<AvailableTemplate> = TemplateDiscovery::discover_templates(&config)?
+        .into_iter()
+        .filter(|template| {
+            package.is_none_or(|package| {
+                template
+                    .package_info
+                    .as_ref()
+                    .is_some_and(|info| info.name == package)
+            })
+        })
+        .collect();
+
+    if templates.is_empty() {
+        OutputManager::print_status(Status::Warning, "No templates found to preview");
+        return Ok(());
+    }
+
+    let preview_dir = prepare_preview_dir()?;
+    OutputManager::print_status(Status::Loading, "Rendering template gallery...");
+
+    let mut sources = Vec::new();
+    for template in &templates {
+        let package_name = template
+            .package_info
+            .as_ref()
+            .map(|info| info.name.clone())
+            .unwrap_or_default();
+
+        let variants = std::iter::once(None).chain(
+            template
+                .variants
+                .iter()
+                .map(|variant| Some(variant.name.as_str())),
+        );
+        for variant in variants {
+            let label = format!(
+                "{} {}{}",
+                package_name,
+                template.definition.name,
+                variant.map(|v| format!(" ({})", v)).unwrap_or_default()
+            );
+            match write_preview_source(
+                &config,
+                &preview_dir,
+                &template.definition,
+                variant,
+                course,
+                &package_name,
+            ) {
+                Ok(source) => sources.push((label, source)),
+                Err(e) => {
+                    println!("  {} {}: {}", "❌".red(), label, e);
+                    if let TemplateSource::Local { path } = &template.source {
+                        println!("    {}", format!("Package: {}", path).dimmed());
+                    }
+                }
+            }
+        }
+    }
+
+    let Some((_, first_source)) = sources.first() else {
+        return Err(anyhow::anyhow!("No template could be rendered"));
+    };
+    require_typst(first_source)?;
+
+    let mut rendered = 0;
+    for (label, source) in &sources {
+        match TypstCompiler::compile_file(&source.to_string_lossy(), &config) {
+            Ok(_) => {
+                rendered += 1;
+                println!("  {} {}", "✅".green(), label);
+            }
+            Err(e) => println!("  {} {}: {}", "❌".red(), label, e.to_string().trim()),
+        }
+    }
+
+    OutputManager::print_status(
+        Status::Success,
+        &format!(
+            "Rendered {} preview(s) in {}",
+            rendered,
+            preview_dir.display()
+        ),
+    );
+
+    if !no_open && rendered > 0 {
+        let _ = opener::open(&preview_dir);
+    }
+    Ok(())
+}
+
+/// Configuration for rendering previews: PDFs stay next to their sources and
+/// no configured output directory is cleaned
+fn preview_config() -> Result<Config> {
+    let mut config = get_config()?;
+    config.typst.output_dir = None;
+    config.typst.clean_before_compile = false;
+    Ok(config)
+}
+
+/// Empty directory for preview sources and PDFs, kept so the viewer can open them
+fn prepare_preview_dir() -> Result<std::path::PathBuf> {
+    let preview_dir = std::env::temp_dir().join("noter-preview");
+    if preview_dir.exists() {
+        fs::remove_dir_all(&preview_dir)?;
+    }
+    fs::create_dir_all(&preview_dir)?;
+    Ok(preview_dir)
+}
+
+/// Build a template with sample data and write it to the preview directory
+fn write_preview_source(
+    config: &Config,
+    preview_dir: &Path,
+    definition: &TemplateDefinition,
+    variant: Option<&str>,
+    course: &str,
+    package: &str,
+) -> Result<std::path::PathBuf> {
+    let mut reference = TemplateReference::new(definition.name.clone());
+    if let Some(variant) = variant {
+        reference = reference.with_variant(variant);
+    }
+
+    let mut builder = TemplateBuilder::new(course, config)?
+        .with_title(&format!("{} Preview", definition.display_name))
+        .with_reference(reference)
+        .with_package(package);
+
+    for parameter in definition.parameters.iter().filter(|p| p.default.is_none()) {
+        builder = builder.with_variable(&parameter.name, &sample_parameter_value(parameter));
+    }
+
+    let content = builder.build()?;
+    let file_name = match variant {
+        Some(variant) => format!("{}-{}-{}.typ", package, definition.name, variant),
+        None => format!("{}-{}.typ", package, definition.name),
+    };
+    let source = preview_dir.join(file_name);
+    fs::write(&source, content)?;
+    Ok(source)
+}
+
+/// Placeholder value for a template parameter without a default
+fn sample_parameter_value(parameter: &TemplateParameter) -> String {
+    match parameter.parameter_type {
+        ParameterType::String => format!("Sample {}", parameter.name),
+        ParameterType::Content => format!("Sample {}", parameter.name),
+        ParameterType::Integer => "1".to_string(),
+        ParameterType::Float => "1.5".to_string(),
+        ParameterType::Boolean => "true".to_string(),
+        ParameterType::Date => chrono::Local::now().format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// Fail with a helpful message when Typst isn't installed
+fn require_typst(source: &Path) -> Result<()> {
+    TypstCompiler::check_typst_availability().map_err(|_| {
+        anyhow::anyhow!(
+            "Typst was not found; install it to render previews (source written to {})",
+            source.display()
+        )
+    })?;
+    Ok(())
+}
+
+/// Package directory to roll back: the named package or repository, or the
+/// only package with tracked versions
+fn find_rollback_package(config: &Config, package: Option<&str>) -> Result<std::path::PathBuf> {
+    let packages_dir = Path::new(&config.paths.typst_packages_dir);
+
+    if let Some(name) = package {
+        let package_dir = Fetcher::package_dir_for(config, name)
+            .filter(|dir| VersionHistory::is_tracked(dir))
+            .unwrap_or_else(|| packages_dir.join(name));
+
+        if !VersionHistory::is_tracked(&package_dir) {
+            return Err(anyhow::anyhow!(
+                "No installed versions are recorded for '{}'",
+                name
+            ));
+        }
+        return Ok(package_dir);
+    }
+
+    let mut tracked: Vec<_> = match fs::read_dir(packages_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| VersionHistory::is_tracked(path))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    tracked.sort();
+
+    match tracked.len() {
+        0 => Err(anyhow::anyhow!(
+            "No installed template versions are recorded; run 'noter template update' first"
+        )),
+        1 => Ok(tracked.remove(0)),
+        _ => Err(anyhow::anyhow!(
+            "Several packages are installed; choose one with --package ({})",
+            tracked
+                .iter()
+                .filter_map(|path| path.file_name().and_then(|n| n.to_str()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// List the archives in the template download cache
+pub fn list_template_cache() -> Result<()> {
+    let cache = TemplateCache::open()?;
+    let archives = cache.list()?;
+
+    if archives.is_empty() {
+        OutputManager::print_status(Status::Info, "The template cache is empty");
+        return Ok(());
+    }
+
+    println!("{} Template Cache:", "📦".blue());
+    println!("  Location: {}", cache.dir().display().to_string().dimmed());
+    println!();
+
+    let mut total = 0;
+    for archive in &archives {
+        total += archive.size;
+        let file = archive
+            .path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+
+        match &archive.entry {
+            Some(entry) => println!(
+                "  {} {} {} {}",
+                entry.repository.bright_white(),
+                entry.tag.green(),
+                FileOperations::format_file_size(archive.size),
+                format!(
+                    "({}, downloaded {})",
+                    file,
+                    entry.downloaded_at.format("%Y-%m-%d %H:%M")
+                )
+                .dimmed()
+            ),
+            None => println!(
+                "  {} {} {}",
+                file.yellow(),
+                FileOperations::format_file_size(archive.size),
+                "(not indexed)".dimmed()
+            ),
+        }
+    }
+
+    println!();
+    println!(
+        "  Total: {} in {} archive(s)",
+        FileOperations::format_file_size(total).bright_white(),
+        archives.len()
+    );
+
+    Ok(())
+}
+
+/// Delete old versions from the template download cache
+pub fn prune_template_cache(keep: usize) -> Result<()> {
+    let cache = TemplateCache::open()?;
+    let removed = cache.prune(keep)?;
+
+    if removed.is_empty() {
+        OutputManager::print_status(Status::Info, "Nothing to prune");
+        return Ok(());
+    }
+
+    for archive in &removed {
+        println!("  Deleted {}", archive.path.display().to_string().dimmed());
+    }
+
+    let freed: u64 = removed.iter().map(|archive| archive.size).sum();
+    OutputManager::print_status(
+        Status::Success,
+        &format!(
+            "Pruned {} archive(s), freed {}",
+            removed.len(),
+            FileOperations::format_file_size(freed)
+        ),
+    );
+
+    Ok(())
+}
+
+/// Check every cached archive against its checksum and that it extracts
+pub fn verify_template_cache() -> Result<()> {
+    let cache = TemplateCache::open()?;
+    let archives = cache.list()?;
+
+    if archives.is_empty() {
+        OutputManager::print_status(Status::Info, "The template cache is empty");
+        return Ok(());
+    }
+
+    let mut failures = 0;
+    for archive in &archives {
+        let file = archive
+            .path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+
+        match cache.verify(archive) {
+            Ok(()) => println!("  {} {}", "✅".green(), file),
+            Err(e) => {
+                failures += 1;
+                println!("  {} {}: {}", "❌".red(), file, e);
+            }
+        }
+    }
+
+    println!();
+    if failures > 0 {
+        return Err(anyhow::anyhow!(
+            "{} cached archive(s) failed verification; run 'noter template reinstall' to download them again",
+            failures
+        ));
+    }
+
+    OutputManager::print_status(
+        Status::Success,
+        &format!("All {} cached archive(s) are valid", archives.len()),
+    );
+    Ok(())
+}
+
+/// Create a custom template using the new TemplateBuilder
+pub fn create_custom_template(
+    course_id: &str,
+    title: &str,
+    template_type: &str,
+    sections: Option<&str>,
+) -> Result<()> {
+    let config = get_config()?;
+
+    // Validate course ID
+    Validator::validate_course_id(course_id)?;
+
+    OutputManager::print_status(
+        Status::Loading,
+        &format!(
+            "Creating custom {} template for {}",
+            template_type.bright_blue(),
+            course_id.yellow()
+        ),
+    );
+
+    // Parse template reference from template type
+    let template_ref = match template_type.to_lowercase().as_str() {
+        "lecture" | "l" | "note" => TemplateReference::lecture(),
+        "assignment" | "a" => TemplateReference::assignment(),
+        "lab" | "lab-report" => TemplateReference::lab_report(),
+        "thesis" | "project" => TemplateReference::thesis(),
+        custom => TemplateReference::new(custom),
+    };
+    let has_course_sections = config
+        .course_overrides(course_id)
+        .sections
+        .contains_key(&template_ref.name);
+
+    // Build template using new TemplateBuilder
+    let mut builder = TemplateBuilder::new(course_id, &config)?
+        .with_title(title)
+        .with_reference(template_ref);
+
+    // Parse custom sections if provided
+    if let Some(sections_str) = sections {
+        let custom_sections: Vec<String> = sections_str
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        if !custom_sections.is_empty() {
+            builder = builder.with_sections(custom_sections);
+        }
+    } else if !has_course_sections {
+        // Use default sections based on type; course settings replace them
+        let default_sections = match template_type.to_lowercase().as_str() {
+            "assignment" | "a" => config.note_preferences.assignment_sections.clone(),
+            _ => config.note_preferences.lecture_sections.clone(),
+        };
+        builder = builder.with_sections(default_sections);
+    }
+
+    // Generate template content using the builder
+    let content = builder.build()?;
+
+    // Generate filename
+    let filename = generate_custom_template_filename(course_id, template_type, title);
+
+    // Create output directory
+    let output_dir = Path::new(&config.paths.notes_dir)
+        .join(course_id)
+        .join("templates");
+
+    if !output_dir.exists() {
+        fs::create_dir_all(&output_dir)?;
+    }
+
+    // Write template file
+    let file_path = output_dir.join(&filename);
+    fs::write(&file_path, &content)?;
+
+    OutputManager::print_status(
+        Status::Success,
+        &format!(
+            "Custom template created: {}",
+            file_path.display().to_string().bright_white()
+        ),
+    );
+
+    // Auto-open if configured
+    if config.note_preferences.auto_open_file {
+        OutputManager::print_status(Status::Info, "Opening in editor...");
+
+        if let Some(editor) = &config.preferred_editor {
+            if std::process::Command::new(editor)
+                .arg(&file_path)
+                .spawn()
+                .is_err()
+            {
+                // Fallback to system default
+                let _ = opener::open(&file_path);
+            }
+        } else {
+            let _ = opener::open(&file_path);
+        }
+    }
+
+    // Show what was created
+    println!();
+    println!("Template Details:");
+    println!("  Type: {}", template_type.bright_blue());
+    println!(
+        "  Course: {} - {}",
+        course_id.yellow(),
+        config.get_course_name(course_id).dimmed()
+    );
+    println!("  Title: {}", title.green());
+
+    // Show sections if any
     let sections_used = if let Some(s) = sections {
         s.split(',')
             .map(|s| s.trim())
@@ -501,6 +1731,199 @@ pub fn create_custom_template(
     Ok(())
 }
 
+/// Install a template package from a local directory or archive
+pub fn install_local_template(source: &str, link: bool) -> Result<()> {
+    let config = get_config()?;
+
+    OutputManager::print_status(
+        Status::Loading,
+        &format!("Installing template package from {}", source.bright_white()),
+    );
+
+    let result = Fetcher::install_from_path(&config, Path::new(source), link)?;
+
+    let source_path = Path::new(source)
+        .canonicalize()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| source.to_string());
+
+    Config::update(|config| {
+        config.templates.record_local_package(LocalTemplatePackage {
+            name: result.name.clone(),
+            version: result.version.clone(),
+            source: source_path,
+            linked: result.linked,
+        });
+        Ok(())
+    })?;
+
+    OutputManager::print_status(
+        Status::Success,
+        &format!(
+            "{} {} as {}",
+            if result.linked { "Linked" } else { "Installed" },
+            result.name.green(),
+            format!("@local/{}:{}", result.name, result.version).yellow()
+        ),
+    );
+    println!(
+        "  Location: {}",
+        result.installed_path.display().to_string().dimmed()
+    );
+
+    if !result.installed_path.join(".noter.config.toml").exists() {
+        OutputManager::print_status(
+            Status::Warning,
+            "Package has no .noter.config.toml, so noter can't use it for notes",
+        );
+    }
+
+    if result.linked {
+        println!(
+            "  {}",
+            "Changes in the source directory are picked up immediately".dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Scaffold a new template package, optionally installing it locally
+pub fn new_template_package(name: &str, path: Option<&str>, install: bool) -> Result<()> {
+    let config = get_config()?;
+    let scaffold = PackageScaffold::new(name, &config.author)?;
+
+    // Never write a package that noter itself would reject
+    let template_config = scaffold.template_config()?;
+    let issues = TemplateValidator::validate_template_config(&template_config)?;
+    if issues
+        .iter()
+        .any(|issue| issue.severity == ValidationSeverity::Error)
+    {
+        return Err(anyhow::anyhow!(
+            "Generated package failed validation:\n{}",
+            TemplateValidator::format_validation_report(&issues)
+        ));
+    }
+
+    let package_dir = Path::new(path.unwrap_or(name)).to_path_buf();
+    scaffold.write_to(&package_dir)?;
+
+    OutputManager::print_status(
+        Status::Success,
+        &format!(
+            "Created template package {} in {}",
+            name.green(),
+            package_dir.display().to_string().bright_white()
+        ),
+    );
+    for (file, _) in scaffold.files() {
+        println!("  {}", file.dimmed());
+    }
+
+    if install {
+        let install_dir = Path::new(&config.paths.typst_packages_dir)
+            .join(&scaffold.name)
+            .join(&scaffold.version);
+
+        if install_dir.exists() {
+            OutputManager::print_status(
+                Status::Info,
+                &format!("Replacing installed {} {}", name, scaffold.version),
+            );
+            fs::remove_dir_all(&install_dir)?;
+        }
+
+        FileOperations::copy_dir_recursive(&package_dir, &install_dir)?;
+        OutputManager::print_status(
+            Status::Success,
+            &format!(
+                "Installed locally as {}",
+                format!("@local/{}:{}", scaffold.name, scaffold.version).yellow()
+            ),
+        );
+    }
+
+    println!();
+    OutputManager::print_command_examples(&[
+        ("noter template status", "See which packages noter found"),
+        (
+            "noter note 02101 -t \"Test\"",
+            "Create a note with the package",
+        ),
+    ]);
+
+    Ok(())
+}
+
+/// Check an existing note against the validation rules of its template package
+pub fn lint_note(file: &str) -> Result<()> {
+    let config = get_config()?;
+    let path = Path::new(file);
+
+    if !path.exists() {
+        return Err(anyhow::anyhow!("File not found: {}", file));
+    }
+
+    let content = fs::read_to_string(path)?;
+    let template_config = find_package_for_document(&config, &content)?;
+    let engine = template_config.engine.clone().unwrap_or_default();
+
+    OutputManager::print_status(
+        Status::Loading,
+        &format!(
+            "Linting {} against {} {}",
+            file.bright_white(),
+            template_config.metadata.name.yellow(),
+            template_config.metadata.version.dimmed()
+        ),
+    );
+
+    let issues = TemplateValidator::lint_document(&content, &engine, Some(&config), path.parent())?;
+
+    println!();
+    println!("{}", TemplateValidator::format_validation_report(&issues));
+
+    let failed = issues.iter().any(|issue| {
+        issue.severity == ValidationSeverity::Error
+            || (engine.validation.strict_validation
+                && issue.severity == ValidationSeverity::Warning)
+    });
+
+    if failed {
+        return Err(anyhow::anyhow!("{} failed validation", file));
+    }
+
+    OutputManager::print_status(Status::Success, "No blocking validation issues");
+    Ok(())
+}
+
+/// Find the template package a document imports, falling back to the default package
+fn find_package_for_document(config: &Config, content: &str) -> Result<TemplateConfig> {
+    let import = content.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("#import \"@local/")
+            .and_then(|rest| rest.split('"').next())
+            .map(|package| match package.split_once(':') {
+                Some((name, version)) => (name.to_string(), Some(version.to_string())),
+                None => (package.to_string(), None),
+            })
+    });
+
+    if let Some((name, version)) = import {
+        let configs = TemplateDiscovery::load_template_configs(config)?;
+        let exact = configs.iter().find(|c| {
+            c.metadata.name == name && version.as_deref() == Some(c.metadata.version.as_str())
+        });
+
+        if let Some(found) = exact.or_else(|| configs.iter().find(|c| c.metadata.name == name)) {
+            return Ok(found.clone());
+        }
+    }
+
+    TemplateDiscovery::load_template_config(config)
+}
+
 /// Generate filename for custom templates
 fn generate_custom_template_filename(course_id: &str, template_type: &str, title: &str) -> String {
     use chrono::Local;
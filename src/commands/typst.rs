@@ -192,13 +192,12 @@ pub fn check_file_status(filepath: &str, detailed: bool) -> Result<()> {
                 println!("  Source: {}", input_path.display());
                 println!("  Output: {}", output_path.display());
 
-                if input_path.exists() {
-                    if let Ok(metadata) = std::fs::metadata(input_path) {
-                        if let Ok(modified) = metadata.modified() {
-                            let datetime: chrono::DateTime<chrono::Local> = modified.into();
-                            println!("  Modified: {}", datetime.format("%Y-%m-%d %H:%M:%S"));
-                        }
-                    }
+                if input_path.exists()
+                    && let Ok(metadata) = std::fs::metadata(input_path)
+                    && let Ok(modified) = metadata.modified()
+                {
+                    let datetime: chrono::DateTime<chrono::Local> = modified.into();
+                    println!("  Modified: {}", datetime.format("%Y-%m-%d %H:%M:%S"));
                 }
 
                 if output_path.exists() {
@@ -255,12 +254,11 @@ pub fn check_all_files(detailed: bool) -> Result<()> {
     let mut all_files = Vec::new();
     let notes_dir = Path::new(&config.paths.notes_dir);
 
-    if notes_dir.exists() {
-        if let Ok(files) =
+    if notes_dir.exists()
+        && let Ok(files) =
             FileOperations::list_files_with_extensions(notes_dir.to_str().unwrap(), &["typ"])
-        {
-            all_files.extend(files);
-        }
+    {
+        all_files.extend(files);
     }
 
     if all_files.is_empty() {
@@ -310,18 +308,18 @@ pub fn check_all_files(detailed: bool) -> Result<()> {
             ),
             (CompilationStatus::NotCompiled, "🔴 Uncompiled files"),
         ] {
-            if let Some(files) = files_by_status.get(&status) {
-                if !files.is_empty() {
-                    println!();
-                    println!("{}:", status_name);
-                    for file in files {
-                        let relative_path = file
-                            .strip_prefix(&config.paths.notes_dir)
-                            .unwrap_or(file)
-                            .display()
-                            .to_string();
-                        println!("  • {}", relative_path);
-                    }
+            if let Some(files) = files_by_status.get(&status)
+                && !files.is_empty()
+            {
+                println!();
+                println!("{}:", status_name);
+                for file in files {
+                    let relative_path = file
+                        .strip_prefix(&config.paths.notes_dir)
+                        .unwrap_or(file)
+                        .display()
+                        .to_string();
+                    println!("  • {}", relative_path);
                 }
             }
         }
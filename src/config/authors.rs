@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// A person listed on the front page of a document
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Author {
+    pub name: String,
+
+    /// DTU study number (e.g. s234567)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub student_id: Option<String>,
+}
+
+impl Author {
+    pub fn new(name: &str, student_id: Option<&str>) -> Self {
+        Self {
+            name: name.to_string(),
+            student_id: student_id.map(str::to_string),
+        }
+    }
+}
+
+impl std::fmt::Display for Author {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.student_id {
+            Some(student_id) => write!(f, "{} ({})", self.name, student_id),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
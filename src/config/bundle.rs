@@ -0,0 +1,341 @@
+//! Portable config bundles
+//!
+//! A bundle is a partial config file with the settings that can be shared
+//! between people, e.g. when a teammate joins: courses, template
+//! repositories, preferences and groups. Personal settings (author, study
+//! number, editor, profiles) are never exported, and neither is whether a
+//! template repository may run hooks: that is only decided locally.
+
+use anyhow::{Result, anyhow};
+use serde_json::{Value, json};
+
+use super::json_path::{leaf_keys, navigate_json_path, remove_json_path, set_json_path};
+use super::layers::merge_values;
+use super::metadata::CONFIG_VERSION;
+use super::migrations;
+use super::schema::{self, ValueType};
+use super::templates::{InstallSource, TemplateRepository};
+
+/// Setting of a template repository that is never exported or imported
+const TRUST_KEY: &str = "allow_hooks";
+
+/// Keys of the config that are exported, in bundle order
+pub const BUNDLE_KEYS: &[&str] = &[
+    "courses",
+    "course_overrides",
+    "groups",
+    "templates.custom_repositories",
+    "templates.preference_order",
+    "templates.use_official_fallback",
+    "semester_format",
+    "note_preferences",
+    "search",
+    "typst",
+    "obsidian_integration",
+    "paths",
+];
+
+/// How an imported bundle is combined with the current config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Add the bundle's entries; entries only in the current config are kept
+    Merge,
+    /// Replace every setting the bundle contains with the bundle's
+    Replace,
+}
+
+impl std::fmt::Display for ImportMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportMode::Merge => write!(f, "merge"),
+            ImportMode::Replace => write!(f, "replace"),
+        }
+    }
+}
+
+/// Build a bundle from the JSON form of a config
+///
+/// With `redact_paths`, every path setting is left out, since they rarely
+/// exist on another machine.
+pub fn export_bundle(config: &Value, redact_paths: bool) -> Value {
+    let mut bundle = json!({ "metadata": { "config_version": CONFIG_VERSION } });
+    for key in BUNDLE_KEYS {
+        if let Some(value) = navigate_json_path(config, key) {
+            set_json_path(&mut bundle, key, value.clone()).expect("bundle sections are tables");
+        }
+    }
+    if let Some(Value::Array(repositories)) = bundle.pointer_mut("/templates/custom_repositories") {
+        for repository in repositories.iter_mut().filter_map(Value::as_object_mut) {
+            repository.shift_remove(TRUST_KEY);
+        }
+    }
+
+    if redact_paths {
+        for key in leaf_keys(&bundle, "") {
+            if schema::find_key(&key).is_some_and(|key| matches!(key.value_type, ValueType::Path)) {
+                remove_json_path(&mut bundle, &key);
+            }
+        }
+        if navigate_json_path(&bundle, "paths").is_some_and(|paths| paths == &json!({})) {
+            remove_json_path(&mut bundle, "paths");
+        }
+    }
+
+    bundle
+}
+
+/// Apply a bundle to the JSON form of a config, returning the new config
+///
+/// Bundles from older versions are migrated first. Template repositories
+/// keep the local `allow_hooks` setting only if they still point at the same
+/// repository on the same host; others don't allow hooks.
+/// Fails if the bundle contains settings that aren't shared (see
+/// [`BUNDLE_KEYS`]) or if the result isn't a valid config.
+pub fn import_bundle(config: &Value, bundle: Value, mode: ImportMode) -> Result<Value> {
+    if !bundle.is_object() {
+        return Err(anyhow!("A config bundle must be a table"));
+    }
+    let mut bundle = migrations::migrate(bundle, &chrono::Utc::now().to_rfc3339())?.value;
+    remove_json_path(&mut bundle, "metadata");
+
+    if let Some(key) = leaf_keys(&bundle, "").into_iter().find(|key| {
+        !BUNDLE_KEYS
+            .iter()
+            .any(|section| is_in_section(key, section))
+    }) {
+        return Err(anyhow!(
+            "'{}' can't be imported. A bundle may contain: {}",
+            key,
+            BUNDLE_KEYS.join(", ")
+        ));
+    }
+
+    let mut result = config.clone();
+    for key in BUNDLE_KEYS {
+        let Some(imported) = navigate_json_path(&bundle, key) else {
+            continue;
+        };
+
+        let value = match (mode, navigate_json_path(&result, key)) {
+            (ImportMode::Merge, Some(Value::Array(current)))
+                if *key == "templates.custom_repositories" =>
+            {
+                merge_repositories(current, imported)
+            }
+            (ImportMode::Merge, Some(current)) => {
+                let mut merged = current.clone();
+                merge_values(&mut merged, imported.clone());
+                merged
+            }
+            _ => imported.clone(),
+        };
+        set_json_path(&mut result, key, value)?;
+    }
+    keep_local_trust(config, &mut result);
+
+    let problems = schema::check_config(&result);
+    if !problems.is_empty() {
+        return Err(anyhow!(
+            "The bundle contains invalid settings:\n  {}",
+            problems.join("\n  ")
+        ));
+    }
+
+    Ok(result)
+}
+
+fn is_in_section(key: &str, section: &str) -> bool {
+    key == section
+        || key
+            .strip_prefix(section)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// Set `allow_hooks` of every repository in `result` to its value in
+/// `config`, if `config` has a repository with the same name, repository,
+/// provider and base URL; otherwise to `false`
+fn keep_local_trust(config: &Value, result: &mut Value) {
+    let parse =
+        |repository: &Value| serde_json::from_value::<TemplateRepository>(repository.clone()).ok();
+    let local: Vec<TemplateRepository> =
+        navigate_json_path(config, "templates.custom_repositories")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(parse)
+            .collect();
+    let Some(Value::Array(repositories)) = result.pointer_mut("/templates/custom_repositories")
+    else {
+        return;
+    };
+
+    for repository in repositories.iter_mut() {
+        let allowed = parse(repository).is_some_and(|imported| {
+            local.iter().any(|existing| {
+                existing.name == imported.name
+                    && existing.allow_hooks
+                    && InstallSource::of(existing).matches(&imported)
+            })
+        });
+        if let Some(repository) = repository.as_object_mut() {
+            repository.insert(TRUST_KEY.to_string(), Value::Bool(allowed));
+        }
+    }
+}
+
+/// Template repositories of both lists; imported ones replace those with the
+/// same name
+fn merge_repositories(current: &[Value], imported: &Value) -> Value {
+    let imported = imported.as_array().cloned().unwrap_or_default();
+    let name = |repository: &Value| repository.get("name").cloned();
+
+    let mut repositories: Vec<Value> = current
+        .iter()
+        .map(|repository| {
+            imported
+                .iter()
+                .find(|new| name(new) == name(repository))
+                .unwrap_or(repository)
+                .clone()
+        })
+        .collect();
+    for repository in imported {
+        if !current
+            .iter()
+            .any(|existing| name(existing) == name(&repository))
+        {
+            repositories.push(repository);
+        }
+    }
+
+    Value::Array(repositories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn config() -> Value {
+        let mut config = serde_json::to_value(Config::default()).unwrap();
+        config["author"] = json!("Jane Doe");
+        config["courses"] = json!({ "02101": "Introduction to Programming" });
+        config["templates"]["custom_repositories"] =
+            json!([{ "name": "dtu", "repository": "owner/dtu-template" }]);
+        config
+    }
+
+    #[test]
+    fn test_export_bundle() {
+        let bundle = export_bundle(&config(), false);
+        assert_eq!(bundle["courses"]["02101"], "Introduction to Programming");
+        assert_eq!(bundle["templates"]["custom_repositories"][0]["name"], "dtu");
+        assert!(bundle["paths"]["notes_dir"].is_string());
+        assert!(bundle.get("author").is_none());
+        assert!(bundle["templates"].get("local_packages").is_none());
+
+        let redacted = export_bundle(&config(), true);
+        assert!(redacted.get("paths").is_none());
+        assert!(redacted["typst"].get("output_dir").is_none());
+        assert_eq!(redacted["courses"], bundle["courses"]);
+    }
+
+    #[test]
+    fn test_import_bundle() {
+        let bundle = json!({
+            "courses": { "02102": "Algorithms and Data Structures" },
+            "templates": {
+                "custom_repositories": [
+                    { "name": "dtu", "repository": "team/dtu-template" },
+                    { "name": "team", "repository": "team/templates" }
+                ]
+            }
+        });
+
+        let merged = import_bundle(&config(), bundle.clone(), ImportMode::Merge).unwrap();
+        assert_eq!(merged["courses"].as_object().unwrap().len(), 2);
+        let repositories = merged["templates"]["custom_repositories"]
+            .as_array()
+            .unwrap();
+        assert_eq!(repositories.len(), 2);
+        assert_eq!(repositories[0]["repository"], "team/dtu-template");
+        assert_eq!(merged["author"], "Jane Doe");
+
+        let replaced = import_bundle(&config(), bundle, ImportMode::Replace).unwrap();
+        assert_eq!(
+            replaced["courses"],
+            json!({ "02102": "Algorithms and Data Structures" })
+        );
+        assert_eq!(replaced["paths"], config()["paths"]);
+
+        // Personal settings and invalid values are rejected
+        assert!(import_bundle(&config(), json!({ "author": "Eve" }), ImportMode::Merge).is_err());
+        let invalid = json!({ "search": { "max_results": 0 } });
+        assert!(import_bundle(&config(), invalid, ImportMode::Merge).is_err());
+    }
+
+    #[test]
+    fn test_hook_trust_is_local() {
+        let mut local = config();
+        local["templates"]["custom_repositories"][0]["allow_hooks"] = json!(true);
+        let bundle = export_bundle(&local, false);
+        assert!(
+            bundle["templates"]["custom_repositories"][0]
+                .get("allow_hooks")
+                .is_none()
+        );
+
+        let bundle = json!({
+            "templates": {
+                "custom_repositories": [
+                    { "name": "dtu", "repository": "team/dtu-template", "allow_hooks": false },
+                    { "name": "team", "repository": "team/templates", "allow_hooks": true }
+                ]
+            }
+        });
+        // `dtu` now points at another repository, so it isn't trusted
+        for mode in [ImportMode::Merge, ImportMode::Replace] {
+            let imported = import_bundle(&local, bundle.clone(), mode).unwrap();
+            let repositories = &imported["templates"]["custom_repositories"];
+            assert_eq!(repositories[0]["allow_hooks"], false);
+            assert_eq!(repositories[1]["allow_hooks"], false);
+        }
+
+        // The same repository keeps the local setting, unless its host changed
+        let same = json!({
+            "templates": {
+                "custom_repositories": [
+                    { "name": "dtu", "repository": "owner/dtu-template", "allow_hooks": false }
+                ]
+            }
+        });
+        let imported = import_bundle(&local, same.clone(), ImportMode::Merge).unwrap();
+        assert_eq!(
+            imported["templates"]["custom_repositories"][0]["allow_hooks"],
+            true
+        );
+
+        let mut moved = same;
+        moved["templates"]["custom_repositories"][0]["provider"] = json!("gitea");
+        let imported = import_bundle(&local, moved, ImportMode::Replace).unwrap();
+        assert_eq!(
+            imported["templates"]["custom_repositories"][0]["allow_hooks"],
+            false
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let bundle = export_bundle(&config(), true);
+        let fresh = serde_json::to_value(Config::default()).unwrap();
+        let imported = import_bundle(&fresh, bundle, ImportMode::Merge).unwrap();
+
+        assert_eq!(imported["courses"]["02101"], "Introduction to Programming");
+        assert_eq!(
+            imported["templates"]["custom_repositories"],
+            json!([{ "name": "dtu", "repository": "owner/dtu-template", "allow_hooks": false }])
+        );
+        assert_eq!(imported["paths"], fresh["paths"]);
+        assert_eq!(imported["author"], fresh["author"]);
+    }
+}
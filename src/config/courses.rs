@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Per-course settings that take precedence over the global preferences and
+/// the template package defaults
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct CourseOverrides {
+    /// Variant used when none is given, per template (e.g. "note" = "math")
+    pub variants: BTreeMap<String, String>,
+
+    /// Sections used instead of the template's default sections, per template
+    pub sections: BTreeMap<String, Vec<String>>,
+
+    /// Title used when none is given, with {{var}} placeholders (e.g. "Week {{week}}")
+    pub title_format: Option<String>,
+
+    /// Co-authors added after the author
+    pub co_authors: Vec<String>,
+
+    /// Group whose members are the authors, unless `--group` is given
+    pub group: Option<String>,
+
+    /// Extra template parameters, like `--var key=value`
+    pub parameters: BTreeMap<String, String>,
+}
+
+impl CourseOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
@@ -0,0 +1,154 @@
+//! Dot-notation access to configuration values
+//!
+//! Keys like `paths.notes_dir` address values in the JSON form of the config.
+//! Used by `noter config get/set` and for overrides from the environment and
+//! the command line.
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::config::schema;
+
+/// Navigate JSON value using dot notation path
+pub fn navigate_json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let parts: Vec<&str> = path.split('.').collect();
+    let mut current = value;
+
+    for part in parts {
+        match current {
+            Value::Object(map) => {
+                current = map.get(part)?;
+            }
+            _ => return None,
+        }
+    }
+
+    Some(current)
+}
+
+/// Update JSON value using dot notation path
+///
+/// The value is parsed and validated according to the config schema. Entries
+/// of tables keyed by the user (e.g. `courses.02101`) are created if missing.
+pub fn update_json_path(value: &mut Value, path: &str, new_value: &str) -> Result<()> {
+    let parsed_value = match schema::find_key(path) {
+        Some(config_key) => config_key.parse(path, new_value)?,
+        None => match table_entry(path).map(|table| &table.value_type) {
+            // Entries of string tables such as template parameters are kept as text
+            Some(schema::ValueType::StringTable) => Value::String(new_value.to_string()),
+            // Free-form tables take any JSON value
+            Some(_) => serde_json::from_str(new_value)
+                .unwrap_or_else(|_| Value::String(new_value.to_string())),
+            None => return Err(schema::lookup_key(path).unwrap_err()),
+        },
+    };
+
+    set_json_path(value, path, parsed_value)
+}
+
+/// The schema entry of the table a key lies within, for keys like
+/// `course_overrides.02101.parameters.semester`
+fn table_entry(path: &str) -> Option<&'static schema::ConfigKey> {
+    let parts: Vec<&str> = path.split('.').collect();
+    (1..parts.len()).rev().find_map(|len| {
+        schema::find_key(&parts[..len].join(".")).filter(|config_key| {
+            matches!(
+                config_key.value_type,
+                schema::ValueType::Table | schema::ValueType::StringTable
+            )
+        })
+    })
+}
+
+/// Set a JSON value at a dot notation path, creating missing tables on the way
+pub fn set_json_path(value: &mut Value, path: &str, new_value: Value) -> Result<()> {
+    let parts: Vec<&str> = path.split('.').collect();
+    let (last_key, parents) = parts
+        .split_last()
+        .filter(|(last_key, _)| !last_key.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Invalid key path"))?;
+
+    let mut current = value;
+
+    // Navigate to the parent of the target
+    for part in parents {
+        match current {
+            Value::Object(map) => {
+                current = map
+                    .entry(part.to_string())
+                    .or_insert_with(|| Value::Object(Default::default()));
+            }
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Cannot navigate through non-object at '{}'",
+                    part
+                ));
+            }
+        }
+    }
+
+    match current {
+        Value::Object(map) => {
+            map.insert(last_key.to_string(), new_value);
+            Ok(())
+        }
+        _ => Err(anyhow::anyhow!(
+            "Cannot set value on non-object at '{}'",
+            last_key
+        )),
+    }
+}
+
+/// Remove the value at a dot notation path, if it exists
+pub fn remove_json_path(value: &mut Value, path: &str) -> Option<Value> {
+    let (parent, last_key) = match path.rsplit_once('.') {
+        Some((parent, last_key)) => (navigate_json_path_mut(value, parent)?, last_key),
+        None => (value, path),
+    };
+    parent.as_object_mut()?.shift_remove(last_key)
+}
+
+fn navigate_json_path_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.').try_fold(value, |current, part| {
+        current.as_object_mut()?.get_mut(part)
+    })
+}
+
+/// Dot paths of all values in a table, descending into nested tables
+pub fn leaf_keys(value: &Value, prefix: &str) -> Vec<String> {
+    match value {
+        Value::Object(map) if !map.is_empty() || prefix.is_empty() => map
+            .iter()
+            .flat_map(|(key, value)| {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                leaf_keys(value, &path)
+            })
+            .collect(),
+        _ => vec![prefix.to_string()],
+    }
+}
+
+/// A changed value between two JSON values: key, old value, new value
+pub type ValueChange = (String, Option<Value>, Option<Value>);
+
+/// The values that differ between two JSON values, by dot path
+pub fn changes(before: &Value, after: &Value) -> Vec<ValueChange> {
+    let mut keys = leaf_keys(before, "");
+    for key in leaf_keys(after, "") {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let old = navigate_json_path(before, &key).cloned();
+            let new = navigate_json_path(after, &key).cloned();
+            (old != new).then_some((key, old, new))
+        })
+        .collect()
+}
@@ -0,0 +1,780 @@
+//! Layered configuration files
+//!
+//! The configuration is merged from several layers, later layers taking
+//! precedence:
+//!
+//! 1. Built-in defaults
+//! 2. The global config file (`~/.config/noter/config.toml` or `config.json`)
+//! 3. The active profile from the global file (`--profile <name>` or
+//!    `metadata.active_profile`), which may set `author`, `paths`, `courses`
+//!    and `templates`
+//! 4. The workspace file `.noter.toml`, found by walking up from the current directory
+//! 5. `NOTER_<SECTION>__<KEY>` environment variables
+//! 6. `--set key=value` on the command line
+//!
+//! Files only need to contain the keys they change. Tables are merged key by
+//! key; values and arrays replace those of earlier layers. Environment and
+//! command-line overrides apply to a single run and are never saved.
+
+use anyhow::{Context, Result, anyhow};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use super::Config;
+use super::json_path::{
+    leaf_keys, navigate_json_path, remove_json_path, set_json_path, update_json_path,
+};
+use super::profiles::{PROFILE_REMOVED_KEY, PROFILE_SECTIONS};
+use super::schema;
+
+/// File name of the per-workspace config file
+pub const WORKSPACE_CONFIG_FILE: &str = ".noter.toml";
+
+/// Prefix of environment variables that override config keys
+pub const ENV_PREFIX: &str = "NOTER_";
+
+/// Separates the parts of a key in environment variable names (`NOTER_PATHS__NOTES_DIR`)
+const ENV_KEY_SEPARATOR: &str = "__";
+
+/// Variables noter uses for other purposes (processing hooks, tests), which are not overrides
+const RESERVED_ENV_VARS: &[&str] = &["NOTER_HOOK_STAGE", "NOTER_UPDATE_GOLDEN"];
+
+/// Set by the global `--set` flag; never written to the config file
+static COMMAND_LINE_OVERRIDES: RwLock<Vec<(String, String)>> = RwLock::new(Vec::new());
+
+/// Overrides from environment variables, read once per process so problems
+/// with them are reported once rather than on every load of the config
+static ENV_OVERRIDES: OnceLock<Vec<(ConfigLayer, String, String)>> = OnceLock::new();
+
+/// Set by the global `--profile` flag; takes precedence over `metadata.active_profile`
+static PROFILE_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Keys under `paths` that are resolved relative to the workspace file
+const PATH_KEYS: &[&str] = &[
+    "notes_dir",
+    "obsidian_dir",
+    "templates_dir",
+    "typst_packages_dir",
+];
+
+/// A configuration layer, in order of precedence
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigLayer {
+    Default,
+    Global(PathBuf),
+    /// A profile from the global config file, by name
+    Profile(String),
+    Workspace(PathBuf),
+    /// An environment variable, by name
+    Environment(String),
+    /// A `--set` flag, by key
+    CommandLine(String),
+}
+
+impl ConfigLayer {
+    /// Short name of the layer
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigLayer::Default => "default",
+            ConfigLayer::Global(_) => "global",
+            ConfigLayer::Profile(_) => "profile",
+            ConfigLayer::Workspace(_) => "workspace",
+            ConfigLayer::Environment(_) => "environment",
+            ConfigLayer::CommandLine(_) => "command line",
+        }
+    }
+
+    /// Label for values from this layer: the variable or flag for overrides
+    pub fn label(&self) -> String {
+        match self {
+            ConfigLayer::Environment(variable) => variable.clone(),
+            ConfigLayer::CommandLine(_) => "--set".to_string(),
+            ConfigLayer::Profile(name) => format!("profile {}", name),
+            _ => self.name().to_string(),
+        }
+    }
+
+    /// File the layer was read from
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ConfigLayer::Global(path) | ConfigLayer::Workspace(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Whether the layer overrides the config for this run only
+    pub fn is_override(&self) -> bool {
+        matches!(
+            self,
+            ConfigLayer::Environment(_) | ConfigLayer::CommandLine(_)
+        )
+    }
+}
+
+impl fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigLayer::Environment(variable) => write!(f, "{} ({})", self.name(), variable),
+            ConfigLayer::CommandLine(key) => write!(f, "{} (--set {})", self.name(), key),
+            ConfigLayer::Profile(name) => write!(f, "{} ({})", self.name(), name),
+            _ => match self.path() {
+                Some(path) => write!(f, "{} ({})", self.name(), path.display()),
+                None => write!(f, "{}", self.name()),
+            },
+        }
+    }
+}
+
+/// Override config values for this process with `--set key=value` pairs
+pub fn set_command_line_overrides(overrides: Vec<(String, String)>) {
+    if let Ok(mut current) = COMMAND_LINE_OVERRIDES.write() {
+        *current = overrides;
+    }
+}
+
+/// Use a profile for this process instead of `metadata.active_profile`
+pub fn set_profile_override(profile: Option<String>) {
+    if let Ok(mut current) = PROFILE_OVERRIDE.write() {
+        *current = profile;
+    }
+}
+
+/// The config layers found for the current directory, with their contents
+#[derive(Debug, Clone)]
+pub struct ConfigLayers {
+    files: Vec<(ConfigLayer, Value)>,
+}
+
+impl ConfigLayers {
+    /// Read the config files for the current directory and apply the
+    /// environment and command-line overrides
+    pub fn discover() -> Result<Self> {
+        let mut layers = Self::discover_files()?;
+
+        let mut overrides = ENV_OVERRIDES
+            .get_or_init(|| env_overrides(std::env::vars()))
+            .clone();
+        if let Ok(command_line) = COMMAND_LINE_OVERRIDES.read() {
+            overrides.extend(command_line.iter().map(|(key, value)| {
+                (
+                    ConfigLayer::CommandLine(key.clone()),
+                    key.clone(),
+                    value.clone(),
+                )
+            }));
+        }
+        layers.add_overrides(overrides)?;
+
+        Ok(layers)
+    }
+
+    /// Read the global config file and the workspace file for the current directory
+    fn discover_files() -> Result<Self> {
+        let mut files = Vec::new();
+
+        let global_path = Config::config_file_path()?;
+        let global = if global_path.exists() {
+            read_config_file(&global_path)?
+        } else {
+            Value::Object(Map::new())
+        };
+
+        let profile = active_profile(&global)?;
+        if global_path.exists() {
+            files.push((ConfigLayer::Global(global_path), global));
+        }
+        if let Some((name, value)) = profile {
+            files.push((ConfigLayer::Profile(name), value));
+        }
+
+        if let Some(workspace_path) = find_workspace_config(&std::env::current_dir()?) {
+            let mut value = read_config_file(&workspace_path)?;
+            if let Some(dir) = workspace_path.parent() {
+                resolve_workspace_paths(&mut value, dir);
+            }
+            files.push((ConfigLayer::Workspace(workspace_path), value));
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Add overrides of single keys, checked against the type of the value they replace
+    fn add_overrides(&mut self, overrides: Vec<(ConfigLayer, String, String)>) -> Result<()> {
+        if overrides.is_empty() {
+            return Ok(());
+        }
+
+        let mut current = serde_json::to_value(self.to_config()?)?;
+        for (layer, key, raw) in overrides {
+            update_json_path(&mut current, &key, &raw)
+                .with_context(|| format!("Invalid config override from {}", layer))?;
+            let value = navigate_json_path(&current, &key)
+                .cloned()
+                .unwrap_or(Value::Null);
+            self.files.push((layer, nest_value(&key, value)));
+        }
+
+        // Catch values the field's type rejects, e.g. unknown enum variants
+        self.to_config()?;
+        Ok(())
+    }
+
+    /// The layers in order of precedence, starting with the defaults
+    pub fn layers(&self) -> Vec<&ConfigLayer> {
+        std::iter::once(&ConfigLayer::Default)
+            .chain(self.files.iter().map(|(layer, _)| layer))
+            .collect()
+    }
+
+    /// Contents of the global config file, if it exists
+    pub fn global(&self) -> Option<&Value> {
+        self.files
+            .iter()
+            .find(|(layer, _)| matches!(layer, ConfigLayer::Global(_)))
+            .map(|(_, value)| value)
+    }
+
+    /// Name of the profile applied on top of the global file, if any
+    pub fn profile(&self) -> Option<&str> {
+        self.files.iter().find_map(|(layer, _)| match layer {
+            ConfigLayer::Profile(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Merge the files into one config; missing keys get their defaults
+    pub fn to_config(&self) -> Result<Config> {
+        let mut merged = Value::Object(Map::new());
+        for (layer, value) in &self.files {
+            match layer {
+                ConfigLayer::Profile(_) => apply_profile(&mut merged, value),
+                _ => merge_values(&mut merged, value.clone()),
+            }
+        }
+
+        serde_json::from_value(merged).map_err(|e| {
+            // Name the file that doesn't fit the config structure
+            let culprit = self
+                .files
+                .iter()
+                .find(|(_, value)| serde_json::from_value::<Config>(value.clone()).is_err());
+            match culprit {
+                Some((layer, _)) if layer.is_override() => {
+                    anyhow!("Invalid config override from {}: {}", layer, e)
+                }
+                Some((layer, _)) => anyhow!("Invalid configuration in {}: {}", layer, e),
+                None => anyhow!("Invalid configuration: {}", e),
+            }
+        })
+    }
+
+    /// The layer each key of the merged config comes from, keyed by dot path
+    ///
+    /// Tables are broken down into their keys; arrays count as one value.
+    /// Keys not set by any file come from the defaults.
+    pub fn origins(&self, config: &Config) -> Result<BTreeMap<String, ConfigLayer>> {
+        let mut origins = BTreeMap::new();
+        for key in leaf_keys(&serde_json::to_value(config)?, "") {
+            origins.insert(key, ConfigLayer::Default);
+        }
+
+        for (layer, value) in &self.files {
+            let (value, _) = split_removed_keys(value);
+            for key in leaf_keys(&value, "") {
+                origins.insert(key, layer.clone());
+            }
+        }
+
+        Ok(origins)
+    }
+}
+
+/// The profile to apply and its settings, from `--profile` or `metadata.active_profile`
+///
+/// An unknown `--profile` is an error; an unknown active profile is ignored
+/// (`noter config check` warns about it).
+fn active_profile(global: &Value) -> Result<Option<(String, Value)>> {
+    let profiles = global.get("profiles").and_then(Value::as_object);
+    let profile = |name: &str| profiles.and_then(|profiles| profiles.get(name)).cloned();
+
+    let flag = PROFILE_OVERRIDE.read().ok().and_then(|name| name.clone());
+    if let Some(name) = flag {
+        let value = profile(&name).ok_or_else(|| match profiles {
+            Some(profiles) if !profiles.is_empty() => anyhow!(
+                "Profile '{}' not found. Available profiles: {}",
+                name,
+                profiles.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+            _ => anyhow!(
+                "Profile '{}' not found. Create it with 'noter config profile create {}'",
+                name,
+                name
+            ),
+        })?;
+        return Ok(Some((name, value)));
+    }
+
+    let name = navigate_json_path(global, "metadata.active_profile").and_then(Value::as_str);
+    Ok(name.and_then(|name| Some((name.to_string(), profile(name)?))))
+}
+
+/// Overrides from `NOTER_<SECTION>__<KEY>` environment variables, sorted by name
+fn env_overrides(
+    variables: impl Iterator<Item = (String, String)>,
+) -> Vec<(ConfigLayer, String, String)> {
+    let mut overrides: Vec<_> = variables
+        .filter(|(name, _)| !RESERVED_ENV_VARS.contains(&name.as_str()))
+        .filter_map(|(name, value)| {
+            let key = env_var_key(&name)?;
+            if schema::find_key(&key).is_none() {
+                eprintln!(
+                    "Warning: ignoring {}, '{}' is not a configuration key",
+                    name, key
+                );
+                return None;
+            }
+            Some((ConfigLayer::Environment(name), key, value))
+        })
+        .collect();
+    overrides.sort_by(|a, b| a.1.cmp(&b.1));
+    overrides
+}
+
+/// The config key of an environment variable: `NOTER_PATHS__NOTES_DIR` is `paths.notes_dir`
+pub fn env_var_key(name: &str) -> Option<String> {
+    let key = name.strip_prefix(ENV_PREFIX)?;
+    if key.is_empty() {
+        return None;
+    }
+
+    Some(
+        key.split(ENV_KEY_SEPARATOR)
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join("."),
+    )
+}
+
+/// A table containing only `value` at the dot path `key`
+fn nest_value(key: &str, value: Value) -> Value {
+    key.rsplit('.').fold(value, |value, part| {
+        let mut map = Map::new();
+        map.insert(part.to_string(), value);
+        Value::Object(map)
+    })
+}
+
+/// Find `.noter.toml` in `start` or one of its parent directories
+pub fn find_workspace_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(WORKSPACE_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Whether a config file is TOML (otherwise it is JSON)
+pub fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "toml")
+}
+
+/// Read a JSON or TOML config file, depending on its extension
+pub fn read_config_file(path: &Path) -> Result<Value> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    parse_config(path, &content)
+}
+
+/// Parse config file content as JSON or TOML, depending on the file's extension
+pub fn parse_config(path: &Path, content: &str) -> Result<Value> {
+    if is_toml(path) {
+        toml::from_str(content).with_context(|| format!("Invalid TOML in {}", path.display()))
+    } else {
+        serde_json::from_str(content).with_context(|| format!("Invalid JSON in {}", path.display()))
+    }
+}
+
+/// Format a config value for a JSON or TOML file, depending on its extension
+pub fn format_config(path: &Path, value: &Value) -> Result<String> {
+    if is_toml(path) {
+        // TOML has no null; unset options are left out
+        let mut value = value.clone();
+        remove_nulls(&mut value);
+        toml::to_string_pretty(&value).context("Failed to format config as TOML")
+    } else {
+        Ok(serde_json::to_string_pretty(value)?)
+    }
+}
+
+/// Merge `overlay` into `base`: tables key by key, everything else replaced
+pub fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Merge a profile into a config and remove the keys the profile hides
+pub fn apply_profile(base: &mut Value, profile: &Value) {
+    let (overrides, removed) = split_removed_keys(profile);
+    merge_values(base, overrides);
+    for key in removed {
+        remove_json_path(base, &key);
+    }
+}
+
+/// A profile's overrides and the keys it hides
+fn split_removed_keys(profile: &Value) -> (Value, Vec<String>) {
+    let mut overrides = profile.clone();
+    let removed = overrides
+        .as_object_mut()
+        .and_then(|profile| profile.shift_remove(PROFILE_REMOVED_KEY))
+        .and_then(|removed| serde_json::from_value(removed).ok())
+        .unwrap_or_default();
+    (overrides, removed)
+}
+
+/// Apply the changes between `before` and `after` to `target`
+///
+/// Keys that didn't change are left as they are in `target`, so values from
+/// other layers are not copied into it. Keys removed in `after` are removed.
+/// Tables `target` doesn't have yet are written whole.
+pub fn apply_changes(target: &mut Value, before: &Value, after: &Value) {
+    apply_changes_to(target, before, after, true);
+}
+
+/// Apply the changes between `before` and `after` to the global config file
+///
+/// With an active profile, changes to the sections a profile can override go
+/// to that profile, and only the changed keys are written to it. Keys removed
+/// from those sections stay in the base config; the profile lists them as
+/// `removed`, which hides them while it is active.
+pub fn apply_global_changes(
+    global: &mut Value,
+    profile: Option<&str>,
+    before: &Value,
+    after: &Value,
+) -> Result<()> {
+    let Some(profile) = profile else {
+        apply_changes(global, before, after);
+        return Ok(());
+    };
+
+    let (before_base, before_profile) = split_profile_sections(before);
+    let (after_base, after_profile) = split_profile_sections(after);
+    apply_changes(global, &before_base, &after_base);
+    if before_profile == after_profile {
+        return Ok(());
+    }
+
+    let current = navigate_json_path(global, &format!("profiles.{}", profile))
+        .cloned()
+        .unwrap_or_else(|| Value::Object(Map::new()));
+    let (mut target, mut removed) = split_removed_keys(&current);
+    apply_changes_to(&mut target, &before_profile, &after_profile, false);
+
+    // Keys set again are shown again, and removed keys of the base config hidden
+    removed.retain(|key| navigate_json_path(&after_profile, key).is_none());
+    for key in leaf_keys(&before_profile, "") {
+        if navigate_json_path(&after_profile, &key).is_none()
+            && navigate_json_path(global, &key).is_some()
+            && !removed.contains(&key)
+        {
+            removed.push(key);
+        }
+    }
+    if !removed.is_empty() {
+        set_json_path(
+            &mut target,
+            PROFILE_REMOVED_KEY,
+            serde_json::to_value(removed)?,
+        )?;
+    }
+
+    set_json_path(global, &format!("profiles.{}", profile), target)
+}
+
+/// Split a config into the sections a profile can't and can override
+fn split_profile_sections(config: &Value) -> (Value, Value) {
+    let mut base = config.as_object().cloned().unwrap_or_default();
+    let mut sections = Map::new();
+    for section in PROFILE_SECTIONS {
+        if let Some(value) = base.shift_remove(*section) {
+            sections.insert(section.to_string(), value);
+        }
+    }
+    (Value::Object(base), Value::Object(sections))
+}
+
+fn apply_changes_to(target: &mut Value, before: &Value, after: &Value, whole_tables: bool) {
+    if before == after {
+        return;
+    }
+
+    let (Value::Object(before), Value::Object(after)) = (before, after) else {
+        *target = after.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        unreachable!()
+    };
+
+    for (key, value) in after {
+        match (before.get(key), target.get_mut(key)) {
+            (Some(old), _) if old == value => {}
+            (Some(old), Some(existing)) => apply_changes_to(existing, old, value, whole_tables),
+            (Some(old @ Value::Object(_)), None) if !whole_tables && value.is_object() => {
+                let mut table = Value::Object(Map::new());
+                apply_changes_to(&mut table, old, value, whole_tables);
+                target.insert(key.clone(), table);
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+
+    for key in before.keys() {
+        if !after.contains_key(key) {
+            target.remove(key);
+        }
+    }
+}
+
+/// Make relative `paths.*` values of a workspace file relative to its directory
+fn resolve_workspace_paths(value: &mut Value, dir: &Path) {
+    let Some(Value::Object(paths)) = value.get_mut("paths") else {
+        return;
+    };
+
+    for key in PATH_KEYS {
+        if let Some(Value::String(path)) = paths.get_mut(*key)
+            && Path::new(path.as_str()).is_relative()
+        {
+            *path = dir.join(&*path).to_string_lossy().to_string();
+        }
+    }
+}
+
+fn remove_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, value| !value.is_null());
+            map.values_mut().for_each(remove_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_values() {
+        let mut base = json!({
+            "author": "Ada",
+            "courses": { "02101": "Programming" },
+            "search": { "file_extensions": ["typ", "md"] }
+        });
+        merge_values(
+            &mut base,
+            json!({
+                "courses": { "01005": "Math" },
+                "search": { "file_extensions": ["typ"] }
+            }),
+        );
+
+        assert_eq!(
+            base,
+            json!({
+                "author": "Ada",
+                "courses": { "02101": "Programming", "01005": "Math" },
+                "search": { "file_extensions": ["typ"] }
+            })
+        );
+    }
+
+    #[test]
+    fn test_apply_changes_keeps_other_layers_out() {
+        // The global file sets the author and one course; a workspace adds a course
+        let mut global = json!({ "author": "Ada", "courses": { "02101": "Programming" } });
+        let before = json!({
+            "author": "Ada",
+            "courses": { "02101": "Programming", "01005": "Math" },
+            "search": { "max_results": 50 }
+        });
+        let after = json!({
+            "author": "Ada Lovelace",
+            "courses": { "01005": "Math" },
+            "search": { "max_results": 50 }
+        });
+
+        apply_changes(&mut global, &before, &after);
+        assert_eq!(global, json!({ "author": "Ada Lovelace", "courses": {} }));
+    }
+
+    #[test]
+    fn test_profile_changes_go_to_the_profile() {
+        let mut global = json!({
+            "author": "Ada",
+            "paths": { "notes_dir": "/notes", "templates_dir": "/templates" },
+            "courses": { "02101": "Programming" },
+            "profiles": { "msc": { "author": "Ada L." } }
+        });
+        let before = json!({
+            "author": "Ada L.",
+            "paths": { "notes_dir": "/notes", "templates_dir": "/templates" },
+            "courses": { "02101": "Programming" },
+            "search": { "max_results": 50 }
+        });
+        let after = json!({
+            "author": "Ada L.",
+            "paths": { "notes_dir": "/msc", "templates_dir": "/templates" },
+            "courses": { "02456": "Deep Learning" },
+            "search": { "max_results": 10 }
+        });
+        apply_global_changes(&mut global, Some("msc"), &before, &after).unwrap();
+
+        assert_eq!(
+            global,
+            json!({
+                "author": "Ada",
+                "paths": { "notes_dir": "/notes", "templates_dir": "/templates" },
+                "courses": { "02101": "Programming" },
+                "profiles": {
+                    "msc": {
+                        "author": "Ada L.",
+                        "paths": { "notes_dir": "/msc" },
+                        "courses": { "02456": "Deep Learning" },
+                        "removed": ["courses.02101"]
+                    }
+                },
+                "search": { "max_results": 10 }
+            })
+        );
+
+        // The removed course stays hidden while the profile is active
+        let mut merged = global.clone();
+        apply_profile(&mut merged, &global["profiles"]["msc"]);
+        assert_eq!(merged["courses"], json!({ "02456": "Deep Learning" }));
+        assert!(merged.get("removed").is_none());
+
+        // Adding it again shows it again
+        let before = after.clone();
+        let mut after = after;
+        after["courses"]["02101"] = json!("Programming 2");
+        apply_global_changes(&mut global, Some("msc"), &before, &after).unwrap();
+        assert_eq!(
+            global["profiles"]["msc"]["courses"],
+            json!({ "02456": "Deep Learning", "02101": "Programming 2" })
+        );
+        assert!(global["profiles"]["msc"].get("removed").is_none());
+    }
+
+    #[test]
+    fn test_workspace_config_discovery() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let nested = dir.path().join("course").join("week1");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            dir.path().join(WORKSPACE_CONFIG_FILE),
+            "author = \"Ada\"\n[paths]\nnotes_dir = \"notes\"\n",
+        )
+        .unwrap();
+
+        let path = find_workspace_config(&nested).unwrap();
+        assert_eq!(path, dir.path().join(WORKSPACE_CONFIG_FILE));
+
+        let mut value = read_config_file(&path).unwrap();
+        resolve_workspace_paths(&mut value, dir.path());
+        assert_eq!(value["author"], "Ada");
+        assert_eq!(
+            value["paths"]["notes_dir"],
+            dir.path().join("notes").to_string_lossy().as_ref()
+        );
+    }
+
+    #[test]
+    fn test_env_var_key() {
+        assert_eq!(env_var_key("NOTER_AUTHOR").as_deref(), Some("author"));
+        assert_eq!(
+            env_var_key("NOTER_PATHS__NOTES_DIR").as_deref(),
+            Some("paths.notes_dir")
+        );
+        assert_eq!(env_var_key("NOTER_"), None);
+        assert_eq!(env_var_key("EDITOR"), None);
+
+        let overrides = env_overrides(
+            [
+                ("NOTER_HOOK_STAGE".to_string(), "pre".to_string()),
+                ("NOTER_UPDATE_GOLDEN".to_string(), "1".to_string()),
+                ("NOTER_UNKNOWN_SETTING".to_string(), "x".to_string()),
+                ("NOTER_AUTHOR".to_string(), "Ada".to_string()),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].1, "author");
+    }
+
+    #[test]
+    fn test_overrides_are_type_checked() {
+        let layers = || ConfigLayers {
+            files: vec![(
+                ConfigLayer::Global(PathBuf::from("config.json")),
+                json!({ "author": "Ada" }),
+            )],
+        };
+        let set = |key: &str, value: &str| {
+            let mut layers = layers();
+            layers
+                .add_overrides(vec![(
+                    ConfigLayer::CommandLine(key.to_string()),
+                    key.to_string(),
+                    value.to_string(),
+                )])
+                .map(|()| layers)
+        };
+
+        let overridden = set("search.max_results", "10").unwrap();
+        let config = overridden.to_config().unwrap();
+        assert_eq!(config.search.max_results, 10);
+        assert_eq!(config.author, "Ada");
+        assert!(overridden.origins(&config).unwrap()["search.max_results"].is_override());
+
+        assert!(set("search.max_results", "many").is_err());
+        assert!(set("note_preferences.auto_open_file", "maybe").is_err());
+        assert!(set("semester_format", "Weekly").is_err());
+        assert!(set("no_such_key", "1").is_err());
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let path = Path::new("config.toml");
+        let value = serde_json::to_value(Config::default()).unwrap();
+        let content = format_config(path, &value).unwrap();
+
+        let parsed: Config = serde_json::from_value(parse_config(path, &content).unwrap()).unwrap();
+        assert_eq!(parsed.author, Config::default().author);
+        assert_eq!(parsed.courses, Config::default().courses);
+    }
+}
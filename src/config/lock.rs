@@ -0,0 +1,108 @@
+//! Advisory lock on the global config
+//!
+//! Commands that change the config hold the lock from loading the config
+//! until it is saved (see [`Config::update`]), so concurrent noter processes
+//! don't overwrite each other's changes. The lock is taken on a separate
+//! `config.lock` file, since saving replaces the config file itself.
+
+use anyhow::{Context, Result, anyhow};
+use std::cell::Cell;
+use std::fs::{self, File, TryLockError};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::Config;
+
+/// File name of the lock file, next to the global config file
+const LOCK_FILE: &str = "config.lock";
+
+/// How long to wait for another process to release the lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+thread_local! {
+    /// Number of nested locks held by this thread
+    static LOCK_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A held lock on the global config, released when dropped
+pub struct ConfigLock {
+    /// The locked file; `None` for nested locks. Closing it releases the lock.
+    _file: Option<File>,
+}
+
+impl ConfigLock {
+    /// Lock the global config, waiting for other noter processes to finish
+    ///
+    /// Locking again while this thread holds the lock succeeds at once.
+    pub fn acquire() -> Result<Self> {
+        let depth = LOCK_DEPTH.get();
+        if depth > 0 {
+            LOCK_DEPTH.set(depth + 1);
+            return Ok(Self { _file: None });
+        }
+
+        let config_dir = Config::config_dir()?;
+        fs::create_dir_all(&config_dir)?;
+        let file = lock_file(&config_dir.join(LOCK_FILE), LOCK_TIMEOUT)?;
+
+        LOCK_DEPTH.set(1);
+        Ok(Self { _file: Some(file) })
+    }
+}
+
+/// Open and lock `path`, retrying until `timeout` has passed
+fn lock_file(path: &Path, timeout: Duration) -> Result<File> {
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+    let start = Instant::now();
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(file),
+            Err(TryLockError::WouldBlock) if start.elapsed() < timeout => {
+                thread::sleep(RETRY_INTERVAL);
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(anyhow!(
+                    "The config is in use by another noter process. Try again when it has finished (lock file: {})",
+                    path.display()
+                ));
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e).with_context(|| format!("Failed to lock {}", path.display()));
+            }
+        }
+    }
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        LOCK_DEPTH.set(LOCK_DEPTH.get().saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(LOCK_FILE);
+
+        let held = lock_file(&path, Duration::ZERO).unwrap();
+        let error = lock_file(&path, Duration::from_millis(100)).unwrap_err();
+        assert!(error.to_string().contains("another noter process"));
+
+        drop(held);
+        assert!(lock_file(&path, Duration::ZERO).is_ok());
+    }
+}
@@ -4,11 +4,13 @@ use serde::{Deserialize, Serialize};
 /// Current config version - increment when making breaking changes
 ///
 /// When you modify the Config struct in a breaking way (rename fields, change types, etc.),
-/// increment this version and add corresponding migration logic in `migrate()`.
+/// increment this version and add a migration step to `migrations::MIGRATIONS`.
 ///
 /// Version history:
+/// - `0.3.0`: DTU Notes config (`_metadata`, `template_repositories`, see `docs/config-example.json`)
 /// - `1.0.0`: Initial versioned config with automatic migration system
-pub const CONFIG_VERSION: &str = "1.0.0";
+/// - `1.1.0`: Typed config schema; `search.file_extensions` are written without the dot
+pub const CONFIG_VERSION: &str = "1.1.0";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Metadata {
@@ -16,6 +18,10 @@ pub struct Metadata {
     pub created_at: String,
     pub last_updated: String,
     pub migration_notes: String,
+
+    /// Profile applied on top of the config, set by `noter config profile switch`
+    #[serde(default)]
+    pub active_profile: Option<String>,
 }
 
 impl Default for Metadata {
@@ -26,6 +32,7 @@ impl Default for Metadata {
             created_at: now.clone(),
             last_updated: now,
             migration_notes: String::new(),
+            active_profile: None,
         }
     }
 }
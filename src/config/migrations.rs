@@ -0,0 +1,348 @@
+//! Config file migrations
+//!
+//! Each [`Migration`] upgrades the JSON form of a config file from one
+//! version to the next. Migrations are chained until the file reaches
+//! [`CONFIG_VERSION`], so a config from any known version can be loaded.
+//!
+//! To change the config format in a breaking way:
+//! 1. Bump `CONFIG_VERSION` in `metadata.rs`
+//! 2. Add a step from the previous version to [`MIGRATIONS`]
+//! 3. Add a golden file for the old shape in `tests/fixtures/config/`
+
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value, json};
+
+use super::json_path::navigate_json_path;
+use super::metadata::CONFIG_VERSION;
+
+/// A step that upgrades a config file from one version to another
+pub struct Migration {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub description: &'static str,
+    apply: fn(&mut Map<String, Value>),
+}
+
+/// All migration steps; each `from` version appears once
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: "0.0.0",
+        to: "1.0.0",
+        description: "Start tracking the config version",
+        apply: |_| {},
+    },
+    Migration {
+        from: "0.3.0",
+        to: "1.0.0",
+        description: "Convert the DTU Notes config: rename paths.obsidian_vault_dir, \
+                      move template_repositories to templates.custom_repositories and \
+                      drop the unused compilation, UI and advanced settings",
+        apply: migrate_dtu_notes_config,
+    },
+    Migration {
+        from: "1.0.0",
+        to: "1.1.0",
+        description: "Remove leading dots from search.file_extensions",
+        apply: strip_extension_dots,
+    },
+];
+
+/// The result of migrating a config file
+pub struct MigrationOutcome {
+    /// The migrated config file
+    pub value: Value,
+    /// The steps that were applied, in order
+    pub steps: Vec<&'static Migration>,
+}
+
+/// Version of a config file
+///
+/// Files without a version are treated as current, since hand-written
+/// files only need to contain the keys they change.
+pub fn file_version(value: &Value) -> String {
+    let version = navigate_json_path(value, "metadata.config_version")
+        .or_else(|| navigate_json_path(value, "_metadata.config_version"))
+        .and_then(Value::as_str);
+
+    match version {
+        Some("") => "0.0.0".to_string(),
+        Some(version) => version.to_string(),
+        None => CONFIG_VERSION.to_string(),
+    }
+}
+
+/// Check if a config file needs migration
+pub fn needs_migration(value: &Value) -> bool {
+    file_version(value) != CONFIG_VERSION
+}
+
+/// Fail if a config file was written by a newer version of noter
+///
+/// Such a file can't be migrated, and rewriting it would lose the settings
+/// this version doesn't know.
+pub fn check_not_newer(value: &Value) -> Result<()> {
+    let version = file_version(value);
+    let parse = |version: &str| semver::Version::parse(version).ok();
+
+    match (parse(&version), parse(CONFIG_VERSION)) {
+        (Some(file), Some(current)) if file > current => Err(anyhow!(
+            "The config was written by a newer version of noter (config version {}, this version reads up to {}). Update noter to use it",
+            version,
+            CONFIG_VERSION
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Migrate a config file to the current version
+///
+/// `now` is the timestamp recorded as `metadata.last_updated`.
+pub fn migrate(mut value: Value, now: &str) -> Result<MigrationOutcome> {
+    check_not_newer(&value)?;
+    let mut version = file_version(&value);
+    let mut steps = Vec::new();
+
+    while version != CONFIG_VERSION {
+        let step = MIGRATIONS
+            .iter()
+            .find(|step| step.from == version)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown config version {}; this version of noter can migrate from {}",
+                    version,
+                    MIGRATIONS
+                        .iter()
+                        .map(|step| step.from)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })?;
+
+        let map = value
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("Config file is not a table"))?;
+        (step.apply)(map);
+
+        version = step.to.to_string();
+        steps.push(step);
+    }
+
+    if let (false, Some(map)) = (steps.is_empty(), value.as_object_mut()) {
+        let metadata = map
+            .entry("metadata")
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("'metadata' is not a table"))?;
+
+        let from = steps[0].from;
+        metadata.insert("config_version".to_string(), json!(CONFIG_VERSION));
+        if metadata
+            .get("created_at")
+            .and_then(Value::as_str)
+            .is_none_or(str::is_empty)
+        {
+            metadata.insert("created_at".to_string(), json!(now));
+        }
+        metadata.insert("last_updated".to_string(), json!(now));
+        metadata.insert(
+            "migration_notes".to_string(),
+            json!(format!("Migrated from {} to {}", from, CONFIG_VERSION)),
+        );
+    }
+
+    Ok(MigrationOutcome { value, steps })
+}
+
+/// 0.3.0 → 1.0.0: the config format of DTU Notes (see `docs/config-example.json`)
+fn migrate_dtu_notes_config(config: &mut Map<String, Value>) {
+    remove_comments(config);
+
+    rename_key(config, "_metadata", "metadata");
+    if let Some(metadata) = table(config, "metadata") {
+        rename_key(metadata, "last_modified", "last_updated");
+    }
+
+    if let Some(paths) = table(config, "paths") {
+        rename_key(paths, "obsidian_vault_dir", "obsidian_dir");
+    }
+
+    if let Some(note_preferences) = table(config, "note_preferences") {
+        rename_key(
+            note_preferences,
+            "auto_open_after_creation",
+            "auto_open_file",
+        );
+        note_preferences.shift_remove("default_template_type");
+    }
+
+    // "name": "owner/repo" pairs become template repositories
+    if let Some(Value::Object(repositories)) = config.shift_remove("template_repositories") {
+        let repositories: Vec<Value> = repositories
+            .into_iter()
+            .filter(|(name, _)| !name.starts_with('_'))
+            .filter_map(|(name, repository)| {
+                let repository = repository.as_str()?;
+                Some(json!({ "name": name, "repository": repository, "enabled": true }))
+            })
+            .collect();
+        if !repositories.is_empty() {
+            section(config, "templates").insert(
+                "custom_repositories".to_string(),
+                Value::Array(repositories),
+            );
+        }
+    }
+
+    if let Some(Value::Object(compilation)) = config.shift_remove("compilation_settings")
+        && let Some(clean) = compilation.get("clean_on_compile")
+    {
+        section(config, "typst").insert("clean_before_compile".to_string(), clean.clone());
+    }
+
+    if let Some(Value::Object(advanced)) = config.shift_remove("advanced_settings") {
+        if let Some(caching) = advanced.get("template_cache_enabled") {
+            section(config, "templates").insert("enable_caching".to_string(), caching.clone());
+        }
+        if let Some(backups) = advanced.get("file_backup_enabled") {
+            section(config, "note_preferences")
+                .insert("create_backups".to_string(), backups.clone());
+        }
+    }
+
+    config.shift_remove("ui_preferences");
+}
+
+/// 1.0.0 → 1.1.0: extensions are written without the dot since the config schema
+fn strip_extension_dots(config: &mut Map<String, Value>) {
+    let Some(Value::Array(extensions)) =
+        table(config, "search").and_then(|search| search.get_mut("file_extensions"))
+    else {
+        return;
+    };
+
+    for extension in extensions {
+        if let Value::String(text) = extension {
+            *text = text.trim_start_matches('.').to_string();
+        }
+    }
+}
+
+/// Remove `_comment` and `_description` keys at any depth
+fn remove_comments(config: &mut Map<String, Value>) {
+    config.retain(|key, _| key != "_comment" && key != "_description");
+    for value in config.values_mut() {
+        if let Value::Object(table) = value {
+            remove_comments(table);
+        }
+    }
+}
+
+fn table<'a>(config: &'a mut Map<String, Value>, name: &str) -> Option<&'a mut Map<String, Value>> {
+    config.get_mut(name).and_then(Value::as_object_mut)
+}
+
+/// A table of the config, created (or replacing a non-table value) if needed
+fn section<'a>(config: &'a mut Map<String, Value>, name: &str) -> &'a mut Map<String, Value> {
+    let section = config.entry(name).or_insert_with(|| json!({}));
+    if !section.is_object() {
+        *section = json!({});
+    }
+    section.as_object_mut().expect("section is a table")
+}
+
+/// Rename a key, keeping its position in the file
+fn rename_key(map: &mut Map<String, Value>, from: &str, to: &str) {
+    if map.contains_key(from) {
+        *map = std::mem::take(map)
+            .into_iter()
+            .map(|(key, value)| {
+                let key = if key == from { to.to_string() } else { key };
+                (key, value)
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::fs;
+    use std::path::PathBuf;
+
+    const NOW: &str = "2026-01-01T00:00:00+00:00";
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/config")
+            .join(name)
+    }
+
+    /// Migrate `input` and compare with the golden file `expected`
+    ///
+    /// Set `NOTER_UPDATE_GOLDEN=1` to rewrite the golden file instead.
+    fn assert_golden(input: PathBuf, expected: &str) {
+        let value: Value = serde_json::from_str(&fs::read_to_string(&input).unwrap()).unwrap();
+        let outcome = migrate(value, NOW).unwrap();
+        let migrated = serde_json::to_string_pretty(&outcome.value).unwrap() + "\n";
+
+        let expected = fixture(expected);
+        if std::env::var_os("NOTER_UPDATE_GOLDEN").is_some() {
+            fs::write(&expected, &migrated).unwrap();
+        }
+        assert_eq!(
+            migrated,
+            fs::read_to_string(&expected).unwrap(),
+            "{} doesn't match {}",
+            input.display(),
+            expected.display()
+        );
+
+        // The migrated file loads and is current
+        let config: Config = serde_json::from_value(outcome.value).unwrap();
+        assert_eq!(config.metadata.config_version, CONFIG_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_dtu_notes_example() {
+        let input = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("docs/config-example.json");
+        assert_golden(input, "0.3.0.migrated.json");
+    }
+
+    #[test]
+    fn test_migrate_unversioned() {
+        assert_golden(fixture("0.0.0.json"), "0.0.0.migrated.json");
+    }
+
+    #[test]
+    fn test_migrate_1_0_0() {
+        assert_golden(fixture("1.0.0.json"), "1.0.0.migrated.json");
+    }
+
+    #[test]
+    fn test_migration_chain() {
+        let value = json!({ "_metadata": { "config_version": "0.3.0" } });
+        let outcome = migrate(value, NOW).unwrap();
+        let versions: Vec<_> = outcome.steps.iter().map(|step| step.to).collect();
+        assert_eq!(versions, ["1.0.0", "1.1.0"]);
+
+        // Current and unversioned files are left alone
+        let current = json!({ "author": "Jane" });
+        assert!(!needs_migration(&current));
+        assert!(migrate(current, NOW).unwrap().steps.is_empty());
+
+        let newer = json!({ "metadata": { "config_version": "9.0.0" } });
+        let error = migrate(newer.clone(), NOW).err().unwrap();
+        assert!(error.to_string().contains("newer version of noter"));
+        assert!(check_not_newer(&newer).is_err());
+        assert!(check_not_newer(&json!({ "metadata": { "config_version": "1.0.0" } })).is_ok());
+    }
+
+    #[test]
+    fn test_every_version_reaches_current() {
+        for step in MIGRATIONS {
+            let value = json!({ "metadata": { "config_version": step.from } });
+            assert!(migrate(value, NOW).is_ok(), "no path from {}", step.from);
+        }
+    }
+}
@@ -4,11 +4,20 @@
 //! validation, and migration. Configuration is split across multiple submodules
 //! for better organization.
 
+pub mod authors;
+pub mod bundle;
+pub mod courses;
 pub mod integrations;
+pub mod json_path;
+pub mod layers;
+pub mod lock;
 pub mod metadata;
+pub mod migrations;
 pub mod paths;
 pub mod persistence;
 pub mod preferences;
+pub mod profiles;
+pub mod schema;
 pub mod search;
 pub mod semester;
 pub mod templates;
@@ -20,13 +29,20 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 // Re-export commonly used types
+pub use authors::Author;
+pub use courses::CourseOverrides;
 pub use integrations::ObsidianIntegrationConfig;
+pub use layers::ConfigLayer;
 pub use metadata::Metadata;
 pub use paths::PathConfig;
 pub use preferences::NotePreferences;
+pub use profiles::Profile;
 pub use search::SearchConfig;
 pub use semester::SemesterFormat;
-pub use templates::{TemplateRepository, UserTemplateConfig};
+pub use templates::{
+    AutoUpdatePolicy, InstallSource, LocalTemplatePackage, TemplateProvider, TemplateRepository,
+    UserTemplateConfig,
+};
 pub use typst::TypstConfig;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -35,6 +51,9 @@ pub struct Config {
     /// User's name for templates
     pub author: String,
 
+    /// User's DTU study number (e.g. s234567)
+    pub student_id: Option<String>,
+
     /// Preferred text editor
     pub preferred_editor: Option<String>,
 
@@ -62,9 +81,18 @@ pub struct Config {
     /// User's DTU courses
     pub courses: HashMap<String, String>,
 
+    /// Per-course template settings, keyed by course code
+    pub course_overrides: HashMap<String, CourseOverrides>,
+
+    /// Project groups, keyed by name, with their members
+    pub groups: HashMap<String, Vec<Author>>,
+
     /// Obsidian integration settings
     pub obsidian_integration: ObsidianIntegrationConfig,
 
+    /// Named profiles applied on top of this config (`noter --profile <name>`)
+    pub profiles: HashMap<String, Profile>,
+
     /// Metadata (Not used by user)
     pub metadata: Metadata,
 }
@@ -91,6 +119,7 @@ impl Default for Config {
 
         Self {
             author: "Your Name".to_string(),
+            student_id: None,
             preferred_editor: None,
             template_version: env!("CARGO_PKG_VERSION").to_string(),
             semester_format: SemesterFormat::default(),
@@ -100,7 +129,10 @@ impl Default for Config {
             typst: TypstConfig::default(),
             search: SearchConfig::default(),
             courses: default_courses,
+            course_overrides: HashMap::new(),
+            groups: HashMap::new(),
             obsidian_integration: ObsidianIntegrationConfig::default(),
+            profiles: HashMap::new(),
             metadata: Metadata::default(),
         }
     }
@@ -108,15 +140,13 @@ impl Default for Config {
 
 impl Config {
     /// Update author name
-    pub fn set_author(&mut self, author: String) -> Result<()> {
+    pub fn set_author(&mut self, author: String) {
         self.author = author;
-        self.save()
     }
 
     /// Update preferred editor
-    pub fn set_editor(&mut self, editor: Option<String>) -> Result<()> {
+    pub fn set_editor(&mut self, editor: Option<String>) {
         self.preferred_editor = editor;
-        self.save()
     }
 
     /// Get formatted semester string
@@ -140,16 +170,15 @@ impl Config {
     }
 
     /// Add a course
-    pub fn add_course(&mut self, course_id: String, course_name: String) -> Result<()> {
+    pub fn add_course(&mut self, course_id: String, course_name: String) {
         self.courses.insert(course_id, course_name);
-        self.save()
     }
 
     /// Remove a course
-    pub fn remove_course(&mut self, course_id: &str) -> Result<bool> {
+    pub fn remove_course(&mut self, course_id: &str) -> bool {
         let removed = self.courses.remove(course_id).is_some();
-        self.save()?;
-        Ok(removed)
+        self.course_overrides.remove(course_id);
+        removed
     }
 
     /// Get course name
@@ -157,6 +186,14 @@ impl Config {
         self.courses.get(course_id).cloned().unwrap_or_default()
     }
 
+    /// Template settings for a course (empty if it has none)
+    pub fn course_overrides(&self, course_id: &str) -> CourseOverrides {
+        self.course_overrides
+            .get(course_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// List all courses
     pub fn list_courses(&self) -> Vec<(String, String)> {
         let mut courses: Vec<(String, String)> = self
@@ -207,14 +244,18 @@ pub fn get_config() -> Result<Config> {
 
 /// Helper function to update author
 pub fn update_author(new_author: String) -> Result<()> {
-    let mut config = Config::load()?;
-    config.set_author(new_author)
+    Config::update(|config| {
+        config.set_author(new_author);
+        Ok(())
+    })
 }
 
 /// Helper function to update editor
 pub fn update_editor(new_editor: Option<String>) -> Result<()> {
-    let mut config = Config::load()?;
-    config.set_editor(new_editor)
+    Config::update(|config| {
+        config.set_editor(new_editor);
+        Ok(())
+    })
 }
 
 #[cfg(test)]
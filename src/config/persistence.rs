@@ -1,20 +1,27 @@
 use anyhow::{Context, Result};
-use serde_json;
+use serde_json::{self, Value};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::Config;
+use super::layers::{self, ConfigLayers};
+use super::lock::ConfigLock;
 use super::metadata::CONFIG_VERSION;
+use super::migrations::{self, MigrationOutcome};
+use crate::core::files::FileOperations;
 
 impl Config {
     /// Load configuration from file or create default with automatic migration
     ///
     /// This method handles three scenarios:
     /// 1. **No config exists**: Creates a new default config
-    /// 2. **Compatible config**: Loads and potentially migrates to current version
+    /// 2. **Compatible config**: Loads and, if it is from an older version,
+    ///    migrates it to the current version (see [`migrations`])
     /// 3. **Incompatible config**: Creates backup and recovers what it can
     ///
     /// The migration happens automatically and transparently to the user.
+    /// The global config is then merged with the workspace's `.noter.toml`,
+    /// if there is one (see [`ConfigLayers`]).
     ///
     /// # Returns
     ///
@@ -30,53 +37,99 @@ impl Config {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn load() -> Result<Self> {
+        Ok(Self::load_layers()?.0)
+    }
+
+    /// Load the configuration together with the layers it was merged from
+    pub fn load_layers() -> Result<(Self, ConfigLayers)> {
         let config_path = Self::config_file_path()?;
 
-        let mut config = if config_path.exists() {
+        if config_path.exists() {
             let content = fs::read_to_string(&config_path)?;
 
+            // A newer config can't be migrated, and recovering it would
+            // overwrite the settings this version doesn't know
+            let parsed = layers::parse_config(&config_path, &content);
+            if let Ok(value) = &parsed {
+                migrations::check_not_newer(value)?;
+            }
+
             // Try to load with lenient deserialization (serde(default) helps here)
-            match serde_json::from_str::<Config>(&content) {
-                Ok(mut config) => {
-                    // Check if migration is needed
-                    if Self::needs_migration(&config) {
-                        eprintln!("Config format has changed. Migrating...");
-                        config = Self::migrate(config)?;
-                        config.save()?;
-                        eprintln!("Config migrated successfully!");
-                    }
-                    config
-                }
-                Err(e) => {
-                    eprintln!("Failed to load config: {}", e);
-                    eprintln!("Creating backup and recovering values from old config...");
-
-                    // Backup old config
-                    let backup_path = config_path.with_extension("json.backup");
-                    fs::copy(&config_path, &backup_path)?;
-                    eprintln!("Old config backed up to: {}", backup_path.display());
-
-                    // Try to extract what we can from old config
-                    let config = Self::recover_from_old_config(&content)?;
-                    config.save()?;
-                    eprintln!("New config created with recovered values!");
-                    config
-                }
+            let loaded = parsed.and_then(|value| {
+                let value = Self::migrate_on_load(&config_path, value);
+                Ok(serde_json::from_value::<Config>(value)?)
+            });
+
+            if let Err(e) = loaded {
+                let _lock = ConfigLock::acquire()?;
+                eprintln!("Failed to load config: {:#}", e);
+                eprintln!("Creating backup and recovering values from old config...");
+
+                let backup_path = Self::backup_config_file(&config_path)?;
+                eprintln!("Old config backed up to: {}", backup_path.display());
+
+                // Try to extract what we can from old config
+                let old_value = layers::parse_config(&config_path, &content)?;
+                let config = Self::recover_from_old_config(old_value)?;
+                config.save()?;
+                eprintln!("New config created with recovered values!");
             }
         } else {
             // Create default config and save it
-            let config = Config::default();
-            config.save()?;
-            config
-        };
+            Config::default().save()?;
+        }
+
+        let layers = ConfigLayers::discover()?;
+        let mut config = layers.to_config()?;
 
         // Resolve relative paths to absolute paths
         config.paths.resolve_paths()?;
-        Ok(config)
+        Ok((config, layers))
+    }
+
+    /// Load the configuration, change it and save it
+    ///
+    /// The config lock is held from loading until saving, so noter processes
+    /// running at the same time can't overwrite each other's changes. All
+    /// commands that change the config go through this. Nothing is saved if
+    /// `change` fails or leaves the config as it was.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use noter::config::Config;
+    ///
+    /// Config::update(|config| {
+    ///     config.author = "Jane Doe".to_string();
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn update<T>(change: impl FnOnce(&mut Config) -> Result<T>) -> Result<T> {
+        let _lock = ConfigLock::acquire()?;
+        let mut config = Self::load()?;
+        let before = serde_json::to_value(&config)?;
+
+        let result = change(&mut config)?;
+        if serde_json::to_value(&config)? != before {
+            config.save()?;
+        }
+
+        Ok(result)
     }
 
-    /// Save configuration to file
+    /// Save configuration to the global config file
+    ///
+    /// Only the values that differ from the loaded configuration are written,
+    /// so settings from a workspace `.noter.toml` don't end up in the global
+    /// file and keys the file leaves out keep their defaults. With an active
+    /// profile, changes to the sections it can override are saved to it.
+    ///
+    /// The file is replaced atomically, so it is never left half-written.
+    /// To change the loaded config, use [`Config::update`] instead, which
+    /// keeps other noter processes from changing the file in between.
     pub fn save(&self) -> Result<()> {
+        let _lock = ConfigLock::acquire()?;
         let config_path = Self::config_file_path()?;
 
         // Create config directory if it doesn't exist
@@ -84,19 +137,52 @@ impl Config {
             fs::create_dir_all(parent)?;
         }
 
-        let content = serde_json::to_string_pretty(self)?;
-        fs::write(&config_path, content)?;
+        let value = serde_json::to_value(self)?;
+        let value = match Self::loaded_state() {
+            Some((mut global, profile, loaded)) => {
+                layers::apply_global_changes(&mut global, profile.as_deref(), &loaded, &value)?;
+                global
+            }
+            None => value,
+        };
+
+        let content = layers::format_config(&config_path, &value)?;
+        FileOperations::write_atomically(&config_path, &content)
+    }
 
-        Ok(())
+    /// The global file, the active profile and the merged config as they are
+    /// on disk now, or `None` if there is no global file or it doesn't load
+    fn loaded_state() -> Option<(Value, Option<String>, Value)> {
+        let layers = ConfigLayers::discover().ok()?;
+        let global = layers.global()?.clone();
+        let profile = layers.profile().map(str::to_string);
+        let mut loaded = layers.to_config().ok()?;
+        loaded.paths.resolve_paths().ok()?;
+        Some((global, profile, serde_json::to_value(loaded).ok()?))
     }
 
-    /// Get the path to the config file
+    /// Get the path to the global config file
+    ///
+    /// This is `config.toml` if it exists, otherwise `config.json`.
     pub fn config_file_path() -> Result<PathBuf> {
         let config_dir = dirs::home_dir()
             .map(|h| h.join(".config"))
-            .context("Failed to determine home directory")?;
+            .context("Failed to determine home directory")?
+            .join("noter");
+
+        let toml_path = config_dir.join("config.toml");
+        if toml_path.exists() {
+            return Ok(toml_path);
+        }
+
+        Ok(config_dir.join("config.json"))
+    }
 
-        Ok(config_dir.join("noter").join("config.json"))
+    /// The `config.json` that is ignored because a `config.toml` exists
+    pub fn shadowed_config_file() -> Result<Option<PathBuf>> {
+        let config_path = Self::config_file_path()?;
+        let json_path = config_path.with_file_name("config.json");
+        Ok((layers::is_toml(&config_path) && json_path.exists()).then_some(json_path))
     }
 
     /// Get the config directory path
@@ -105,65 +191,88 @@ impl Config {
         Ok(config_file.parent().unwrap().to_path_buf())
     }
 
-    /// Completely remove configuration file and start fresh
+    /// Completely remove the global configuration files and start fresh
     pub fn cleanse() -> Result<()> {
+        let _lock = ConfigLock::acquire()?;
         let config_path = Self::config_file_path()?;
 
-        if config_path.exists() {
-            fs::remove_file(&config_path)?;
+        for path in [
+            config_path.with_file_name("config.toml"),
+            config_path.with_file_name("config.json"),
+        ] {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
         }
 
         Ok(())
     }
 
-    /// Check if config needs migration based on version
-    ///
-    /// Compares the config's version against the current version constant.
-    /// Returns `true` if migration is needed.
-    fn needs_migration(config: &Config) -> bool {
-        config.metadata.config_version != CONFIG_VERSION
-    }
-
-    /// Migrate config from old version to current version
+    /// Migrate the global config file if it is from an older version
     ///
-    /// Applies version-specific migration logic to update the config structure.
-    /// Each version transition should have its own migration case that handles
-    /// the specific changes made in that version.
-    ///
-    /// # Developer Notes
-    ///
-    /// When adding a new version:
-    /// 1. Update `CONFIG_VERSION` constant
-    /// 2. Add a new match arm for the previous version
-    /// 3. Implement migration logic (field renames, type changes, etc.)
-    /// 4. Update migration_notes with a description of changes
-    fn migrate(mut config: Config) -> Result<Self> {
-        let old_version = config.metadata.config_version.as_str();
+    /// Returns the file as it is afterwards. If the migration fails (e.g. the
+    /// file is from a newer version of noter), it is loaded as it is.
+    fn migrate_on_load(config_path: &Path, value: Value) -> Value {
+        if !migrations::needs_migration(&value) {
+            return value;
+        }
 
-        // Perform version-specific migrations
-        match old_version {
-            "" | "0.0.0" => {
-                // Migration from initial version (no version tracking)
-                config.metadata.migration_notes =
-                    "Migrated from initial version to 1.0.0".to_string();
+        let migrated = ConfigLock::acquire().and_then(|_lock| {
+            // Another noter process may have migrated the file in the meantime
+            let content = fs::read_to_string(config_path)?;
+            let current = layers::parse_config(config_path, &content)?;
+            if !migrations::needs_migration(&current) {
+                return Ok(current);
             }
-            // Add more version-specific migrations as needed
-            // "1.0.0" => {
-            //     // Example: rename field, restructure data, etc.
-            //     config.metadata.migration_notes =
-            //         "Migrated from 1.0.0 to 1.1.0".to_string();
-            // }
-            v => {
-                config.metadata.migration_notes =
-                    format!("Migration from version {} to {}", v, CONFIG_VERSION);
+
+            eprintln!("Config format has changed. Migrating...");
+            let (outcome, backup_path) = Self::write_migration(config_path, current)?;
+            eprintln!("Config migrated successfully!");
+            eprintln!("Old config backed up to: {}", backup_path.display());
+            Ok(outcome.value)
+        });
+
+        match migrated {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("Failed to migrate config: {:#}", e);
+                value
             }
         }
+    }
 
-        // Update version and timestamp
-        config.metadata.config_version = CONFIG_VERSION.to_string();
-        config.metadata.last_updated = chrono::Utc::now().to_rfc3339();
+    /// Migrate a config file to the current version
+    ///
+    /// A timestamped backup of the file is written before it is replaced.
+    /// Returns the migration and the path of the backup.
+    pub fn write_migration(
+        config_path: &Path,
+        value: Value,
+    ) -> Result<(MigrationOutcome, PathBuf)> {
+        let _lock = ConfigLock::acquire()?;
+        let outcome = migrations::migrate(value, &chrono::Utc::now().to_rfc3339())?;
+        let backup_path = Self::backup_config_file(config_path)?;
+
+        let content = layers::format_config(config_path, &outcome.value)?;
+        FileOperations::write_atomically(config_path, &content)?;
+
+        Ok((outcome, backup_path))
+    }
 
-        Ok(config)
+    /// Copy a config file to `<file name>.<timestamp>.backup` next to it
+    fn backup_config_file(config_path: &Path) -> Result<PathBuf> {
+        let file_name = config_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("config.json");
+        let backup_path = config_path.with_file_name(format!(
+            "{}.{}.backup",
+            file_name,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+
+        fs::copy(config_path, &backup_path)?;
+        Ok(backup_path)
     }
 
     /// Recover values from an incompatible old config
@@ -176,9 +285,7 @@ impl Config {
     /// - Required fields are missing
     /// - Field types have changed incompatibly
     /// - Struct layout has changed dramatically
-    fn recover_from_old_config(content: &str) -> Result<Self> {
-        // Parse as generic JSON value to safely extract fields
-        let old_value: serde_json::Value = serde_json::from_str(content)?;
+    fn recover_from_old_config(old_value: Value) -> Result<Self> {
         let mut new_config = Config::default();
 
         // Safely extract old values that are important to preserve
@@ -190,10 +297,10 @@ impl Config {
             new_config.preferred_editor = Some(editor.to_string());
         }
 
-        if let Some(courses) = old_value.get("courses") {
-            if let Ok(courses) = serde_json::from_value(courses.clone()) {
-                new_config.courses = courses;
-            }
+        if let Some(courses) = old_value.get("courses")
+            && let Ok(courses) = serde_json::from_value(courses.clone())
+        {
+            new_config.courses = courses;
         }
 
         if let Some(template_version) = old_value.get("template_version").and_then(|v| v.as_str()) {
@@ -201,40 +308,40 @@ impl Config {
         }
 
         // Try to recover nested structs if they're compatible
-        if let Some(paths) = old_value.get("paths") {
-            if let Ok(paths) = serde_json::from_value(paths.clone()) {
-                new_config.paths = paths;
-            }
+        if let Some(paths) = old_value.get("paths")
+            && let Ok(paths) = serde_json::from_value(paths.clone())
+        {
+            new_config.paths = paths;
         }
 
-        if let Some(note_prefs) = old_value.get("note_preferences") {
-            if let Ok(note_prefs) = serde_json::from_value(note_prefs.clone()) {
-                new_config.note_preferences = note_prefs;
-            }
+        if let Some(note_prefs) = old_value.get("note_preferences")
+            && let Ok(note_prefs) = serde_json::from_value(note_prefs.clone())
+        {
+            new_config.note_preferences = note_prefs;
         }
 
-        if let Some(typst) = old_value.get("typst") {
-            if let Ok(typst) = serde_json::from_value(typst.clone()) {
-                new_config.typst = typst;
-            }
+        if let Some(typst) = old_value.get("typst")
+            && let Ok(typst) = serde_json::from_value(typst.clone())
+        {
+            new_config.typst = typst;
         }
 
-        if let Some(search) = old_value.get("search") {
-            if let Ok(search) = serde_json::from_value(search.clone()) {
-                new_config.search = search;
-            }
+        if let Some(search) = old_value.get("search")
+            && let Ok(search) = serde_json::from_value(search.clone())
+        {
+            new_config.search = search;
         }
 
-        if let Some(templates) = old_value.get("templates") {
-            if let Ok(templates) = serde_json::from_value(templates.clone()) {
-                new_config.templates = templates;
-            }
+        if let Some(templates) = old_value.get("templates")
+            && let Ok(templates) = serde_json::from_value(templates.clone())
+        {
+            new_config.templates = templates;
         }
 
-        if let Some(obsidian) = old_value.get("obsidian_integration") {
-            if let Ok(obsidian) = serde_json::from_value(obsidian.clone()) {
-                new_config.obsidian_integration = obsidian;
-            }
+        if let Some(obsidian) = old_value.get("obsidian_integration")
+            && let Ok(obsidian) = serde_json::from_value(obsidian.clone())
+        {
+            new_config.obsidian_integration = obsidian;
         }
 
         // Set metadata for recovered config
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Sections of the config a profile can override
+pub const PROFILE_SECTIONS: &[&str] = &["author", "paths", "courses", "templates"];
+
+/// Key of a profile listing the base config keys it hides
+pub const PROFILE_REMOVED_KEY: &str = "removed";
+
+/// A named set of settings applied on top of the base config, e.g. for a
+/// second study program or another machine
+///
+/// Only the keys a profile sets are overridden; tables are merged key by key
+/// like the config layers.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Keys of `paths` to override
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub paths: Map<String, Value>,
+
+    /// Courses added to (or renamed from) the base config
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub courses: HashMap<String, String>,
+
+    /// Keys of `templates` to override
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub templates: Map<String, Value>,
+
+    /// Keys of the base config hidden while the profile is active, e.g.
+    /// `courses.02101` after removing that course with the profile active
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+}
+
+impl Profile {
+    /// Short description of what the profile overrides, e.g. "author, 3 courses"
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(author) = &self.author {
+            parts.push(format!("author {}", author));
+        }
+        if !self.paths.is_empty() {
+            parts.push(format!("{} path(s)", self.paths.len()));
+        }
+        if !self.courses.is_empty() {
+            parts.push(format!("{} course(s)", self.courses.len()));
+        }
+        if !self.templates.is_empty() {
+            parts.push(format!("{} template setting(s)", self.templates.len()));
+        }
+        if !self.removed.is_empty() {
+            parts.push(format!("{} removed", self.removed.len()));
+        }
+
+        if parts.is_empty() {
+            "no overrides".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
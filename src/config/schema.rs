@@ -0,0 +1,945 @@
+//! Description of every configuration key
+//!
+//! Each key has a type, a description and optionally a validation rule. The
+//! schema is used to parse values for `noter config set` and overrides, to
+//! check the config in `noter config check`, to list keys in `noter config
+//! list-keys`, and to export a JSON Schema with `noter config schema`.
+//!
+//! Keys are dot paths. `*` matches any name in tables keyed by the user, such
+//! as course codes in `courses.*`.
+
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value, json};
+
+use crate::config::profiles::{PROFILE_REMOVED_KEY, PROFILE_SECTIONS};
+use crate::core::validation::Validator;
+
+/// Type of a configuration value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueType {
+    String,
+    /// A file system path
+    Path,
+    Boolean,
+    /// A whole number of at least `min`
+    Integer {
+        min: u64,
+    },
+    /// One of a fixed set of strings
+    Enum(&'static [&'static str]),
+    /// A list of strings, given as a JSON array or comma-separated
+    StringList,
+    /// The semester format: one of the named formats or `{"Custom": "..."}`
+    SemesterFormat,
+    /// A table, given as a JSON object
+    Table,
+    /// A table of strings keyed by the user; its entries are set as plain text
+    StringTable,
+    /// A list of tables, given as a JSON array
+    TableList,
+}
+
+impl ValueType {
+    /// How the type is described to users
+    pub fn describe(&self) -> String {
+        match self {
+            ValueType::String => "string".to_string(),
+            ValueType::Path => "path".to_string(),
+            ValueType::Boolean => "boolean".to_string(),
+            ValueType::Integer { min: 0 } => "integer".to_string(),
+            ValueType::Integer { min } => format!("integer >= {}", min),
+            ValueType::Enum(values) => values.join(" | "),
+            ValueType::StringList => "list of strings".to_string(),
+            ValueType::SemesterFormat => SEMESTER_FORMATS.join(" | "),
+            ValueType::Table => "table".to_string(),
+            ValueType::StringTable => "table of strings".to_string(),
+            ValueType::TableList => "list of tables".to_string(),
+        }
+    }
+}
+
+/// A validation rule, given the full key and the value
+pub type Validate = fn(&str, &Value) -> Result<(), String>;
+
+/// A configuration key
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigKey {
+    /// Dot path of the key; `*` matches any name
+    pub key: &'static str,
+    pub value_type: ValueType,
+    /// Whether the key may be unset (`null`)
+    pub optional: bool,
+    pub description: &'static str,
+    pub validate: Option<Validate>,
+}
+
+const SEMESTER_FORMATS: &[&str] = &["YearSeason", "SeasonYear", "ShortForm"];
+
+const fn key(key: &'static str, value_type: ValueType, description: &'static str) -> ConfigKey {
+    ConfigKey {
+        key,
+        value_type,
+        optional: false,
+        description,
+        validate: None,
+    }
+}
+
+const fn optional(
+    key: &'static str,
+    value_type: ValueType,
+    description: &'static str,
+) -> ConfigKey {
+    ConfigKey {
+        optional: true,
+        ..self::key(key, value_type, description)
+    }
+}
+
+const fn validated(config_key: ConfigKey, validate: Validate) -> ConfigKey {
+    ConfigKey {
+        validate: Some(validate),
+        ..config_key
+    }
+}
+
+/// All configuration keys
+pub const CONFIG_KEYS: &[ConfigKey] = &[
+    validated(
+        key(
+            "author",
+            ValueType::String,
+            "Your name, used as the author of documents",
+        ),
+        not_empty,
+    ),
+    validated(
+        optional(
+            "student_id",
+            ValueType::String,
+            "Your DTU study number (e.g. s234567)",
+        ),
+        valid_student_id,
+    ),
+    optional(
+        "preferred_editor",
+        ValueType::String,
+        "Command used to open files (e.g. code, nvim)",
+    ),
+    key(
+        "template_version",
+        ValueType::String,
+        "DTU template version to use",
+    ),
+    key(
+        "semester_format",
+        ValueType::SemesterFormat,
+        "How semesters are written; {\"Custom\": \"...\"} for your own format",
+    ),
+    key(
+        "note_preferences.auto_open_file",
+        ValueType::Boolean,
+        "Open new files in the editor",
+    ),
+    key(
+        "note_preferences.auto_open_dir",
+        ValueType::Boolean,
+        "Open the directory of new files",
+    ),
+    key(
+        "note_preferences.include_date_in_title",
+        ValueType::Boolean,
+        "Include the date in default lecture note titles",
+    ),
+    key(
+        "note_preferences.lecture_sections",
+        ValueType::StringList,
+        "Default sections of lecture notes",
+    ),
+    key(
+        "note_preferences.assignment_sections",
+        ValueType::StringList,
+        "Default sections of assignments",
+    ),
+    key(
+        "note_preferences.create_backups",
+        ValueType::Boolean,
+        "Back up existing files instead of refusing to overwrite them",
+    ),
+    validated(
+        key(
+            "paths.notes_dir",
+            ValueType::Path,
+            "Directory notes are created in",
+        ),
+        not_empty,
+    ),
+    validated(
+        key(
+            "paths.obsidian_dir",
+            ValueType::Path,
+            "Obsidian vault directory",
+        ),
+        not_empty,
+    ),
+    validated(
+        key(
+            "paths.templates_dir",
+            ValueType::Path,
+            "Directory for downloaded templates",
+        ),
+        not_empty,
+    ),
+    validated(
+        key(
+            "paths.typst_packages_dir",
+            ValueType::Path,
+            "Directory of local Typst packages",
+        ),
+        not_empty,
+    ),
+    key(
+        "templates.custom_repositories",
+        ValueType::TableList,
+        "Template repositories (use 'noter config add-template-repo')",
+    ),
+    key(
+        "templates.use_official_fallback",
+        ValueType::Boolean,
+        "Fall back to the official DTU template",
+    ),
+    key(
+        "templates.enable_caching",
+        ValueType::Boolean,
+        "Cache downloaded template releases",
+    ),
+    key(
+        "templates.auto_update",
+        ValueType::Boolean,
+        "Check for template updates automatically",
+    ),
+    key(
+        "templates.auto_update_policy",
+        ValueType::Enum(&["notify", "install"]),
+        "What an automatic update check does when a newer release exists",
+    ),
+    key(
+        "templates.update_check_interval_hours",
+        ValueType::Integer { min: 1 },
+        "Minimum hours between automatic update checks",
+    ),
+    key(
+        "templates.keep_versions",
+        ValueType::Integer { min: 1 },
+        "Installed versions kept per package for 'noter template rollback'",
+    ),
+    key(
+        "templates.offline",
+        ValueType::Boolean,
+        "Never contact template repositories; install from the cache",
+    ),
+    key(
+        "templates.preference_order",
+        ValueType::StringList,
+        "Template repositories in order of preference",
+    ),
+    key(
+        "templates.local_packages",
+        ValueType::TableList,
+        "Packages installed from a local directory or archive",
+    ),
+    key(
+        "typst.compile_args",
+        ValueType::StringList,
+        "Extra arguments for 'typst compile'",
+    ),
+    key(
+        "typst.watch_args",
+        ValueType::StringList,
+        "Extra arguments for 'typst watch'",
+    ),
+    key(
+        "typst.clean_before_compile",
+        ValueType::Boolean,
+        "Remove old PDFs before compiling",
+    ),
+    optional(
+        "typst.output_dir",
+        ValueType::Path,
+        "Directory for compiled PDFs, relative to the source",
+    ),
+    key(
+        "search.max_results",
+        ValueType::Integer { min: 1 },
+        "Maximum number of search results",
+    ),
+    key(
+        "search.context_lines",
+        ValueType::Integer { min: 0 },
+        "Lines of context shown around matches",
+    ),
+    key(
+        "search.case_sensitive",
+        ValueType::Boolean,
+        "Match case when searching",
+    ),
+    validated(
+        key(
+            "search.file_extensions",
+            ValueType::StringList,
+            "File extensions to search, without the dot",
+        ),
+        extensions_without_dot,
+    ),
+    validated(
+        key(
+            "courses.*",
+            ValueType::String,
+            "Course name, keyed by course code",
+        ),
+        valid_course,
+    ),
+    validated(
+        key(
+            "course_overrides.*",
+            ValueType::Table,
+            "Template settings of a course, keyed by course code",
+        ),
+        valid_course,
+    ),
+    key(
+        "course_overrides.*.variants",
+        ValueType::StringTable,
+        "Variant per template name",
+    ),
+    key(
+        "course_overrides.*.sections",
+        ValueType::Table,
+        "Sections per template name",
+    ),
+    optional(
+        "course_overrides.*.title_format",
+        ValueType::String,
+        "Title of new documents; may use template variables",
+    ),
+    key(
+        "course_overrides.*.co_authors",
+        ValueType::StringList,
+        "Names added after the author",
+    ),
+    optional(
+        "course_overrides.*.group",
+        ValueType::String,
+        "Group whose members are the authors",
+    ),
+    key(
+        "course_overrides.*.parameters",
+        ValueType::StringTable,
+        "Template parameters, like --var key=value",
+    ),
+    validated(
+        key(
+            "groups.*",
+            ValueType::TableList,
+            "Members of a group: [{\"name\": ..., \"student_id\": ...}]",
+        ),
+        valid_group,
+    ),
+    key(
+        "obsidian_integration.enabled",
+        ValueType::Boolean,
+        "Enable Obsidian integration",
+    ),
+    key(
+        "obsidian_integration.create_course_index",
+        ValueType::Boolean,
+        "Create a course index note",
+    ),
+    key(
+        "obsidian_integration.create_daily_notes",
+        ValueType::Boolean,
+        "Create daily notes (currently unused)",
+    ),
+    optional(
+        "obsidian_integration.vault_structure",
+        ValueType::Table,
+        "Vault structure (currently unused)",
+    ),
+    key(
+        "obsidian_integration.link_format",
+        ValueType::Enum(&["wiki", "markdown"]),
+        "Link format",
+    ),
+    key(
+        "obsidian_integration.tag_format",
+        ValueType::String,
+        "Tag format; may use {{course_id}}",
+    ),
+    key(
+        "metadata.config_version",
+        ValueType::String,
+        "Config format version (managed by noter)",
+    ),
+    key(
+        "metadata.created_at",
+        ValueType::String,
+        "When the config was created (managed by noter)",
+    ),
+    key(
+        "metadata.last_updated",
+        ValueType::String,
+        "When the config was last migrated (managed by noter)",
+    ),
+    key(
+        "metadata.migration_notes",
+        ValueType::String,
+        "Notes from the last migration (managed by noter)",
+    ),
+    optional(
+        "metadata.active_profile",
+        ValueType::String,
+        "Profile applied on top of the config (use 'noter config profile switch')",
+    ),
+    validated(
+        key(
+            "profiles.*",
+            ValueType::Table,
+            "Settings applied on top of the config by 'noter --profile <name>'",
+        ),
+        valid_profile,
+    ),
+];
+
+impl ConfigKey {
+    /// Parse a value given on the command line and validate it
+    pub fn parse(&self, full_key: &str, raw: &str) -> Result<Value> {
+        let value = if self.optional && raw == "null" {
+            Value::Null
+        } else {
+            self.parse_raw(raw)
+                .map_err(|expected| anyhow!("'{}' expects {}, got '{}'", full_key, expected, raw))?
+        };
+
+        self.check(full_key, &value)?;
+        Ok(value)
+    }
+
+    fn parse_raw(&self, raw: &str) -> Result<Value, String> {
+        let expected = || self.expected();
+        match self.value_type {
+            ValueType::String | ValueType::Path => Ok(Value::String(raw.to_string())),
+            ValueType::Boolean => match raw.to_lowercase().as_str() {
+                "true" | "1" | "yes" | "y" => Ok(Value::Bool(true)),
+                "false" | "0" | "no" | "n" => Ok(Value::Bool(false)),
+                _ => Err(expected()),
+            },
+            ValueType::Integer { .. } => raw
+                .trim()
+                .parse::<u64>()
+                .map(|n| json!(n))
+                .map_err(|_| expected()),
+            ValueType::Enum(_) | ValueType::SemesterFormat => Ok(Value::String(raw.to_string())),
+            ValueType::StringList if raw.trim_start().starts_with('[') => {
+                serde_json::from_str(raw).map_err(|_| expected())
+            }
+            ValueType::StringList => Ok(json!(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .collect::<Vec<_>>()
+            )),
+            ValueType::Table | ValueType::StringTable | ValueType::TableList => {
+                serde_json::from_str(raw).map_err(|_| expected())
+            }
+        }
+    }
+
+    /// Check a value against the key's type and validation rule
+    pub fn check(&self, full_key: &str, value: &Value) -> Result<()> {
+        if value.is_null() {
+            if self.optional {
+                return Ok(());
+            }
+            return Err(anyhow!("'{}' must be set", full_key));
+        }
+
+        let valid_type = match self.value_type {
+            ValueType::String | ValueType::Path => value.is_string(),
+            ValueType::Boolean => value.is_boolean(),
+            ValueType::Integer { min } => value.as_u64().is_some_and(|n| n >= min),
+            ValueType::Enum(values) => value.as_str().is_some_and(|v| values.contains(&v)),
+            ValueType::SemesterFormat => match value {
+                Value::String(format) => SEMESTER_FORMATS.contains(&format.as_str()),
+                Value::Object(map) => {
+                    map.len() == 1 && map.get("Custom").is_some_and(Value::is_string)
+                }
+                _ => false,
+            },
+            ValueType::StringList => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(Value::is_string)),
+            ValueType::Table => value.is_object(),
+            ValueType::StringTable => value
+                .as_object()
+                .is_some_and(|map| map.values().all(Value::is_string)),
+            ValueType::TableList => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(Value::is_object)),
+        };
+        if !valid_type {
+            return Err(anyhow!(
+                "'{}' expects {}, got {}",
+                full_key,
+                self.expected(),
+                value
+            ));
+        }
+
+        if let Some(validate) = self.validate {
+            validate(full_key, value)
+                .map_err(|e| anyhow!("Invalid value for '{}': {}", full_key, e))?;
+        }
+        Ok(())
+    }
+
+    /// What the key expects, for error messages
+    fn expected(&self) -> String {
+        match self.value_type {
+            ValueType::Boolean => "true or false".to_string(),
+            ValueType::Integer { min: 0 } => "a whole number".to_string(),
+            ValueType::Integer { min } => format!("a whole number of at least {}", min),
+            ValueType::Enum(values) => format!("one of: {}", values.join(", ")),
+            ValueType::SemesterFormat => format!(
+                "one of: {} (or {{\"Custom\": \"...\"}} in the config file)",
+                SEMESTER_FORMATS.join(", ")
+            ),
+            ValueType::StringList => {
+                "a comma-separated list or a JSON array of strings".to_string()
+            }
+            ValueType::Table => "a JSON object".to_string(),
+            ValueType::StringTable => "a JSON object of strings".to_string(),
+            ValueType::TableList => "a JSON array of objects".to_string(),
+            ValueType::String | ValueType::Path => "a string".to_string(),
+        }
+    }
+
+    /// How the key is shown to users, with `*` named after what it stands for
+    pub fn display_key(&self) -> String {
+        let placeholder = match self.key.split('.').next() {
+            Some("groups") => "<group>",
+            Some("profiles") => "<profile>",
+            _ => "<course code>",
+        };
+        self.key.replace('*', placeholder)
+    }
+}
+
+/// Find the schema entry for a dot path
+pub fn find_key(key: &str) -> Option<&'static ConfigKey> {
+    // Keys in a profile are described by the keys they override
+    if let Some(overridden) = profile_key(key) {
+        return find_key(overridden);
+    }
+
+    let parts: Vec<&str> = key.split('.').collect();
+    CONFIG_KEYS.iter().find(|config_key| {
+        let pattern: Vec<&str> = config_key.key.split('.').collect();
+        pattern.len() == parts.len()
+            && pattern
+                .iter()
+                .zip(&parts)
+                .all(|(pattern, part)| *pattern == "*" || pattern == part)
+    })
+}
+
+/// The key a key inside a profile overrides
+/// (`profiles.bsc.paths.notes_dir` → `paths.notes_dir`)
+fn profile_key(key: &str) -> Option<&str> {
+    let mut parts = key.splitn(3, '.');
+    if parts.next()? != "profiles" {
+        return None;
+    }
+    parts.next()?;
+    let overridden = parts.next()?;
+    let section = overridden.split('.').next()?;
+    PROFILE_SECTIONS.contains(&section).then_some(overridden)
+}
+
+/// Look up a key, with a suggestion if it doesn't exist
+pub fn lookup_key(key: &str) -> Result<&'static ConfigKey> {
+    find_key(key).ok_or_else(|| {
+        let section = key.split('.').next().unwrap_or_default();
+        let similar: Vec<String> = CONFIG_KEYS
+            .iter()
+            .filter(|config_key| config_key.key.split('.').next() == Some(section))
+            .map(ConfigKey::display_key)
+            .collect();
+
+        if similar.is_empty() {
+            anyhow!(
+                "Unknown configuration key '{}'. Run 'noter config list-keys' to see all keys",
+                key
+            )
+        } else {
+            anyhow!(
+                "Unknown configuration key '{}'. Keys in '{}': {}",
+                key,
+                section,
+                similar.join(", ")
+            )
+        }
+    })
+}
+
+/// Check every value of a config against the schema
+///
+/// Returns one message per invalid value. Values without a schema entry
+/// (e.g. keys inside template settings) are not checked.
+pub fn check_config(value: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    check_value(value, "", &mut problems);
+    problems
+}
+
+fn check_value(value: &Value, path: &str, problems: &mut Vec<String>) {
+    if let Some(config_key) = find_key(path)
+        && let Err(e) = config_key.check(path, value)
+    {
+        problems.push(e.to_string());
+        return;
+    }
+
+    if let Value::Object(map) = value {
+        for (key, value) in map {
+            let child = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", path, key)
+            };
+            check_value(value, &child, problems);
+        }
+    }
+}
+
+/// Export the schema as a JSON Schema, for editor completion in `config.json`
+pub fn json_schema() -> Value {
+    let mut root = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "noter configuration",
+        "type": "object",
+        "properties": {
+            "$schema": { "type": "string", "description": "JSON Schema of this file" }
+        },
+    });
+
+    for config_key in CONFIG_KEYS {
+        let mut node = &mut root;
+        for part in config_key.key.split('.') {
+            node = child_schema(node, part);
+        }
+
+        let Value::Object(schema) = node else {
+            continue;
+        };
+        for (name, value) in type_schema(config_key) {
+            schema.insert(name, value);
+        }
+        schema.insert("description".to_string(), json!(config_key.description));
+    }
+
+    // A profile has the same keys as the sections it can override
+    let mut sections: Map<String, Value> = PROFILE_SECTIONS
+        .iter()
+        .filter_map(|section| {
+            let schema = root["properties"].get(*section)?.clone();
+            Some((section.to_string(), schema))
+        })
+        .collect();
+    sections.insert(
+        PROFILE_REMOVED_KEY.to_string(),
+        json!({
+            "type": "array",
+            "items": { "type": "string" },
+            "description": "Keys of the base config hidden while the profile is active"
+        }),
+    );
+    if let Some(Value::Object(profile)) =
+        root.pointer_mut("/properties/profiles/additionalProperties")
+    {
+        profile.insert("properties".to_string(), Value::Object(sections));
+        profile.insert("additionalProperties".to_string(), json!(false));
+    }
+
+    root
+}
+
+/// The schema of a property (`*`: of any property) of an object schema
+fn child_schema<'a>(node: &'a mut Value, part: &str) -> &'a mut Value {
+    let object = node.as_object_mut().expect("schema nodes are objects");
+    object.entry("type").or_insert_with(|| json!("object"));
+
+    if part == "*" {
+        object
+            .entry("additionalProperties")
+            .or_insert_with(|| json!({}))
+    } else {
+        object
+            .entry("properties")
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .expect("properties are objects")
+            .entry(part)
+            .or_insert_with(|| json!({}))
+    }
+}
+
+fn type_schema(config_key: &ConfigKey) -> Map<String, Value> {
+    let schema = match config_key.value_type {
+        ValueType::String | ValueType::Path => json!({ "type": "string" }),
+        ValueType::Boolean => json!({ "type": "boolean" }),
+        ValueType::Integer { min } => json!({ "type": "integer", "minimum": min }),
+        ValueType::Enum(values) => json!({ "enum": values }),
+        ValueType::SemesterFormat => json!({
+            "anyOf": [
+                { "enum": SEMESTER_FORMATS },
+                {
+                    "type": "object",
+                    "properties": { "Custom": { "type": "string" } },
+                    "required": ["Custom"],
+                    "additionalProperties": false
+                }
+            ]
+        }),
+        ValueType::StringList => json!({ "type": "array", "items": { "type": "string" } }),
+        ValueType::Table => json!({ "type": "object" }),
+        ValueType::StringTable => {
+            json!({ "type": "object", "additionalProperties": { "type": "string" } })
+        }
+        ValueType::TableList => json!({ "type": "array", "items": { "type": "object" } }),
+    };
+
+    let mut schema = match schema {
+        Value::Object(map) => map,
+        _ => unreachable!(),
+    };
+    if config_key.optional {
+        let inner = Value::Object(std::mem::take(&mut schema));
+        schema.insert("anyOf".to_string(), json!([inner, { "type": "null" }]));
+    }
+    schema
+}
+
+fn not_empty(_: &str, value: &Value) -> Result<(), String> {
+    match value.as_str() {
+        Some(text) if text.trim().is_empty() => Err("must not be empty".to_string()),
+        _ => Ok(()),
+    }
+}
+
+fn valid_student_id(_: &str, value: &Value) -> Result<(), String> {
+    match value.as_str() {
+        Some(student_id) => Validator::validate_student_id(student_id).map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// The last part of `courses.<code>` and `course_overrides.<code>` is a course code
+fn valid_course(key: &str, _: &Value) -> Result<(), String> {
+    let course_id = key.rsplit('.').next().unwrap_or_default();
+    Validator::validate_course_id(course_id).map_err(|e| e.to_string())
+}
+
+fn extensions_without_dot(_: &str, value: &Value) -> Result<(), String> {
+    let with_dot = value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .find(|extension| extension.starts_with('.'));
+    match with_dot {
+        Some(extension) => Err(format!(
+            "write extensions without the dot ('{}', not '{}')",
+            extension.trim_start_matches('.'),
+            extension
+        )),
+        None => Ok(()),
+    }
+}
+
+fn valid_profile(_: &str, value: &Value) -> Result<(), String> {
+    let unknown = value
+        .as_object()
+        .into_iter()
+        .flat_map(|profile| profile.keys())
+        .find(|section| {
+            !PROFILE_SECTIONS.contains(&section.as_str()) && *section != PROFILE_REMOVED_KEY
+        });
+    if let Some(section) = unknown {
+        return Err(format!(
+            "a profile can't set '{}', only {}",
+            section,
+            PROFILE_SECTIONS.join(", ")
+        ));
+    }
+
+    match value.get(PROFILE_REMOVED_KEY) {
+        Some(Value::Array(keys)) if keys.iter().all(Value::is_string) => Ok(()),
+        Some(_) => Err(format!("'{}' must be a list of keys", PROFILE_REMOVED_KEY)),
+        None => Ok(()),
+    }
+}
+
+fn valid_group(_: &str, value: &Value) -> Result<(), String> {
+    for member in value.as_array().into_iter().flatten() {
+        let name = member
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if name.trim().is_empty() {
+            return Err("every member needs a \"name\"".to_string());
+        }
+        if let Some(student_id) = member.get("student_id").and_then(Value::as_str) {
+            Validator::validate_student_id(student_id).map_err(|e| format!("{}: {}", name, e))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_every_config_key_is_described() {
+        let value = serde_json::to_value(Config::default()).unwrap();
+        let mut missing = Vec::new();
+        collect_missing(&value, "", &mut missing);
+        assert!(missing.is_empty(), "keys without schema: {:?}", missing);
+
+        let unknown: Vec<_> = CONFIG_KEYS
+            .iter()
+            .filter(|config_key| !config_key.key.contains('*'))
+            .filter(|config_key| {
+                crate::config::json_path::navigate_json_path(&value, config_key.key).is_none()
+            })
+            .map(|config_key| config_key.key)
+            .collect();
+        assert!(
+            unknown.is_empty(),
+            "schema keys not in config: {:?}",
+            unknown
+        );
+
+        assert!(check_config(&value).is_empty());
+    }
+
+    fn collect_missing(value: &Value, path: &str, missing: &mut Vec<String>) {
+        match value {
+            Value::Object(map) if find_key(path).is_none() => {
+                for (key, value) in map {
+                    let child = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    collect_missing(value, &child, missing);
+                }
+            }
+            _ if find_key(path).is_none() => missing.push(path.to_string()),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_parse_values() {
+        let max_results = find_key("search.max_results").unwrap();
+        assert_eq!(
+            max_results.parse("search.max_results", "10").unwrap(),
+            json!(10)
+        );
+        assert!(max_results.parse("search.max_results", "0").is_err());
+        assert!(max_results.parse("search.max_results", "many").is_err());
+
+        let policy = find_key("templates.auto_update_policy").unwrap();
+        assert!(
+            policy
+                .parse("templates.auto_update_policy", "install")
+                .is_ok()
+        );
+        let error = policy
+            .parse("templates.auto_update_policy", "sometimes")
+            .unwrap_err();
+        assert!(error.to_string().contains("notify, install"));
+
+        let extensions = find_key("search.file_extensions").unwrap();
+        assert_eq!(
+            extensions
+                .parse("search.file_extensions", "typ, md")
+                .unwrap(),
+            json!(["typ", "md"])
+        );
+        assert!(extensions.parse("search.file_extensions", ".typ").is_err());
+
+        let editor = find_key("preferred_editor").unwrap();
+        assert_eq!(
+            editor.parse("preferred_editor", "null").unwrap(),
+            Value::Null
+        );
+
+        assert!(find_key("courses.02101").is_some());
+        assert!(
+            find_key("courses.02101")
+                .unwrap()
+                .parse("courses.2101x", "Name")
+                .is_err()
+        );
+        assert!(lookup_key("search.max").is_err());
+    }
+
+    #[test]
+    fn test_table_entries() {
+        use crate::config::json_path::update_json_path;
+
+        let mut config = json!({});
+        update_json_path(&mut config, "course_overrides.02101.parameters.week", "3").unwrap();
+        update_json_path(&mut config, "course_overrides.02101.variants.note", "42").unwrap();
+        update_json_path(
+            &mut config,
+            "course_overrides.02101.sections.note",
+            r#"["Intro", "Exercises"]"#,
+        )
+        .unwrap();
+
+        let overrides = &config["course_overrides"]["02101"];
+        assert_eq!(overrides["parameters"]["week"], json!("3"));
+        assert_eq!(overrides["variants"]["note"], json!("42"));
+        assert_eq!(overrides["sections"]["note"], json!(["Intro", "Exercises"]));
+
+        let parameters = find_key("course_overrides.02101.parameters").unwrap();
+        assert!(
+            parameters
+                .parse("course_overrides.02101.parameters", r#"{"week": 3}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let schema = json_schema();
+        let properties = &schema["properties"];
+        assert_eq!(properties["author"]["type"], "string");
+        assert_eq!(
+            properties["search"]["properties"]["max_results"]["minimum"],
+            1
+        );
+        assert_eq!(
+            properties["courses"]["additionalProperties"]["type"],
+            "string"
+        );
+        assert_eq!(
+            properties["course_overrides"]["additionalProperties"]["properties"]["co_authors"]["type"],
+            "array"
+        );
+    }
+}
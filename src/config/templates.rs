@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by the global `--offline` flag; never written to the config file
+static OFFLINE_OVERRIDE: AtomicBool = AtomicBool::new(false);
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(default)]
@@ -15,8 +19,39 @@ pub struct UserTemplateConfig {
     /// Auto-update templates on startup
     pub auto_update: bool,
 
+    /// What an automatic update check does when a newer release exists
+    pub auto_update_policy: AutoUpdatePolicy,
+
+    /// Minimum hours between automatic update checks
+    pub update_check_interval_hours: u64,
+
+    /// Installed versions kept per package for `noter template rollback`
+    pub keep_versions: usize,
+
+    /// Never contact template repositories; install from the download cache
+    pub offline: bool,
+
     /// Template preference order (repository names)
     pub preference_order: Vec<String>,
+
+    /// Packages installed from a local directory or archive
+    pub local_packages: Vec<LocalTemplatePackage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LocalTemplatePackage {
+    /// Typst package name
+    pub name: String,
+
+    /// Installed package version
+    pub version: String,
+
+    /// Directory or archive the package was installed from
+    pub source: String,
+
+    /// Whether the installed package is a symlink to `source`
+    pub linked: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,20 +60,34 @@ pub struct TemplateRepository {
     /// Display name for the repository
     pub name: String,
 
-    /// GitHub repository in format "owner/repo"
+    /// Repository in format "owner/repo" (for the `url` provider: an archive URL,
+    /// optionally containing a `{version}` placeholder)
     pub repository: String,
 
+    /// Hosting service the repository lives on
+    pub provider: TemplateProvider,
+
+    /// Web address of a self-hosted instance, e.g. `https://gitlab.example.com`, for
+    /// every provider (None for the provider's public service)
+    pub base_url: Option<String>,
+
     /// Specific version/tag to use (None for latest)
     pub version: Option<String>,
 
-    /// Branch to use if not using releases
+    /// Branch to track instead of releases
     pub branch: Option<String>,
 
+    /// Commit to install instead of releases (takes precedence over `branch`)
+    pub rev: Option<String>,
+
     /// Subdirectory within the repo containing templates
     pub template_path: Option<String>,
 
     /// Whether this repository is enabled
     pub enabled: bool,
+
+    /// Allow packages from this repository to run external processing hooks
+    pub allow_hooks: bool,
 }
 
 impl Default for TemplateRepository {
@@ -46,10 +95,132 @@ impl Default for TemplateRepository {
         Self {
             name: String::new(),
             repository: String::new(),
+            provider: TemplateProvider::default(),
+            base_url: None,
             version: None,
             branch: None,
+            rev: None,
             template_path: None,
             enabled: true,
+            allow_hooks: false,
+        }
+    }
+}
+
+impl TemplateRepository {
+    /// Branch or commit to install from, if the repository doesn't use releases
+    pub fn git_ref(&self) -> Option<&str> {
+        self.rev.as_deref().or(self.branch.as_deref())
+    }
+}
+
+/// Where a package was installed from, recorded by the installer
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct InstallSource {
+    /// Repository in format "owner/repo" (for the `url` provider: the archive URL)
+    pub repository: String,
+
+    /// Hosting service the repository lives on
+    #[serde(default)]
+    pub provider: TemplateProvider,
+
+    /// Base URL of a self-hosted instance (None for the provider's public service)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+impl InstallSource {
+    pub fn of(repo: &TemplateRepository) -> Self {
+        Self {
+            repository: repo.repository.clone(),
+            provider: repo.provider,
+            base_url: repo.base_url.clone(),
+        }
+    }
+
+    /// Whether this is the same repository on the same host as `repo`
+    pub fn matches(&self, repo: &TemplateRepository) -> bool {
+        self.provider == repo.provider
+            && normalize_repository(&self.repository) == normalize_repository(&repo.repository)
+            && self.base_url.as_deref().map(normalize_base_url)
+                == repo.base_url.as_deref().map(normalize_base_url)
+    }
+}
+
+/// Hosting service a template repository is fetched from
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateProvider {
+    #[default]
+    GitHub,
+    GitLab,
+    #[serde(alias = "forgejo")]
+    Gitea,
+    /// A plain HTTPS archive URL
+    Url,
+}
+
+impl std::fmt::Display for TemplateProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TemplateProvider::GitHub => "github",
+            TemplateProvider::GitLab => "gitlab",
+            TemplateProvider::Gitea => "gitea",
+            TemplateProvider::Url => "url",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for TemplateProvider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "github" => Ok(TemplateProvider::GitHub),
+            "gitlab" => Ok(TemplateProvider::GitLab),
+            "gitea" | "forgejo" => Ok(TemplateProvider::Gitea),
+            "url" => Ok(TemplateProvider::Url),
+            other => Err(anyhow::anyhow!(
+                "Unknown provider '{}'. Use github, gitlab, gitea or url",
+                other
+            )),
+        }
+    }
+}
+
+/// Action taken when an automatic update check finds a newer release
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoUpdatePolicy {
+    /// Print a one-line notice
+    #[default]
+    Notify,
+    /// Install the update before running the command
+    Install,
+}
+
+impl std::fmt::Display for AutoUpdatePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AutoUpdatePolicy::Notify => "notify",
+            AutoUpdatePolicy::Install => "install",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for AutoUpdatePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "notify" => Ok(AutoUpdatePolicy::Notify),
+            "install" => Ok(AutoUpdatePolicy::Install),
+            other => Err(anyhow::anyhow!(
+                "Unknown auto-update policy '{}'. Use notify or install",
+                other
+            )),
         }
     }
 }
@@ -61,7 +232,132 @@ impl Default for UserTemplateConfig {
             use_official_fallback: true,
             enable_caching: true,
             auto_update: false,
+            auto_update_policy: AutoUpdatePolicy::default(),
+            update_check_interval_hours: 24,
+            keep_versions: 3,
+            offline: false,
             preference_order: vec!["official".to_string()],
+            local_packages: Vec::new(),
         }
     }
 }
+
+impl UserTemplateConfig {
+    /// Force offline mode for this process, regardless of the configuration
+    pub fn set_offline_override(offline: bool) {
+        OFFLINE_OVERRIDE.store(offline, Ordering::Relaxed);
+    }
+
+    /// Whether templates must be resolved from the cache without network access
+    pub fn is_offline(&self) -> bool {
+        self.offline || OFFLINE_OVERRIDE.load(Ordering::Relaxed)
+    }
+
+    /// Remember where a locally installed package came from
+    ///
+    /// Replaces any earlier record for the same package and version.
+    pub fn record_local_package(&mut self, package: LocalTemplatePackage) {
+        self.local_packages
+            .retain(|p| !(p.name == package.name && p.version == package.version));
+        self.local_packages.push(package);
+    }
+
+    /// Whether the user allowed external processing hooks for a template package
+    ///
+    /// `installed_from` is the source the installer recorded for the package
+    /// (see `VersionHistory::source`), never what the package declares about
+    /// itself. Its repository, provider and base URL must all match an
+    /// allowed repository.
+    pub fn hooks_allowed_for(&self, installed_from: Option<&InstallSource>) -> bool {
+        let Some(installed_from) = installed_from else {
+            return false;
+        };
+
+        self.custom_repositories
+            .iter()
+            .any(|repo| repo.enabled && repo.allow_hooks && installed_from.matches(repo))
+    }
+}
+
+/// `owner/repo` in a comparable form: lowercase, without a trailing `/` or `.git`
+fn normalize_repository(repository: &str) -> String {
+    let repository = repository.trim().trim_end_matches('/');
+    repository
+        .strip_suffix(".git")
+        .unwrap_or(repository)
+        .to_lowercase()
+}
+
+/// A base URL in a comparable form: lowercase, without a trailing `/`
+fn normalize_base_url(base_url: &str) -> String {
+    base_url.trim().trim_end_matches('/').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hooks_allowed_for() {
+        let mut config = UserTemplateConfig::default();
+        config.custom_repositories.push(TemplateRepository {
+            name: "team".to_string(),
+            repository: "owner/repo".to_string(),
+            provider: TemplateProvider::GitLab,
+            base_url: Some("https://gitlab.example.com".to_string()),
+            allow_hooks: true,
+            ..TemplateRepository::default()
+        });
+        let source = |repository: &str, provider, base_url: Option<&str>| InstallSource {
+            repository: repository.to_string(),
+            provider,
+            base_url: base_url.map(str::to_string),
+        };
+        let host = Some("https://gitlab.example.com");
+
+        assert!(config.hooks_allowed_for(Some(&source(
+            "owner/repo",
+            TemplateProvider::GitLab,
+            host
+        ))));
+        assert!(config.hooks_allowed_for(Some(&source(
+            "Owner/Repo.git/",
+            TemplateProvider::GitLab,
+            Some("https://GitLab.example.com/")
+        ))));
+        assert!(!config.hooks_allowed_for(Some(&source(
+            "evilowner/repo",
+            TemplateProvider::GitLab,
+            host
+        ))));
+        assert!(!config.hooks_allowed_for(Some(&source(
+            "owner/repo2",
+            TemplateProvider::GitLab,
+            host
+        ))));
+        // The same repository on another host or provider isn't trusted
+        assert!(!config.hooks_allowed_for(Some(&source(
+            "owner/repo",
+            TemplateProvider::GitLab,
+            None
+        ))));
+        assert!(!config.hooks_allowed_for(Some(&source(
+            "owner/repo",
+            TemplateProvider::GitLab,
+            Some("https://gitlab.evil.com")
+        ))));
+        assert!(!config.hooks_allowed_for(Some(&source(
+            "owner/repo",
+            TemplateProvider::Gitea,
+            host
+        ))));
+        assert!(!config.hooks_allowed_for(None));
+
+        config.custom_repositories[0].allow_hooks = false;
+        assert!(!config.hooks_allowed_for(Some(&source(
+            "owner/repo",
+            TemplateProvider::GitLab,
+            host
+        ))));
+    }
+}
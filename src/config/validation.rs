@@ -1,7 +1,7 @@
 use anyhow::Result;
 use std::path::Path;
 
-use super::Config;
+use super::{Config, schema};
 
 impl Config {
     /// Validate configuration
@@ -12,8 +12,43 @@ impl Config {
             warnings.push("Author name is set to default value".to_string());
         }
 
-        if self.search.max_results == 0 {
-            warnings.push("Max search results is set to 0".to_string());
+        // Types, allowed values and per-key rules
+        warnings.extend(schema::check_config(&serde_json::to_value(self)?));
+
+        let mut group_names: Vec<_> = self.groups.keys().collect();
+        group_names.sort();
+        for group_name in group_names {
+            let members = &self.groups[group_name];
+            if members.is_empty() {
+                warnings.push(format!("Group '{}' has no members", group_name));
+            }
+        }
+
+        for (course_id, overrides) in &self.course_overrides {
+            if let Some(group) = &overrides.group
+                && !self.groups.contains_key(group)
+            {
+                warnings.push(format!(
+                    "Course {} uses group '{}', which is not defined",
+                    course_id, group
+                ));
+            }
+        }
+
+        if let Some(profile) = &self.metadata.active_profile
+            && !self.profiles.contains_key(profile)
+        {
+            warnings.push(format!(
+                "Active profile '{}' is not defined, so no profile is used",
+                profile
+            ));
+        }
+
+        if let Ok(Some(json_path)) = Config::shadowed_config_file() {
+            warnings.push(format!(
+                "'{}' is ignored because config.toml exists",
+                json_path.display()
+            ));
         }
 
         // Check if template directory exists
@@ -23,14 +23,14 @@ impl<'a> CourseManager<'a> {
         }
 
         self.config
-            .add_course(course_id.to_string(), course_name.to_string())?;
+            .add_course(course_id.to_string(), course_name.to_string());
         Ok(())
     }
 
     pub fn remove_course(&mut self, course_id: &str) -> Result<String> {
         if let Some(course_name) = self.config.courses.get(course_id) {
             let course_name = course_name.clone();
-            self.config.remove_course(course_id)?;
+            self.config.remove_course(course_id);
             Ok(course_name)
         } else {
             Err(anyhow::anyhow!("Course {} not found", course_id))
@@ -4,6 +4,7 @@
 //! multiple commands.
 
 use anyhow::Result;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
@@ -21,6 +22,8 @@ pub struct FileInfo {
 pub struct CourseStats {
     pub notes_count: usize,
     pub assignments_count: usize,
+    /// Documents per template output directory other than lectures and assignments
+    pub document_counts: BTreeMap<String, usize>,
     pub last_activity: Option<FileInfo>,
     pub total_files: usize,
 }
@@ -30,10 +33,20 @@ pub struct DirectoryScanner;
 #[allow(dead_code)]
 impl DirectoryScanner {
     pub fn scan_course_directory<P: AsRef<Path>>(course_path: P) -> Result<CourseStats> {
+        Self::scan_course_directory_with(course_path, &[])
+    }
+
+    /// Scan a course directory, also counting documents in the given
+    /// template output directories (e.g. `labs`)
+    pub fn scan_course_directory_with<P: AsRef<Path>>(
+        course_path: P,
+        document_dirs: &[String],
+    ) -> Result<CourseStats> {
         let course_path = course_path.as_ref();
         let mut stats = CourseStats {
             notes_count: 0,
             assignments_count: 0,
+            document_counts: BTreeMap::new(),
             last_activity: None,
             total_files: 0,
         };
@@ -43,11 +56,7 @@ impl DirectoryScanner {
         if lectures_path.exists() {
             let lecture_files = Self::scan_directory_for_files(&lectures_path, &["typ"])?;
             stats.notes_count = lecture_files.len();
-            stats.total_files += lecture_files.len();
-
-            if let Some(most_recent) = Self::find_most_recent(&lecture_files) {
-                stats.last_activity = Some(most_recent);
-            }
+            Self::record_activity(&mut stats, &lecture_files);
         }
 
         // Scan assignments directory
@@ -55,24 +64,39 @@ impl DirectoryScanner {
         if assignments_path.exists() {
             let assignment_files = Self::scan_directory_for_files(&assignments_path, &["typ"])?;
             stats.assignments_count = assignment_files.len();
-            stats.total_files += assignment_files.len();
-
-            // Update last activity if assignment is more recent
-            if let Some(most_recent) = Self::find_most_recent(&assignment_files) {
-                match &stats.last_activity {
-                    None => stats.last_activity = Some(most_recent),
-                    Some(current) => {
-                        if most_recent.modified > current.modified {
-                            stats.last_activity = Some(most_recent);
-                        }
-                    }
-                }
+            Self::record_activity(&mut stats, &assignment_files);
+        }
+
+        // Scan template output directories
+        for dir in document_dirs {
+            if dir == "lectures" || dir == "assignments" || stats.document_counts.contains_key(dir)
+            {
+                continue;
+            }
+
+            let dir_path = course_path.join(dir);
+            if dir_path.exists() {
+                let files = Self::scan_directory_for_files(&dir_path, &["typ"])?;
+                stats.document_counts.insert(dir.clone(), files.len());
+                Self::record_activity(&mut stats, &files);
             }
         }
 
         Ok(stats)
     }
 
+    /// Count files towards the total and update last activity if one is more recent
+    fn record_activity(stats: &mut CourseStats, files: &[FileInfo]) {
+        stats.total_files += files.len();
+
+        if let Some(most_recent) = Self::find_most_recent(files) {
+            match &stats.last_activity {
+                Some(current) if current.modified >= most_recent.modified => {}
+                _ => stats.last_activity = Some(most_recent),
+            }
+        }
+    }
+
     pub fn scan_directory_for_files<P: AsRef<Path>>(
         dir_path: P,
         extensions: &[&str],
@@ -94,16 +118,15 @@ impl DirectoryScanner {
             if path.is_file() {
                 if let Some(ext) = path.extension() {
                     let ext_str = ext.to_string_lossy().to_lowercase();
-                    if extensions.contains(&ext_str.as_str()) {
-                        if let Ok(metadata) = entry.metadata() {
-                            if let Ok(modified) = metadata.modified() {
-                                files.push(FileInfo {
-                                    path,
-                                    modified,
-                                    size: metadata.len(),
-                                });
-                            }
-                        }
+                    if extensions.contains(&ext_str.as_str())
+                        && let Ok(metadata) = entry.metadata()
+                        && let Ok(modified) = metadata.modified()
+                    {
+                        files.push(FileInfo {
+                            path,
+                            modified,
+                            size: metadata.len(),
+                        });
                     }
                 }
             } else if path.is_dir() {
@@ -121,18 +144,19 @@ impl DirectoryScanner {
 
     pub fn scan_notes_directory<P: AsRef<Path>>(
         notes_dir: P,
+        document_dirs: &[String],
     ) -> Result<Vec<(String, CourseStats)>> {
         let mut course_stats = Vec::new();
 
         for entry in fs::read_dir(notes_dir)? {
             let entry = entry?;
-            if entry.path().is_dir() {
-                if let Some(course_id) = entry.file_name().to_str() {
-                    // Check if it looks like a course code (5 digits)
-                    if course_id.len() == 5 && course_id.chars().all(|c| c.is_ascii_digit()) {
-                        let stats = Self::scan_course_directory(entry.path())?;
-                        course_stats.push((course_id.to_string(), stats));
-                    }
+            if entry.path().is_dir()
+                && let Some(course_id) = entry.file_name().to_str()
+            {
+                // Check if it looks like a course code (5 digits)
+                if course_id.len() == 5 && course_id.chars().all(|c| c.is_ascii_digit()) {
+                    let stats = Self::scan_course_directory_with(entry.path(), document_dirs)?;
+                    course_stats.push((course_id.to_string(), stats));
                 }
             }
         }
@@ -140,3 +164,43 @@ impl DirectoryScanner {
         Ok(course_stats)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scan_course_directory_with_document_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let course = temp_dir.path();
+        for (dir, count) in [
+            ("lectures", 2),
+            ("assignments", 1),
+            ("labs", 3),
+            ("misc", 1),
+        ] {
+            fs::create_dir_all(course.join(dir)).unwrap();
+            for i in 0..count {
+                fs::write(course.join(dir).join(format!("{}.typ", i)), "").unwrap();
+            }
+        }
+        fs::write(course.join("labs").join("notes.txt"), "").unwrap();
+
+        let dirs = ["labs", "labs", "lectures", "exams"].map(String::from);
+        let stats = DirectoryScanner::scan_course_directory_with(course, &dirs).unwrap();
+
+        assert_eq!(stats.notes_count, 2);
+        assert_eq!(stats.assignments_count, 1);
+        assert_eq!(
+            stats.document_counts,
+            BTreeMap::from([("labs".to_string(), 3)])
+        );
+        assert_eq!(stats.total_files, 6);
+        assert!(stats.last_activity.is_some());
+
+        let stats = DirectoryScanner::scan_course_directory(course).unwrap();
+        assert!(stats.document_counts.is_empty());
+        assert_eq!(stats.total_files, 3);
+    }
+}
@@ -152,6 +152,42 @@ impl FileOperations {
         Ok(())
     }
 
+    /// Replace a file's contents without leaving a partly written file
+    ///
+    /// The content is written to a temporary file in the same directory,
+    /// flushed to disk and renamed over `path`, so readers see either the old
+    /// or the new contents, also if noter is interrupted.
+    pub fn write_atomically(path: &Path, content: &str) -> Result<()> {
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow!("Invalid file path: {}", path.display()))?
+            .to_string_lossy();
+        let temp_path = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+        let write = || -> std::io::Result<()> {
+            let mut file = fs::File::create(&temp_path)?;
+            std::io::Write::write_all(&mut file, content.as_bytes())?;
+            file.sync_all()?;
+            fs::rename(&temp_path, path)
+        };
+        if let Err(e) = write() {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e).with_context(|| format!("Failed to write {}", path.display()));
+        }
+
+        // Make the rename itself durable; not possible on every platform
+        #[cfg(unix)]
+        if let Ok(dir) = fs::File::open(dir) {
+            let _ = dir.sync_all();
+        }
+
+        Ok(())
+    }
+
     /// Create a backup of an existing file
     pub fn create_backup(file_path: &Path) -> Result<()> {
         if !file_path.exists() {
@@ -301,12 +337,44 @@ impl FileOperations {
             let entry = entry?;
             let entry_path = entry.path();
 
-            if entry_path.is_file() {
-                if let Some(ext) = entry_path.extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    if extensions.contains(&ext_str.as_str()) {
-                        files.push(entry_path);
-                    }
+            if entry_path.is_file()
+                && let Some(ext) = entry_path.extension()
+            {
+                let ext_str = ext.to_string_lossy().to_lowercase();
+                if extensions.contains(&ext_str.as_str()) {
+                    files.push(entry_path);
+                }
+            }
+        }
+
+        files.sort();
+        Ok(files)
+    }
+
+    /// List files with specific extensions in a directory and all its subdirectories
+    ///
+    /// Symlinks are skipped, so a link back to a parent directory can't loop
+    /// and files outside the directory are never returned.
+    pub fn list_files_recursive(dir_path: &Path, extensions: &[&str]) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+
+        if !dir_path.exists() {
+            return Ok(files);
+        }
+
+        for entry in fs::read_dir(dir_path)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let entry_path = entry.path();
+
+            if file_type.is_symlink() {
+                continue;
+            } else if file_type.is_dir() {
+                files.extend(Self::list_files_recursive(&entry_path, extensions)?);
+            } else if let Some(ext) = entry_path.extension() {
+                let ext_str = ext.to_string_lossy().to_lowercase();
+                if extensions.contains(&ext_str.as_str()) {
+                    files.push(entry_path);
                 }
             }
         }
@@ -442,6 +510,39 @@ mod tests {
         assert_eq!(unique_name, "test-1.txt");
     }
 
+    #[test]
+    fn test_write_atomically() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+
+        FileOperations::write_atomically(&path, "{}").unwrap();
+        FileOperations::write_atomically(&path, "{\"author\": \"Ada\"}").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"author\": \"Ada\"}");
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_list_files_recursive_skips_symlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let notes = temp_dir.path().join("notes");
+        fs::create_dir_all(notes.join("02101")).unwrap();
+        fs::write(notes.join("02101").join("lecture.typ"), "").unwrap();
+
+        // A link back to a parent directory must not recurse forever
+        std::os::unix::fs::symlink(&notes, notes.join("02101").join("loop")).unwrap();
+        std::os::unix::fs::symlink(
+            notes.join("02101").join("lecture.typ"),
+            notes.join("link.typ"),
+        )
+        .unwrap();
+
+        let files = FileOperations::list_files_recursive(&notes, &["typ"]).unwrap();
+        assert_eq!(files, vec![notes.join("02101").join("lecture.typ")]);
+    }
+
     #[test]
     fn test_ensure_directory_exists() {
         let temp_dir = TempDir::new().unwrap();
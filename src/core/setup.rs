@@ -202,10 +202,10 @@ impl SetupManager {
             // List what was installed
             if let Ok(entries) = fs::read_dir(repo_template_dir) {
                 for entry in entries.flatten() {
-                    if entry.path().is_dir() {
-                        if let Some(name) = entry.file_name().to_str() {
-                            result.templates_installed.push(name.to_string());
-                        }
+                    if entry.path().is_dir()
+                        && let Some(name) = entry.file_name().to_str()
+                    {
+                        result.templates_installed.push(name.to_string());
                     }
                 }
             }
@@ -461,12 +461,12 @@ Thumbs.db
         let mut count = 0;
         if let Ok(entries) = fs::read_dir(notes_dir) {
             for entry in entries.flatten() {
-                if entry.path().is_dir() {
-                    if let Some(name) = entry.file_name().to_str() {
-                        if name.len() == 5 && name.chars().all(|c| c.is_ascii_digit()) {
-                            count += 1;
-                        }
-                    }
+                if entry.path().is_dir()
+                    && let Some(name) = entry.file_name().to_str()
+                    && name.len() == 5
+                    && name.chars().all(|c| c.is_ascii_digit())
+                {
+                    count += 1;
                 }
             }
         }
@@ -4,6 +4,7 @@
 
 use crate::config::Config;
 use crate::core::directories::{CourseStats, DirectoryScanner};
+use crate::core::template::discovery::TemplateDiscovery;
 use anyhow::Result;
 use chrono::Datelike;
 use std::collections::HashMap;
@@ -21,6 +22,8 @@ pub struct SystemStatus {
 pub struct ActivitySummary {
     pub total_notes: usize,
     pub total_assignments: usize,
+    /// Documents created from other templates (see `noter new`)
+    pub total_documents: usize,
     pub most_recent_activity: Option<RecentActivity>,
     pub most_active_course: Option<(String, usize)>,
 }
@@ -41,6 +44,7 @@ pub struct CourseHealthInfo {
     pub course_name: String,
     pub notes_count: usize,
     pub assignments_count: usize,
+    pub documents_count: usize,
     pub days_since_last_activity: u64,
     pub health_status: HealthStatus,
 }
@@ -106,24 +110,28 @@ impl StatusManager {
             return Ok(ActivitySummary {
                 total_notes: 0,
                 total_assignments: 0,
+                total_documents: 0,
                 most_recent_activity: None,
                 most_active_course: None,
             });
         }
 
-        let course_stats = DirectoryScanner::scan_notes_directory(&config.paths.notes_dir)?;
+        let document_dirs = TemplateDiscovery::document_directories(config);
+        let course_stats =
+            DirectoryScanner::scan_notes_directory(&config.paths.notes_dir, &document_dirs)?;
 
         let mut total_notes = 0;
         let mut total_assignments = 0;
+        let mut total_documents = 0;
         let mut most_recent_activity: Option<RecentActivity> = None;
         let mut course_activity: HashMap<String, usize> = HashMap::new();
 
         for (course_id, stats) in &course_stats {
             total_notes += stats.notes_count;
             total_assignments += stats.assignments_count;
+            total_documents += stats.document_counts.values().sum::<usize>();
 
-            let total_activity = stats.notes_count + stats.assignments_count;
-            course_activity.insert(course_id.clone(), total_activity);
+            course_activity.insert(course_id.clone(), stats.total_files);
 
             // Check for most recent activity
             if let Some(ref last_activity) = stats.last_activity {
@@ -162,6 +170,7 @@ impl StatusManager {
         Ok(ActivitySummary {
             total_notes,
             total_assignments,
+            total_documents,
             most_recent_activity,
             most_active_course,
         })
@@ -174,12 +183,14 @@ impl StatusManager {
         }
 
         let mut course_health = Vec::new();
+        let document_dirs = TemplateDiscovery::document_directories(config);
 
         for (course_id, course_name) in &config.courses {
             let course_path = Path::new(&config.paths.notes_dir).join(course_id);
 
             if course_path.exists() {
-                let stats = DirectoryScanner::scan_course_directory(&course_path)?;
+                let stats =
+                    DirectoryScanner::scan_course_directory_with(&course_path, &document_dirs)?;
                 let days_since_last = Self::calculate_days_since_last_activity(&stats);
                 let health_status = Self::determine_health_status(&stats, days_since_last);
 
@@ -188,6 +199,7 @@ impl StatusManager {
                     course_name: course_name.clone(),
                     notes_count: stats.notes_count,
                     assignments_count: stats.assignments_count,
+                    documents_count: stats.document_counts.values().sum(),
                     days_since_last_activity: days_since_last,
                     health_status,
                 });
@@ -195,7 +207,7 @@ impl StatusManager {
         }
 
         // Sort by health status and then by activity
-        course_health.sort_by(|a, b| a.days_since_last_activity.cmp(&b.days_since_last_activity));
+        course_health.sort_by_key(|course| course.days_since_last_activity);
 
         Ok(course_health)
     }
@@ -228,7 +240,7 @@ impl StatusManager {
     }
 
     fn determine_health_status(stats: &CourseStats, days_since_last: u64) -> HealthStatus {
-        let total_files = stats.notes_count + stats.assignments_count;
+        let total_files = stats.total_files;
 
         match (total_files, days_since_last) {
             (0, _) => HealthStatus::Critical,
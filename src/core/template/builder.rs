@@ -6,12 +6,15 @@
 //! TemplateEngine.
 
 use anyhow::Result;
+use std::path::Path;
 
 use super::config::{TemplateConfig, TemplateDefinition, TemplateVariant};
 use super::context::{TemplateContext, TemplateContextBuilder, TemplateMetadata};
 use super::discovery::TemplateDiscovery;
 use super::engine::{TemplateEngine, TemplateReference};
+use super::processing::DocumentProcessor;
 use super::validation::{TemplateValidator, ValidationIssue, ValidationSeverity};
+use super::versions::VersionHistory;
 use crate::config::Config;
 
 /// Template builder for fluent template construction
@@ -41,6 +44,7 @@ pub struct TemplateBuilder {
     context_builder: TemplateContextBuilder,
     template_reference: TemplateReference,
     variant_override: Option<String>,
+    package_override: Option<String>,
     processing_options: ProcessingOptions,
 }
 
@@ -80,6 +84,7 @@ impl TemplateBuilder {
             context_builder,
             template_reference: TemplateReference::lecture(), // Default
             variant_override: None,
+            package_override: None,
             processing_options: ProcessingOptions::default(),
         })
     }
@@ -96,6 +101,18 @@ impl TemplateBuilder {
         self
     }
 
+    /// Set the title used when none is given and the course has no title format
+    pub fn with_default_title(mut self, title: &str) -> Self {
+        self.context_builder = self.context_builder.with_default_title(title);
+        self
+    }
+
+    /// Use a group's members as the authors
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.context_builder = self.context_builder.with_group(group);
+        self
+    }
+
     /// Set custom sections for the template
     pub fn with_sections(mut self, sections: Vec<String>) -> Self {
         self.context_builder = self.context_builder.with_sections(sections);
@@ -120,6 +137,12 @@ impl TemplateBuilder {
         self
     }
 
+    /// Render from a specific template package instead of the preferred one
+    pub fn with_package(mut self, package_name: &str) -> Self {
+        self.package_override = Some(package_name.to_string());
+        self
+    }
+
     /// Configure processing options
     pub fn with_processing_options(mut self, options: ProcessingOptions) -> Self {
         self.processing_options = options;
@@ -150,16 +173,23 @@ impl TemplateBuilder {
         self
     }
 
-    /// Enable/disable debug information
-    pub fn with_debug_info(mut self, enabled: bool) -> Self {
-        self.processing_options.include_debug_info = enabled;
-        self
-    }
-
     /// Build the template content - returns only the generated Typst code
     pub fn build(&self) -> Result<String> {
+        // Render from the preferred package that defines the template
+        let mut context_builder = self.context_builder.clone();
+        if let Some(config) = context_builder.get_config() {
+            let template_config = TemplateDiscovery::resolve_template_package(
+                config,
+                &self.template_reference.name,
+                self.package_override.as_deref(),
+            )?;
+            context_builder = context_builder
+                .with_template_config(template_config)
+                .with_template_name(&self.template_reference.name);
+        }
+
         // Build the context
-        let mut context = self.context_builder.clone().build()?;
+        let mut context = context_builder.build()?;
 
         // Apply validation if enabled
         if self.processing_options.validate_before_build {
@@ -167,7 +197,7 @@ impl TemplateBuilder {
 
             // Handle validation results based on configuration
             if !validation_result.issues.is_empty() {
-                self.handle_validation_issues(&validation_result.issues)?;
+                self.handle_validation_issues(&validation_result.issues, &context)?;
             }
         }
 
@@ -184,7 +214,61 @@ impl TemplateBuilder {
         };
 
         // Delegate to TemplateEngine for actual generation
-        TemplateEngine::render_template(&context, &template_ref)
+        let document = self.render_and_process(&context, &template_ref)?;
+
+        // Document-level rules (syntax, references, file size) need the final output
+        if self.processing_options.validate_before_build {
+            let issues = TemplateValidator::validate_rendered_document(
+                &document,
+                &context.engine_config,
+                self.context_builder.get_config(),
+                None,
+            )?;
+
+            if !issues.is_empty() {
+                self.handle_validation_issues(&issues, &context)?;
+            }
+        }
+
+        Ok(document)
+    }
+
+    /// Render the template and run the package's processing hooks on it
+    fn render_and_process(
+        &self,
+        context: &TemplateContext,
+        template_ref: &TemplateReference,
+    ) -> Result<String> {
+        let processor = DocumentProcessor::new(&context.engine_config, self.hooks_allowed(context));
+        for hook in processor.skipped_hooks() {
+            eprintln!(
+                "Warning: skipping processing hook '{}' (not allowed for this template repository, see `noter config allow-template-hooks`)",
+                hook
+            );
+        }
+
+        match TemplateEngine::render_template(context, template_ref) {
+            Ok(document) => processor.process(&document),
+            Err(e) => {
+                processor.run_error_hooks(&format!("{:#}", e));
+                Err(e)
+            }
+        }
+    }
+
+    /// Whether the user trusts the active package's repository to run hook commands
+    fn hooks_allowed(&self, context: &TemplateContext) -> bool {
+        match (self.context_builder.get_config(), &context.template_config) {
+            (Some(config), Some(template_config)) => {
+                let installed_from = template_config
+                    .package_dir
+                    .as_deref()
+                    .and_then(Path::parent)
+                    .and_then(|package_dir| VersionHistory::load(package_dir).source);
+                config.templates.hooks_allowed_for(installed_from.as_ref())
+            }
+            _ => false,
+        }
     }
 
     /// Build with validation report (for debugging and analysis)
@@ -302,7 +386,17 @@ impl TemplateBuilder {
     }
 
     /// Handle validation issues based on processing options
-    fn handle_validation_issues(&self, issues: &[ValidationIssue]) -> Result<()> {
+    ///
+    /// The package's `strict_validation` setting is authoritative: in strict mode
+    /// any error or warning blocks creation, otherwise errors and warnings are
+    /// reported and creation continues (unless `fail_on_validation_errors`).
+    fn handle_validation_issues(
+        &self,
+        issues: &[ValidationIssue],
+        context: &TemplateContext,
+    ) -> Result<()> {
+        let strict = context.engine_config.validation.strict_validation;
+
         let errors: Vec<_> = issues
             .iter()
             .filter(|i| i.severity == ValidationSeverity::Error)
@@ -312,45 +406,62 @@ impl TemplateBuilder {
             .filter(|i| i.severity == ValidationSeverity::Warning)
             .collect();
 
-        // Always fail on errors if fail_on_validation_errors is true
-        if !errors.is_empty() && self.processing_options.fail_on_validation_errors {
-            let error_messages: Vec<String> = errors
+        let blocking: Vec<_> = if strict {
+            errors.iter().chain(&warnings).collect()
+        } else if self.processing_options.fail_on_validation_errors {
+            errors.iter().collect()
+        } else {
+            Vec::new()
+        };
+
+        if !blocking.is_empty() {
+            let messages: Vec<String> = blocking
                 .iter()
-                .map(|e| format!("{}: {}", e.category, e.message))
+                .map(|issue| Self::format_issue(issue))
                 .collect();
             return Err(anyhow::anyhow!(
-                "Template validation errors:\n{}",
-                error_messages.join("\n")
+                "Template validation failed{}:\n{}",
+                if strict { " (strict validation)" } else { "" },
+                messages.join("\n")
             ));
         }
 
-        // Print warnings and info if debug info is enabled
-        if self.processing_options.include_debug_info {
-            if !warnings.is_empty() {
-                eprintln!("Template validation warnings:");
-                for warning in &warnings {
-                    eprintln!("  Warning [{}]: {}", warning.category, warning.message);
-                    if let Some(suggestion) = &warning.suggestion {
-                        eprintln!("    Suggestion: {}", suggestion);
-                    }
-                }
+        for issue in errors.iter().chain(&warnings) {
+            eprintln!("{}", Self::format_issue(issue));
+            if let Some(suggestion) = &issue.suggestion {
+                eprintln!("    Suggestion: {}", suggestion);
             }
+        }
 
-            let info: Vec<_> = issues
+        // Info is only interesting when debugging
+        if self.processing_options.include_debug_info {
+            for info in issues
                 .iter()
                 .filter(|i| i.severity == ValidationSeverity::Info)
-                .collect();
-            if !info.is_empty() {
-                eprintln!("Template validation info:");
-                for info in &info {
-                    eprintln!("  Info [{}]: {}", info.category, info.message);
-                }
+            {
+                eprintln!("Info [{}]: {}", info.category, info.message);
             }
         }
 
         Ok(())
     }
 
+    fn format_issue(issue: &ValidationIssue) -> String {
+        let label = match issue.severity {
+            ValidationSeverity::Error => "Error",
+            ValidationSeverity::Warning => "Warning",
+            ValidationSeverity::Info => "Info",
+        };
+
+        match &issue.location {
+            Some(location) => format!(
+                "{} [{}]: {} ({})",
+                label, issue.category, issue.message, location
+            ),
+            None => format!("{} [{}]: {}", label, issue.category, issue.message),
+        }
+    }
+
     /// Find template definition by reference
     fn find_template_definition(
         &self,
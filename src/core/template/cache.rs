@@ -0,0 +1,335 @@
+//! Template download cache
+//!
+//! Release archives are kept in `~/.cache/dtu-notes/templates` together with
+//! an index recording the repository, tag, source URL and SHA-256 of every
+//! archive. The index lets offline installs find the right archive and lets
+//! `noter template cache` list, prune and verify the cache.
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::lockfile::sha256_hex;
+
+const INDEX_FILE_NAME: &str = "index.json";
+
+/// A cached release archive
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CacheEntry {
+    /// Archive file name inside the cache directory
+    pub file: String,
+    /// Repository name from the configuration
+    pub repository: String,
+    /// Release tag or full commit SHA
+    pub tag: String,
+    pub url: String,
+    pub sha256: String,
+    pub downloaded_at: DateTime<Utc>,
+}
+
+/// An archive in the cache directory, indexed or not
+#[derive(Debug)]
+pub struct CachedArchive {
+    pub path: PathBuf,
+    pub size: u64,
+    pub entry: Option<CacheEntry>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheIndex {
+    entries: Vec<CacheEntry>,
+}
+
+pub struct TemplateCache {
+    dir: PathBuf,
+}
+
+impl TemplateCache {
+    /// The user's template cache directory
+    pub fn default_dir() -> Result<PathBuf> {
+        Ok(dirs::cache_dir()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".cache")))
+            .context("Could not determine cache directory")?
+            .join("dtu-notes")
+            .join("templates"))
+    }
+
+    pub fn open() -> Result<Self> {
+        Ok(Self::at(Self::default_dir()?))
+    }
+
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Record a freshly downloaded archive, replacing any earlier entry for the file
+    pub fn record(
+        &self,
+        repository: &str,
+        tag: &str,
+        url: &str,
+        archive_path: &Path,
+        archive: &[u8],
+    ) -> Result<()> {
+        let file = archive_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("Invalid cache path {}", archive_path.display()))?
+            .to_string();
+
+        let mut index = self.load_index();
+        index.entries.retain(|entry| entry.file != file);
+        index.entries.push(CacheEntry {
+            file,
+            repository: repository.to_string(),
+            tag: tag.to_string(),
+            url: url.to_string(),
+            sha256: sha256_hex(archive),
+            downloaded_at: Utc::now(),
+        });
+        self.save_index(&index)
+    }
+
+    /// The newest cached archive of a repository, optionally for a tag
+    ///
+    /// Tags match exactly, except that an abbreviated commit SHA (at least 7
+    /// hex digits) also matches the full SHA.
+    pub fn find(&self, repository: &str, tag: Option<&str>) -> Option<CacheEntry> {
+        let abbreviates = |tag: &str, full: &str| {
+            tag.len() >= 7 && tag.chars().all(|c| c.is_ascii_hexdigit()) && full.starts_with(tag)
+        };
+
+        self.load_index()
+            .entries
+            .into_iter()
+            .filter(|entry| entry.repository == repository)
+            .filter(|entry| tag.is_none_or(|tag| entry.tag == tag || abbreviates(tag, &entry.tag)))
+            .filter(|entry| self.dir.join(&entry.file).exists())
+            .max_by_key(|entry| entry.downloaded_at)
+    }
+
+    pub fn path_of(&self, entry: &CacheEntry) -> PathBuf {
+        self.dir.join(&entry.file)
+    }
+
+    /// All archives in the cache directory, newest first
+    pub fn list(&self) -> Result<Vec<CachedArchive>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let index = self.load_index();
+        let mut archives = Vec::new();
+
+        for dir_entry in fs::read_dir(&self.dir)? {
+            let path = dir_entry?.path();
+            let Some(file) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if !path.is_file() || file == INDEX_FILE_NAME {
+                continue;
+            }
+
+            archives.push(CachedArchive {
+                size: fs::metadata(&path)?.len(),
+                entry: index
+                    .entries
+                    .iter()
+                    .find(|entry| entry.file == file)
+                    .cloned(),
+                path,
+            });
+        }
+
+        archives.sort_by(|a, b| {
+            let downloaded =
+                |archive: &CachedArchive| archive.entry.as_ref().map(|e| e.downloaded_at);
+            downloaded(b).cmp(&downloaded(a))
+        });
+        Ok(archives)
+    }
+
+    /// Delete all but the newest `keep` archives of every repository
+    ///
+    /// Archives missing from the index can't be attributed to a repository
+    /// and are deleted as well. Returns the deleted archives.
+    pub fn prune(&self, keep: usize) -> Result<Vec<CachedArchive>> {
+        let mut kept_per_repository: Vec<(String, usize)> = Vec::new();
+        let mut removed = Vec::new();
+
+        for archive in self.list()? {
+            let keep_archive = match &archive.entry {
+                Some(entry) => {
+                    match kept_per_repository
+                        .iter_mut()
+                        .find(|(name, _)| *name == entry.repository)
+                    {
+                        Some((_, count)) => {
+                            *count += 1;
+                            *count <= keep
+                        }
+                        None => {
+                            kept_per_repository.push((entry.repository.clone(), 1));
+                            keep >= 1
+                        }
+                    }
+                }
+                None => false,
+            };
+
+            if !keep_archive {
+                fs::remove_file(&archive.path)
+                    .with_context(|| format!("Failed to delete {}", archive.path.display()))?;
+                removed.push(archive);
+            }
+        }
+
+        let mut index = self.load_index();
+        index
+            .entries
+            .retain(|entry| self.dir.join(&entry.file).exists());
+        self.save_index(&index)?;
+
+        Ok(removed)
+    }
+
+    /// Check that an archive is readable and matches its recorded checksum
+    pub fn verify(&self, archive: &CachedArchive) -> Result<()> {
+        let bytes = fs::read(&archive.path)
+            .with_context(|| format!("Failed to read {}", archive.path.display()))?;
+
+        if let Some(entry) = &archive.entry {
+            let actual = sha256_hex(&bytes);
+            if actual != entry.sha256 {
+                return Err(anyhow!(
+                    "Checksum mismatch: expected {}, got {}",
+                    entry.sha256,
+                    actual
+                ));
+            }
+        }
+
+        let is_zip = archive
+            .path
+            .extension()
+            .is_some_and(|extension| extension == "zip");
+
+        if is_zip {
+            let mut zip = zip::ZipArchive::new(std::io::Cursor::new(bytes))
+                .context("Not a valid zip archive")?;
+            for i in 0..zip.len() {
+                let mut file = zip.by_index(i).context("Corrupt zip entry")?;
+                std::io::copy(&mut file, &mut std::io::sink()).context("Corrupt zip entry")?;
+            }
+        } else {
+            let decoder = flate2::read::GzDecoder::new(bytes.as_slice());
+            let mut tar = tar::Archive::new(decoder);
+            for file in tar.entries().context("Not a valid tar.gz archive")? {
+                let mut file = file.context("Corrupt tar.gz entry")?;
+                std::io::copy(&mut file, &mut std::io::sink()).context("Corrupt tar.gz entry")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn load_index(&self) -> CacheIndex {
+        fs::read_to_string(self.dir.join(INDEX_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_index(&self, index: &CacheIndex) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let content = serde_json::to_string_pretty(index)?;
+        fs::write(self.dir.join(INDEX_FILE_NAME), content).context("Failed to write cache index")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn targz(content: &[u8]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "package/lib.typ", content)
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn add(cache: &TemplateCache, repository: &str, tag: &str, content: &[u8]) -> PathBuf {
+        let path = cache.dir().join(format!("{}-{}.tar.gz", repository, tag));
+        let archive = targz(content);
+        fs::create_dir_all(cache.dir()).unwrap();
+        fs::write(&path, &archive).unwrap();
+        cache
+            .record(repository, tag, "https://example.com", &path, &archive)
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_find_and_prune() {
+        let dir = TempDir::new().unwrap();
+        let cache = TemplateCache::at(dir.path());
+
+        let old = add(&cache, "notes", "v1.0.0", b"one");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let new = add(&cache, "notes", "v1.1.0", b"two");
+        let other = add(&cache, "other", "v0.1.0", b"three");
+        fs::write(dir.path().join("stray.tar.gz"), b"stray").unwrap();
+
+        assert_eq!(cache.find("notes", None).unwrap().tag, "v1.1.0");
+        assert_eq!(cache.find("notes", Some("v1.0.0")).unwrap().tag, "v1.0.0");
+        assert!(cache.find("notes", Some("v2.0.0")).is_none());
+        assert!(cache.find("notes", Some("v1.1")).is_none());
+
+        let removed = cache.prune(1).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert!(!old.exists());
+        assert!(new.exists() && other.exists());
+        assert!(cache.find("notes", Some("v1.0.0")).is_none());
+    }
+
+    #[test]
+    fn test_find_abbreviated_commit() {
+        let dir = TempDir::new().unwrap();
+        let cache = TemplateCache::at(dir.path());
+        let sha = "3f2a9c1d8e7b6a5f4e3d2c1b0a9f8e7d6c5b4a39";
+        add(&cache, "notes", sha, b"one");
+
+        assert_eq!(cache.find("notes", Some(sha)).unwrap().tag, sha);
+        assert_eq!(cache.find("notes", Some("3f2a9c1")).unwrap().tag, sha);
+        assert!(cache.find("notes", Some("3f2a9c")).is_none());
+        assert!(cache.find("notes", Some("3f2a9c1x")).is_none());
+    }
+
+    #[test]
+    fn test_verify_detects_corruption() {
+        let dir = TempDir::new().unwrap();
+        let cache = TemplateCache::at(dir.path());
+        let path = add(&cache, "notes", "v1.0.0", b"content");
+
+        let archives = cache.list().unwrap();
+        assert!(cache.verify(&archives[0]).is_ok());
+
+        fs::write(&path, b"not an archive").unwrap();
+        let archives = cache.list().unwrap();
+        assert!(cache.verify(&archives[0]).is_err());
+    }
+}
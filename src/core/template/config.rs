@@ -3,8 +3,10 @@
 //! Contains all configuration structures for template packages,
 //! including engine capabilities and template definitions.
 
+use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Component, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TemplateConfig {
@@ -13,6 +15,9 @@ pub struct TemplateConfig {
     pub variants: Option<Vec<TemplateVariant>>,
     pub course_mapping: Option<HashMap<String, String>>,
     pub engine: Option<EngineConfig>,
+    /// Directory the package was loaded from (None if not loaded from disk)
+    #[serde(skip)]
+    pub package_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -35,6 +40,65 @@ pub struct TemplateDefinition {
     pub supports_variants: bool,
     pub course_types: Option<Vec<String>>,
     pub default_sections: Vec<String>,
+    #[serde(default)]
+    pub parameters: Vec<TemplateParameter>,
+    /// Directory inside the course directory for documents created with `noter new`
+    #[serde(default)]
+    pub output_dir: Option<String>,
+    /// Whether the template function takes an `authors` array with study numbers
+    #[serde(default)]
+    pub supports_authors: bool,
+}
+
+impl TemplateDefinition {
+    /// The directory documents from this template are created in, relative to the course directory
+    ///
+    /// Defaults to `lectures` for notes, `assignments` for assignments and
+    /// the template name otherwise. Absolute paths and `..` are rejected.
+    pub fn output_directory(&self) -> Result<PathBuf> {
+        let dir = match (&self.output_dir, self.name.as_str()) {
+            (Some(dir), _) => dir.trim_matches('/').to_string(),
+            (None, "note") => "lectures".to_string(),
+            (None, "assignment") => "assignments".to_string(),
+            (None, name) => name.to_string(),
+        };
+
+        let path = PathBuf::from(&dir);
+        let is_relative = !dir.is_empty()
+            && path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !is_relative {
+            return Err(anyhow!(
+                "Output directory '{}' of template '{}' must be a relative path inside the course directory",
+                dir,
+                self.name
+            ));
+        }
+
+        Ok(path)
+    }
+}
+
+/// Extra parameter passed to the template function alongside the standard ones
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TemplateParameter {
+    pub name: String,
+    #[serde(default)]
+    pub parameter_type: ParameterType,
+    pub default: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub enum ParameterType {
+    #[default]
+    String,
+    Integer,
+    Float,
+    Boolean,
+    Date,
+    Content,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -230,3 +294,48 @@ impl Default for EngineConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition(name: &str, output_dir: Option<&str>) -> TemplateDefinition {
+        TemplateDefinition {
+            name: name.to_string(),
+            display_name: name.to_string(),
+            description: String::new(),
+            file: "lib.typ".to_string(),
+            function: name.to_string(),
+            supports_variants: false,
+            course_types: None,
+            default_sections: vec![],
+            parameters: vec![],
+            output_dir: output_dir.map(str::to_string),
+            supports_authors: false,
+        }
+    }
+
+    #[test]
+    fn test_output_directory() {
+        let output_directory = |name, dir| definition(name, dir).output_directory();
+
+        assert_eq!(
+            output_directory("note", None).unwrap(),
+            PathBuf::from("lectures")
+        );
+        assert_eq!(
+            output_directory("assignment", None).unwrap(),
+            PathBuf::from("assignments")
+        );
+        assert_eq!(output_directory("lab", None).unwrap(), PathBuf::from("lab"));
+        assert_eq!(
+            output_directory("lab", Some("labs/week/")).unwrap(),
+            PathBuf::from("labs/week")
+        );
+
+        // Only plain relative paths inside the course directory
+        for dir in ["../labs", "labs/../../x", "./labs", "", "/"] {
+            assert!(output_directory("lab", Some(dir)).is_err(), "{}", dir);
+        }
+    }
+}
@@ -5,13 +5,15 @@
 //! It provides specialized context builders for different template types and
 //! integrates with the new template configuration system.
 
-use anyhow::Result;
-use chrono::Local;
+use anyhow::{Context, Result, anyhow};
+use chrono::{Local, NaiveDate};
+use regex::Regex;
 use std::collections::HashMap;
 
-use super::config::{EngineConfig, TemplateConfig};
-use crate::config::Config;
+use super::config::{EngineConfig, TemplateConfig, TransformationType, VariableTransformation};
+use crate::config::{Author, Config};
 use crate::core::status::StatusManager;
+use crate::core::validation::Validator;
 
 /// Rich context structure containing all metadata needed for template generation.
 ///
@@ -33,6 +35,8 @@ pub struct TemplateContext {
     pub course_name: String,
     pub title: String,
     pub author: String,
+    /// Everyone on the front page, starting with the user
+    pub authors: Vec<Author>,
     pub date: String,
     pub semester: String,
     pub template_version: String,
@@ -55,6 +59,8 @@ pub struct TemplateMetadata {
     pub creation_date: chrono::DateTime<Local>,
     pub template_source: String,
     pub variant_used: Option<String>,
+    /// Variant to use when the template reference names none (per-course setting)
+    pub default_variant: Option<String>,
     pub processing_flags: Vec<String>,
 }
 
@@ -64,60 +70,9 @@ impl TemplateContext {
         TemplateContextBuilder::new()
     }
 
-    /// Build lecture context with template configuration support
-    pub fn build_lecture_context(
-        course_id: &str,
-        config: &Config,
-        template_config: &TemplateConfig,
-        custom_title: Option<&str>,
-    ) -> Result<Self> {
-        let course_name = Self::resolve_course_name(course_id, config);
-        let semester = StatusManager::get_current_semester(config);
-        let course_type = Self::determine_course_type(course_id);
-
-        let title = if let Some(custom_title) = custom_title {
-            custom_title.to_string()
-        } else {
-            let date = Local::now();
-            if config.note_preferences.include_date_in_title {
-                format!("Lecture - {}", date.format("%B %d, %Y"))
-            } else {
-                "Lecture Notes".to_string()
-            }
-        };
-
-        let engine_config = template_config.engine.clone().unwrap_or_default();
-        let variables = Self::build_builtin_variables(course_id, &title, &config.author, &semester);
-
-        Ok(Self {
-            course_id: course_id.to_string(),
-            course_name,
-            title,
-            author: config.author.clone(),
-            date: Local::now().format("%Y-%m-%d").to_string(),
-            semester,
-            template_version: config.template_version.clone(),
-            sections: config.note_preferences.lecture_sections.clone(),
-            custom_fields: HashMap::new(),
-            template_config: Some(template_config.clone()),
-            engine_config,
-            template_dir: config.paths.templates_dir.clone(),
-            variables,
-            metadata: TemplateMetadata {
-                course_type,
-                assignment_type: None,
-                creation_date: Local::now(),
-                template_source: "builtin".to_string(),
-                variant_used: None,
-                processing_flags: vec![],
-            },
-        })
-    }
-
-    /// Build assignment context with template configuration support
-    pub fn build_assignment_context(
+    /// Build custom context for builder pattern
+    pub fn build_custom_context(
         course_id: &str,
-        assignment_title: &str,
         config: &Config,
         template_config: &TemplateConfig,
     ) -> Result<Self> {
@@ -130,21 +85,21 @@ impl TemplateContext {
             course_id,
             "general",
         );
-        let assignment_type = Self::determine_assignment_type(assignment_title);
 
         let engine_config = template_config.engine.clone().unwrap_or_default();
         let variables =
-            Self::build_builtin_variables(course_id, assignment_title, &config.author, &semester);
+            Self::build_builtin_variables(course_id, &course_name, "", &config.author, &semester);
 
         Ok(Self {
             course_id: course_id.to_string(),
             course_name,
-            title: assignment_title.to_string(),
+            title: String::new(),
             author: config.author.clone(),
+            authors: vec![Self::config_author(config)],
             date: Local::now().format("%Y-%m-%d").to_string(),
             semester,
             template_version: config.template_version.clone(),
-            sections: config.note_preferences.assignment_sections.clone(),
+            sections: Vec::new(),
             custom_fields: HashMap::new(),
             template_config: Some(template_config.clone()),
             engine_config,
@@ -152,57 +107,130 @@ impl TemplateContext {
             variables,
             metadata: TemplateMetadata {
                 course_type,
-                assignment_type: Some(assignment_type),
+                assignment_type: None,
                 creation_date: Local::now(),
-                template_source: "builtin".to_string(),
+                template_source: "custom".to_string(),
                 variant_used: None,
+                default_variant: None,
                 processing_flags: vec![],
             },
         })
     }
 
-    /// Build custom context for builder pattern
-    pub fn build_custom_context(
-        course_id: &str,
+    /// Apply the course's settings from `course_overrides` for a template
+    ///
+    /// Sets the default variant and sections for the template, the authors
+    /// (see [`Self::set_authors`]) and the extra parameters as variables.
+    /// `group` takes precedence over the course's group, which applies to all
+    /// templates except `note`. Values given on the command line are applied
+    /// later and take precedence.
+    pub fn apply_course_overrides(
+        &mut self,
         config: &Config,
-        template_config: &TemplateConfig,
-    ) -> Result<Self> {
-        use super::discovery::TemplateDiscovery;
+        template_name: &str,
+        group: Option<&str>,
+    ) -> Result<()> {
+        let overrides = config.course_overrides(&self.course_id);
 
-        let course_name = Self::resolve_course_name(course_id, config);
-        let semester = StatusManager::get_current_semester(config);
-        let course_type = TemplateDiscovery::resolve_course_type(
-            std::slice::from_ref(template_config),
-            course_id,
-            "general",
-        );
+        if let Some(variant) = overrides.variants.get(template_name) {
+            self.metadata.default_variant = Some(variant.clone());
+        }
 
-        let engine_config = template_config.engine.clone().unwrap_or_default();
-        let variables = Self::build_builtin_variables(course_id, "", &config.author, &semester);
+        if let Some(sections) = overrides.sections.get(template_name) {
+            self.sections = sections.clone();
+        }
 
-        Ok(Self {
-            course_id: course_id.to_string(),
-            course_name,
-            title: String::new(),
-            author: config.author.clone(),
-            date: Local::now().format("%Y-%m-%d").to_string(),
-            semester,
-            template_version: config.template_version.clone(),
-            sections: Vec::new(),
-            custom_fields: HashMap::new(),
-            template_config: Some(template_config.clone()),
-            engine_config,
-            template_dir: config.paths.templates_dir.clone(),
-            variables,
-            metadata: TemplateMetadata {
-                course_type,
-                assignment_type: None,
-                creation_date: Local::now(),
-                template_source: "custom".to_string(),
-                variant_used: None,
-                processing_flags: vec![],
-            },
-        })
+        // Lecture notes are personal, so the course's group is not applied to them
+        let course_group = overrides
+            .group
+            .as_deref()
+            .filter(|_| template_name != "note");
+        self.set_authors(config, group.or(course_group), &overrides.co_authors)?;
+
+        for (key, value) in &overrides.parameters {
+            self.set_variable(key, value);
+        }
+        Ok(())
+    }
+
+    /// Set the authors from a group and the course's co-authors
+    ///
+    /// Without a group the user is the only author. With one, the group's
+    /// members are used, with the user first unless they are already listed.
+    /// Co-authors are added last. Study numbers are validated, and `author`
+    /// becomes the comma-separated list of names.
+    pub fn set_authors(
+        &mut self,
+        config: &Config,
+        group: Option<&str>,
+        co_authors: &[String],
+    ) -> Result<()> {
+        let user = Self::config_author(config);
+        let mut authors = match group {
+            Some(group) => {
+                let members = config.groups.get(group).ok_or_else(|| {
+                    let mut available: Vec<_> = config.groups.keys().map(String::as_str).collect();
+                    available.sort();
+                    anyhow!(
+                        "Group '{}' not found. Available groups: {}",
+                        group,
+                        if available.is_empty() {
+                            "none (add them under \"groups\" with 'noter config edit')".to_string()
+                        } else {
+                            available.join(", ")
+                        }
+                    )
+                })?;
+                let mut authors = members.clone();
+                if !authors.iter().any(|member| member.name == user.name) {
+                    authors.insert(0, user);
+                }
+                authors
+            }
+            None => vec![user],
+        };
+        authors.extend(co_authors.iter().map(|name| Author::new(name, None)));
+
+        for author in &authors {
+            if let Some(student_id) = &author.student_id {
+                Validator::validate_student_id(student_id)
+                    .with_context(|| format!("Invalid study number for {}", author.name))?;
+            }
+        }
+
+        self.author = authors
+            .iter()
+            .map(|author| author.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.set_variable("author", &self.author.clone());
+        if !co_authors.is_empty() {
+            self.set_variable("co_authors", &co_authors.join(", "));
+        }
+        if let Some(group) = group {
+            self.set_variable("group", group);
+        }
+        self.authors = authors;
+        Ok(())
+    }
+
+    /// The user as an author
+    fn config_author(config: &Config) -> Author {
+        Author::new(&config.author, config.student_id.as_deref())
+    }
+
+    /// Set the title from the course's `title_format`, if it has one
+    pub fn apply_title_format(&mut self, config: &Config) -> Result<()> {
+        let Some(format) = config.course_overrides(&self.course_id).title_format else {
+            return Ok(());
+        };
+
+        let title = self
+            .substitute_variables(&format)
+            .with_context(|| format!("Invalid title format '{}'", format))?;
+        self.set_variable("title", &title);
+        self.title = title;
+        Ok(())
     }
 
     /// Add or update a template variable
@@ -210,81 +238,194 @@ impl TemplateContext {
         self.variables.insert(key.to_string(), value.to_string());
     }
 
-    /// Get a template variable with fallback
+    /// Get a template variable, honouring the engine's case sensitivity setting
     pub fn get_variable(&self, key: &str) -> Option<&String> {
-        self.variables.get(key)
+        self.resolve_variable_key(key)
+            .and_then(|resolved| self.variables.get(&resolved))
     }
 
     /// Apply variable transformations based on engine config
+    ///
+    /// Each transformation targets the variables listed in its `variables`
+    /// (comma-separated) or `variable` parameter. Without either, the
+    /// transformation name itself is used as the variable name. Targets that
+    /// don't exist in the context are skipped.
     pub fn apply_transformations(&mut self) -> Result<()> {
-        // Apply transformations defined in engine config
-        for _transformation in &self.engine_config.variables.transformations {
-            // Implementation for applying transformations
-            // This would handle things like uppercase, lowercase, date formatting, etc.
+        let transformations = self.engine_config.variables.transformations.clone();
+
+        for transformation in &transformations {
+            for target in Self::transformation_targets(transformation) {
+                let Some(key) = self.resolve_variable_key(&target) else {
+                    continue;
+                };
+
+                let value = &self.variables[&key];
+                let transformed =
+                    Self::transform_value(value, transformation).with_context(|| {
+                        format!(
+                            "Transformation '{}' failed for variable '{}'",
+                            transformation.name, key
+                        )
+                    })?;
+                self.variables.insert(key, transformed);
+            }
         }
+
         Ok(())
     }
 
-    /// Validate context against engine requirements
-    pub fn validate(&self) -> Result<Vec<String>> {
-        let mut warnings = Vec::new();
+    /// Replace variable placeholders (e.g. `{{course_id}}`) in a string
+    ///
+    /// The placeholder syntax comes from the engine's `substitution_syntax`.
+    /// Unknown variables are replaced with `undefined_default` when
+    /// `allow_undefined` is set, and are an error otherwise.
+    pub fn substitute_variables(&self, text: &str) -> Result<String> {
+        self.substitute_variables_with(text, str::to_string)
+    }
 
-        // Basic validation
-        if self.author.is_empty() {
-            warnings.push("Author name is empty".to_string());
-        }
+    /// Replace variable placeholders, passing each value through `escape`
+    ///
+    /// Used where the text is Typst markup written by the template, so values
+    /// can't change its structure.
+    pub fn substitute_variables_with(
+        &self,
+        text: &str,
+        escape: impl Fn(&str) -> String,
+    ) -> Result<String> {
+        let variable_config = &self.engine_config.variables;
+        let Some((open, close)) = variable_config.substitution_syntax.split_once("var") else {
+            return Ok(text.to_string());
+        };
 
-        if self.course_name.is_empty() {
-            warnings.push(format!("Course name not found for {}", self.course_id));
+        if open.is_empty() || close.is_empty() {
+            return Ok(text.to_string());
         }
 
-        // Engine-specific validation
-        if self.engine_config.validation.validate_variables {
-            for required_var in &self.engine_config.variables.builtin_variables {
-                if !self.variables.contains_key(required_var) {
-                    warnings.push(format!("Required variable '{}' is missing", required_var));
-                }
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find(open) {
+            let after_open = &rest[start + open.len()..];
+            let Some(end) = after_open.find(close) else {
+                break;
+            };
+
+            result.push_str(&rest[..start]);
+            let name = after_open[..end].trim();
+
+            match self.get_variable(name) {
+                Some(value) => result.push_str(&escape(value)),
+                None if variable_config.allow_undefined => match &variable_config.undefined_default
+                {
+                    Some(default) => result.push_str(&escape(default)),
+                    None => result.push_str(&rest[start..start + open.len() + end + close.len()]),
+                },
+                None => anyhow::bail!("Undefined template variable '{}' in '{}'", name, text),
             }
+
+            rest = &after_open[end + close.len()..];
         }
 
-        Ok(warnings)
+        result.push_str(rest);
+        Ok(result)
     }
 
     // Helper methods
-    fn resolve_course_name(course_id: &str, config: &Config) -> String {
-        config.get_course_name(course_id)
-    }
+    fn resolve_variable_key(&self, key: &str) -> Option<String> {
+        if self.variables.contains_key(key) {
+            return Some(key.to_string());
+        }
 
-    fn determine_course_type(course_id: &str) -> String {
-        match course_id {
-            code if code.starts_with("01") => "math".to_string(),
-            code if code.starts_with("02") => "programming".to_string(),
-            code if code.starts_with("25") => "physics".to_string(),
-            code if code.starts_with("22") => "electronics".to_string(),
-            code if code.starts_with("28") => "environment".to_string(),
-            code if code.starts_with("31") => "mechanics".to_string(),
-            _ => "general".to_string(),
+        if self.engine_config.variables.case_sensitive {
+            return None;
         }
-    }
 
-    fn determine_assignment_type(title: &str) -> String {
-        let title_lower = title.to_lowercase();
+        self.variables
+            .keys()
+            .find(|existing| existing.eq_ignore_ascii_case(key))
+            .cloned()
+    }
 
-        if title_lower.contains("programming") || title_lower.contains("code") {
-            "programming".to_string()
-        } else if title_lower.contains("analysis") || title_lower.contains("research") {
-            "research".to_string()
-        } else if title_lower.contains("problem") || title_lower.contains("exercise") {
-            "theoretical".to_string()
-        } else if title_lower.contains("lab") || title_lower.contains("experiment") {
-            "practical".to_string()
+    fn transformation_targets(transformation: &VariableTransformation) -> Vec<String> {
+        let parameters = &transformation.parameters;
+
+        if let Some(variables) = parameters.get("variables") {
+            variables
+                .split(',')
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect()
+        } else if let Some(variable) = parameters.get("variable") {
+            vec![variable.trim().to_string()]
         } else {
-            "general".to_string()
+            vec![transformation.name.clone()]
         }
     }
 
+    fn transform_value(value: &str, transformation: &VariableTransformation) -> Result<String> {
+        let parameters = &transformation.parameters;
+
+        let transformed = match &transformation.transformation_type {
+            TransformationType::Uppercase => value.to_uppercase(),
+            TransformationType::Lowercase => value.to_lowercase(),
+            TransformationType::TitleCase => value
+                .split_whitespace()
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first.to_uppercase().chain(chars).collect(),
+                        None => String::new(),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" "),
+            TransformationType::DateFormat => {
+                let format = parameters
+                    .get("format")
+                    .ok_or_else(|| anyhow!("DateFormat requires a 'format' parameter"))?;
+                let input_format = parameters
+                    .get("input_format")
+                    .map(String::as_str)
+                    .unwrap_or("%Y-%m-%d");
+
+                NaiveDate::parse_from_str(value, input_format)
+                    .with_context(|| {
+                        format!("'{}' is not a date in format '{}'", value, input_format)
+                    })?
+                    .format(format)
+                    .to_string()
+            }
+            TransformationType::RegexReplace => {
+                let pattern = parameters
+                    .get("pattern")
+                    .ok_or_else(|| anyhow!("RegexReplace requires a 'pattern' parameter"))?;
+                let replacement = parameters
+                    .get("replacement")
+                    .map(String::as_str)
+                    .unwrap_or("");
+
+                Regex::new(pattern)
+                    .with_context(|| format!("Invalid regex pattern '{}'", pattern))?
+                    .replace_all(value, replacement)
+                    .to_string()
+            }
+            TransformationType::Custom(name) => match name.as_str() {
+                "trim" => value.trim().to_string(),
+                "slug" => Validator::sanitize_filename(value),
+                other => anyhow::bail!("Unknown custom transformation '{}'", other),
+            },
+        };
+
+        Ok(transformed)
+    }
+
+    fn resolve_course_name(course_id: &str, config: &Config) -> String {
+        config.get_course_name(course_id)
+    }
+
     fn build_builtin_variables(
         course_id: &str,
+        course_name: &str,
         title: &str,
         author: &str,
         semester: &str,
@@ -292,6 +433,7 @@ impl TemplateContext {
         let mut variables = HashMap::new();
 
         variables.insert("course_id".to_string(), course_id.to_string());
+        variables.insert("course_name".to_string(), course_name.to_string());
         variables.insert("title".to_string(), title.to_string());
         variables.insert("author".to_string(), author.to_string());
         variables.insert("semester".to_string(), semester.to_string());
@@ -313,6 +455,7 @@ impl Default for TemplateMetadata {
             creation_date: Local::now(),
             template_source: "unknown".to_string(),
             variant_used: None,
+            default_variant: None,
             processing_flags: vec![],
         }
     }
@@ -324,7 +467,10 @@ pub struct TemplateContextBuilder {
     course_id: Option<String>,
     config: Option<Config>,
     template_config: Option<TemplateConfig>,
+    template_name: Option<String>,
+    group: Option<String>,
     title: Option<String>,
+    default_title: Option<String>,
     custom_fields: HashMap<String, String>,
     sections: Option<Vec<String>>,
     variables: HashMap<String, String>,
@@ -336,7 +482,10 @@ impl TemplateContextBuilder {
             course_id: None,
             config: None,
             template_config: None,
+            template_name: None,
+            group: None,
             title: None,
+            default_title: None,
             custom_fields: HashMap::new(),
             sections: None,
             variables: HashMap::new(),
@@ -362,11 +511,29 @@ impl TemplateContextBuilder {
         self
     }
 
+    /// Template the context is built for, used to apply the course's settings
+    pub fn with_template_name(mut self, template_name: &str) -> Self {
+        self.template_name = Some(template_name.to_string());
+        self
+    }
+
+    /// Group whose members are the authors, instead of the course's group
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+
     pub fn with_title(mut self, title: &str) -> Self {
         self.title = Some(title.to_string());
         self
     }
 
+    /// Title used when none is set and the course has no `title_format`
+    pub fn with_default_title(mut self, title: &str) -> Self {
+        self.default_title = Some(title.to_string());
+        self
+    }
+
     pub fn with_sections(mut self, sections: Vec<String>) -> Self {
         self.sections = Some(sections);
         self
@@ -395,7 +562,15 @@ impl TemplateContextBuilder {
         let mut context =
             TemplateContext::build_custom_context(&course_id, &config, &template_config)?;
 
-        if let Some(title) = self.title {
+        if let Some(template_name) = &self.template_name {
+            context.apply_course_overrides(&config, template_name, self.group.as_deref())?;
+        } else if let Some(group) = &self.group {
+            context.set_authors(&config, Some(group), &[])?;
+        }
+
+        let has_title = self.title.is_some();
+        if let Some(title) = self.title.or(self.default_title) {
+            context.set_variable("title", &title);
             context.title = title;
         }
 
@@ -407,6 +582,11 @@ impl TemplateContextBuilder {
         context.custom_fields.extend(self.custom_fields);
         context.variables.extend(self.variables);
 
+        // Title formats may refer to variables given on the command line
+        if !has_title {
+            context.apply_title_format(&config)?;
+        }
+
         Ok(context)
     }
 }
@@ -432,6 +612,252 @@ impl Default for TemplateConfig {
             variants: None,
             course_mapping: None,
             engine: None,
+            package_dir: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context_with_transformations(
+        transformations: Vec<VariableTransformation>,
+    ) -> TemplateContext {
+        let mut context = TemplateContext::build_custom_context(
+            "02101",
+            &Config::default(),
+            &TemplateConfig::default(),
+        )
+        .unwrap();
+        context.engine_config.variables.transformations = transformations;
+        context
+    }
+
+    fn transformation(
+        name: &str,
+        transformation_type: TransformationType,
+        parameters: &[(&str, &str)],
+    ) -> VariableTransformation {
+        VariableTransformation {
+            name: name.to_string(),
+            transformation_type,
+            parameters: parameters
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
         }
     }
+
+    #[test]
+    fn test_apply_transformations() {
+        let mut context = context_with_transformations(vec![
+            transformation(
+                "upper",
+                TransformationType::Uppercase,
+                &[("variables", "author, course_name")],
+            ),
+            transformation("title", TransformationType::TitleCase, &[]),
+            transformation(
+                "date",
+                TransformationType::DateFormat,
+                &[("variable", "date"), ("format", "%d/%m/%Y")],
+            ),
+            transformation(
+                "strip",
+                TransformationType::RegexReplace,
+                &[
+                    ("variable", "semester"),
+                    ("pattern", r"\s+"),
+                    ("replacement", "-"),
+                ],
+            ),
+        ]);
+        context.set_variable("title", "graph theory basics");
+        context.set_variable("date", "2025-02-03");
+        context.set_variable("semester", "2025 Spring");
+
+        context.apply_transformations().unwrap();
+
+        assert_eq!(context.get_variable("author").unwrap(), "YOUR NAME");
+        assert_eq!(
+            context.get_variable("course_name").unwrap(),
+            "INTRODUCTION TO PROGRAMMING"
+        );
+        assert_eq!(
+            context.get_variable("title").unwrap(),
+            "Graph Theory Basics"
+        );
+        assert_eq!(context.get_variable("date").unwrap(), "03/02/2025");
+        assert_eq!(context.get_variable("semester").unwrap(), "2025-Spring");
+    }
+
+    #[test]
+    fn test_transformations_skip_unknown_targets() {
+        let mut context = context_with_transformations(vec![transformation(
+            "uppercase",
+            TransformationType::Uppercase,
+            &[],
+        )]);
+        let before = context.variables.clone();
+
+        context.apply_transformations().unwrap();
+
+        assert_eq!(context.variables, before);
+    }
+
+    #[test]
+    fn test_unknown_custom_transformation_fails() {
+        let mut context = context_with_transformations(vec![transformation(
+            "author",
+            TransformationType::Custom("rot13".to_string()),
+            &[],
+        )]);
+
+        assert!(context.apply_transformations().is_err());
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let mut context = context_with_transformations(vec![]);
+        assert_eq!(
+            context
+                .substitute_variables("Notes for {{ course_id }}")
+                .unwrap(),
+            "Notes for 02101"
+        );
+        assert!(context.substitute_variables("{{missing}}").is_err());
+
+        context.engine_config.variables.allow_undefined = true;
+        context.engine_config.variables.undefined_default = Some("?".to_string());
+        assert_eq!(context.substitute_variables("{{missing}}!").unwrap(), "?!");
+    }
+
+    #[test]
+    fn test_variable_lookup_case_sensitivity() {
+        let mut context = context_with_transformations(vec![]);
+        assert!(context.get_variable("COURSE_ID").is_some());
+
+        context.engine_config.variables.case_sensitive = true;
+        assert!(context.get_variable("COURSE_ID").is_none());
+    }
+
+    #[test]
+    fn test_course_overrides() {
+        use crate::config::CourseOverrides;
+
+        let mut config = Config {
+            author: "Ada".to_string(),
+            ..Default::default()
+        };
+        config.course_overrides.insert(
+            "02101".to_string(),
+            CourseOverrides {
+                variants: [("note".to_string(), "programming".to_string())].into(),
+                sections: [("note".to_string(), vec!["Code".to_string()])].into(),
+                title_format: Some("Week {{week}}".to_string()),
+                co_authors: vec!["Grace".to_string()],
+                group: None,
+                parameters: [("week".to_string(), "4".to_string())].into(),
+            },
+        );
+
+        let build = |builder: TemplateContextBuilder| {
+            builder
+                .with_course_id("02101")
+                .with_config(config.clone())
+                .with_template_name("note")
+                .with_default_title("Lecture")
+                .build()
+                .unwrap()
+        };
+
+        let context = build(TemplateContext::builder());
+        assert_eq!(context.title, "Week 4");
+        assert_eq!(context.author, "Ada, Grace");
+        assert_eq!(context.sections, vec!["Code".to_string()]);
+        assert_eq!(
+            context.metadata.default_variant.as_deref(),
+            Some("programming")
+        );
+
+        // Values from the command line win
+        let context = build(
+            TemplateContext::builder()
+                .with_title("Recursion")
+                .with_sections(vec![])
+                .with_variable("week", "5"),
+        );
+        assert_eq!(context.title, "Recursion");
+        assert!(context.sections.is_empty());
+        assert_eq!(context.get_variable("week").unwrap(), "5");
+
+        // Other courses are unaffected
+        let context = TemplateContext::builder()
+            .with_course_id("01005")
+            .with_config(config.clone())
+            .with_template_name("note")
+            .with_default_title("Lecture")
+            .build()
+            .unwrap();
+        assert_eq!(context.title, "Lecture");
+        assert_eq!(context.author, "Ada");
+    }
+
+    #[test]
+    fn test_group_authors() {
+        let mut config = Config {
+            author: "Ada".to_string(),
+            student_id: Some("s234567".to_string()),
+            ..Default::default()
+        };
+        config.groups.insert(
+            "team-a".to_string(),
+            vec![
+                Author::new("Grace", Some("s231234")),
+                Author::new("Alan", None),
+            ],
+        );
+
+        let build = |config: &Config, group: Option<&str>| {
+            let mut builder = TemplateContext::builder()
+                .with_course_id("02101")
+                .with_config(config.clone())
+                .with_template_name("assignment");
+            if let Some(group) = group {
+                builder = builder.with_group(group);
+            }
+            builder.build()
+        };
+
+        let context = build(&config, None).unwrap();
+        assert_eq!(context.authors, vec![Author::new("Ada", Some("s234567"))]);
+
+        let context = build(&config, Some("team-a")).unwrap();
+        assert_eq!(context.author, "Ada, Grace, Alan");
+        assert_eq!(context.authors.len(), 3);
+        assert_eq!(context.get_variable("group").unwrap(), "team-a");
+
+        // The course's group is used without --group
+        config.course_overrides.insert(
+            "02101".to_string(),
+            crate::config::CourseOverrides {
+                group: Some("team-a".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(build(&config, None).unwrap().author, "Ada, Grace, Alan");
+        let note = TemplateContext::builder()
+            .with_course_id("02101")
+            .with_config(config.clone())
+            .with_template_name("note")
+            .build()
+            .unwrap();
+        assert_eq!(note.author, "Ada");
+
+        assert!(build(&config, Some("team-b")).is_err());
+
+        config.groups.get_mut("team-a").unwrap()[1].student_id = Some("123456".to_string());
+        assert!(build(&config, None).is_err());
+    }
 }
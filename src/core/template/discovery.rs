@@ -5,8 +5,9 @@
 
 use super::config::{TemplateConfig, TemplateDefinition, TemplateVariant};
 use super::constants::TOML_FILE_NAME;
+use super::versions::VersionHistory;
 use crate::config::Config;
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use semver::Version;
 use std::path::{Path, PathBuf};
 
@@ -68,41 +69,193 @@ pub struct TemplateDiscovery;
 impl TemplateDiscovery {
     /// Load all template configurations from template packages
     pub fn load_template_configs(user_config: &Config) -> Result<Vec<TemplateConfig>> {
+        Ok(Self::load_installed_packages(user_config)?
+            .into_iter()
+            .map(|(_, config)| config)
+            .collect())
+    }
+
+    /// Load every installed package configuration together with its directory
+    fn load_installed_packages(user_config: &Config) -> Result<Vec<(PathBuf, TemplateConfig)>> {
         let typst_packages_dir = Path::new(&user_config.paths.typst_packages_dir);
 
         let template_package_dirs = Self::find_all_template_packages(typst_packages_dir)?;
-        let mut configs = Vec::new();
+        let mut packages = Vec::new();
 
         for package_dir in template_package_dirs {
             let config_path = package_dir.join(TOML_FILE_NAME);
 
             if config_path.exists() {
                 let content = std::fs::read_to_string(&config_path)?;
-                let package_config: TemplateConfig = toml::from_str(&content)?;
+                let mut package_config: TemplateConfig = toml::from_str(&content)?;
+                package_config.package_dir = Some(package_dir.clone());
 
-                configs.push(package_config);
+                packages.push((package_dir, package_config));
+            }
+        }
+
+        Ok(packages)
+    }
+
+    /// Active (or newest) version of every installed package, most preferred first
+    ///
+    /// Packages are ordered by `templates.preference_order`; packages not
+    /// listed there come last, sorted by name.
+    pub fn load_preferred_configs(user_config: &Config) -> Result<Vec<TemplateConfig>> {
+        let mut newest: Vec<(PathBuf, TemplateConfig)> = Vec::new();
+
+        for (dir, config) in Self::load_installed_packages(user_config)? {
+            match newest
+                .iter_mut()
+                .find(|(_, existing)| existing.metadata.name == config.metadata.name)
+            {
+                Some(existing) => {
+                    if Self::is_preferred_version(&dir, &config, &existing.0, &existing.1) {
+                        *existing = (dir, config);
+                    }
+                }
+                None => newest.push((dir, config)),
             }
         }
 
-        Ok(configs)
+        newest.sort_by(|(dir_a, a), (dir_b, b)| {
+            Self::preference_rank(user_config, dir_a, a)
+                .cmp(&Self::preference_rank(user_config, dir_b, b))
+                .then_with(|| a.metadata.name.cmp(&b.metadata.name))
+        });
+
+        Ok(newest.into_iter().map(|(_, config)| config).collect())
+    }
+
+    /// Whether a version should replace another version of the same package
+    ///
+    /// The version made active by `noter template rollback` wins, otherwise
+    /// the newest.
+    fn is_preferred_version(
+        candidate_dir: &Path,
+        candidate: &TemplateConfig,
+        existing_dir: &Path,
+        existing: &TemplateConfig,
+    ) -> bool {
+        let is_active = |dir: &Path| {
+            let active = dir
+                .parent()
+                .and_then(|package_dir| VersionHistory::load(package_dir).active);
+            active.is_some_and(|active| dir.file_name().is_some_and(|name| *name == *active))
+        };
+
+        match (is_active(candidate_dir), is_active(existing_dir)) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => Self::compare_template_versions(
+                &candidate.metadata.version,
+                &existing.metadata.version,
+            )
+            .is_gt(),
+        }
+    }
+
+    /// Position of a package in `preference_order` (usize::MAX if not listed)
+    ///
+    /// Entries name a repository or a package. Repositories are installed to
+    /// `<typst_packages_dir>/<repository name>/`, the official one ("official")
+    /// to `dtu-template/`.
+    fn preference_rank(user_config: &Config, package_dir: &Path, config: &TemplateConfig) -> usize {
+        let install_name = package_dir
+            .strip_prefix(&user_config.paths.typst_packages_dir)
+            .ok()
+            .and_then(|relative| relative.components().next())
+            .and_then(|component| component.as_os_str().to_str())
+            .unwrap_or_default();
+
+        user_config
+            .templates
+            .preference_order
+            .iter()
+            .position(|entry| {
+                let entry = match entry.as_str() {
+                    "official" | "dtu_template" => "dtu-template",
+                    other => other,
+                };
+                entry == install_name || entry == config.metadata.name
+            })
+            .unwrap_or(usize::MAX)
     }
-    // TODO: This loads ALL templates, but realistically we should only load the one the user wants as primary.
 
-    /// Backwards compatibility - returns newest config or default
+    /// The most preferred package, or the default config if none is installed
     pub fn load_template_config(user_config: &Config) -> Result<TemplateConfig> {
-        let mut configs = Self::load_template_configs(user_config)?;
+        Ok(Self::load_preferred_configs(user_config)?
+            .into_iter()
+            .next()
+            .unwrap_or_default())
+    }
 
-        if configs.is_empty() {
-            return Ok(TemplateConfig::default());
+    /// The package to render a template from
+    ///
+    /// An explicit `package` must be installed. Otherwise the most preferred
+    /// package defining `template_name` is used, falling back to the most
+    /// preferred package overall.
+    pub fn resolve_template_package(
+        user_config: &Config,
+        template_name: &str,
+        package: Option<&str>,
+    ) -> Result<TemplateConfig> {
+        let mut configs = Self::load_preferred_configs(user_config)?;
+
+        if let Some(package) = package {
+            let installed: Vec<String> = configs.iter().map(|c| c.metadata.name.clone()).collect();
+            return configs
+                .into_iter()
+                .find(|config| config.metadata.name == package)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Template package '{}' is not installed (installed: {})",
+                        package,
+                        if installed.is_empty() {
+                            "none".to_string()
+                        } else {
+                            installed.join(", ")
+                        }
+                    )
+                });
         }
 
-        // Sort by semantic version to get the newest one
-        configs.sort_by(|a, b| {
-            Self::compare_template_versions(&a.metadata.version, &b.metadata.version)
-        });
+        let index = configs
+            .iter()
+            .position(|config| config.templates.iter().any(|t| t.name == template_name))
+            .unwrap_or(0);
 
-        // Take the last one (highest version)
-        Ok(configs.into_iter().last().unwrap())
+        Ok(if configs.is_empty() {
+            TemplateConfig::default()
+        } else {
+            configs.swap_remove(index)
+        })
+    }
+
+    /// Template names defined by more than one package, with the packages in preference order
+    pub fn find_template_conflicts(configs: &[TemplateConfig]) -> Vec<(String, Vec<String>)> {
+        let mut conflicts: Vec<(String, Vec<String>)> = Vec::new();
+
+        for config in configs {
+            for template in &config.templates {
+                match conflicts
+                    .iter_mut()
+                    .find(|(name, _)| *name == template.name)
+                {
+                    Some((_, packages)) => {
+                        if !packages.contains(&config.metadata.name) {
+                            packages.push(config.metadata.name.clone());
+                        }
+                    }
+                    None => {
+                        conflicts.push((template.name.clone(), vec![config.metadata.name.clone()]))
+                    }
+                }
+            }
+        }
+
+        conflicts.retain(|(_, packages)| packages.len() > 1);
+        conflicts
     }
 
     /// Compare semantic versions using the semver crate
@@ -191,18 +344,24 @@ impl TemplateDiscovery {
     }
 
     /// Discover all available templates
+    ///
+    /// Only the active version of each package is considered, in preference order.
     pub fn discover_templates(user_config: &Config) -> Result<Vec<AvailableTemplate>> {
-        let configs = Self::load_template_configs(user_config)?;
+        let configs = Self::load_preferred_configs(user_config)?;
         let mut available_templates = Vec::new();
 
         for config in &configs {
             let package_dir = Self::find_package_directory_for_config(user_config, config)?;
 
             for template_def in &config.templates {
-                let variants = Self::find_variants_for_template(&configs, &template_def.name)
-                    .into_iter()
-                    .cloned()
-                    .collect();
+                // Variants only apply to templates from their own package
+                let variants = Self::find_variants_for_template(
+                    std::slice::from_ref(config),
+                    &template_def.name,
+                )
+                .into_iter()
+                .cloned()
+                .collect();
 
                 let file_path = package_dir.join(&template_def.file);
 
@@ -224,6 +383,23 @@ impl TemplateDiscovery {
         Ok(available_templates)
     }
 
+    /// Output directories declared by the templates of the installed packages
+    ///
+    /// Used to include documents created with `noter new` in course statistics.
+    pub fn document_directories(user_config: &Config) -> Vec<String> {
+        let mut dirs: Vec<String> = Self::load_preferred_configs(user_config)
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|config| &config.templates)
+            .filter_map(|template| template.output_directory().ok())
+            .map(|dir| dir.to_string_lossy().to_string())
+            .collect();
+
+        dirs.sort();
+        dirs.dedup();
+        dirs
+    }
+
     /// Find all template packages in the typst packages directory
     fn find_all_template_packages(typst_packages_dir: &Path) -> Result<Vec<PathBuf>> {
         let mut template_packages = Vec::new();
@@ -270,25 +446,24 @@ impl TemplateDiscovery {
         let package_dirs = Self::find_all_template_packages(typst_packages_dir)?;
 
         // Find the directory that contains this specific config
-        for package_dir in package_dirs.clone() {
+        for package_dir in package_dirs {
             let config_path = package_dir.join(TOML_FILE_NAME);
             if config_path.exists() {
                 let content = std::fs::read_to_string(&config_path)?;
-                if let Ok(config) = toml::from_str::<TemplateConfig>(&content) {
-                    if config.metadata.name == target_config.metadata.name
-                        && config.metadata.version == target_config.metadata.version
-                    {
-                        return Ok(package_dir);
-                    }
+                if let Ok(config) = toml::from_str::<TemplateConfig>(&content)
+                    && config.metadata.name == target_config.metadata.name
+                    && config.metadata.version == target_config.metadata.version
+                {
+                    return Ok(package_dir);
                 }
             }
         }
 
-        // Fallback: return first package directory if exact match not found
-        package_dirs
-            .into_iter()
-            .next()
-            .ok_or_else(|| anyhow::anyhow!("No template packages found"))
+        Err(anyhow!(
+            "Template package {} {} is not installed",
+            target_config.metadata.name,
+            target_config.metadata.version
+        ))
     }
 
     /// Extract package information from config and directory
@@ -381,6 +556,19 @@ impl TemplateDiscovery {
         // Return the first matching variant
         matching_variants.first().map(|v| (*v).clone())
     }
+
+    /// The course type and variant `noter note`/`noter assignment` use for a
+    /// template from this package when no variant is given
+    pub fn auto_selected_variant(
+        config: &TemplateConfig,
+        template_name: &str,
+        course_id: &str,
+    ) -> (String, Option<TemplateVariant>) {
+        let configs = std::slice::from_ref(config);
+        let course_type = Self::resolve_course_type(configs, course_id, "general");
+        let variant = Self::find_best_variant(configs, template_name, &course_type);
+        (course_type, variant)
+    }
 }
 
 #[cfg(test)]
@@ -496,6 +684,7 @@ mod tests {
             variants: None,
             course_mapping: Some(course_mapping1),
             engine: None,
+            package_dir: None,
         };
 
         let config2 = TemplateConfig {
@@ -511,6 +700,7 @@ mod tests {
             variants: None,
             course_mapping: Some(course_mapping2),
             engine: None,
+            package_dir: None,
         };
 
         let configs = vec![config1, config2];
@@ -536,6 +726,90 @@ mod tests {
         println!("✅ Course type resolution works correctly");
     }
 
+    fn config_with_packages(dir: &Path, preference_order: &[&str]) -> Config {
+        use crate::core::template::scaffold::PackageScaffold;
+
+        for (name, version) in [
+            ("alpha-notes", "1.0.0"),
+            ("beta-notes", "0.1.0"),
+            ("beta-notes", "0.2.0"),
+        ] {
+            let package_dir = dir.join(name).join(version);
+            if !package_dir.exists() {
+                let mut scaffold = PackageScaffold::new(name, "").unwrap();
+                scaffold.version = version.to_string();
+                scaffold.write_to(&package_dir).unwrap();
+            }
+        }
+
+        let mut config = Config::default();
+        config.paths.typst_packages_dir = dir.to_string_lossy().to_string();
+        config.templates.preference_order =
+            preference_order.iter().map(|s| s.to_string()).collect();
+        config
+    }
+
+    #[test]
+    fn test_preference_order_resolution() {
+        let dir = tempfile::TempDir::new().unwrap();
+
+        let config = config_with_packages(dir.path(), &["beta-notes"]);
+        let configs = TemplateDiscovery::load_preferred_configs(&config).unwrap();
+        let names: Vec<_> = configs.iter().map(|c| c.metadata.name.as_str()).collect();
+        assert_eq!(names, vec!["beta-notes", "alpha-notes"]);
+        assert_eq!(configs[0].metadata.version, "0.2.0");
+
+        let resolved = TemplateDiscovery::resolve_template_package(&config, "note", None).unwrap();
+        assert_eq!(resolved.metadata.name, "beta-notes");
+
+        let explicit =
+            TemplateDiscovery::resolve_template_package(&config, "note", Some("alpha-notes"))
+                .unwrap();
+        assert_eq!(explicit.metadata.name, "alpha-notes");
+        assert!(
+            TemplateDiscovery::resolve_template_package(&config, "note", Some("missing")).is_err()
+        );
+
+        let unordered = config_with_packages(dir.path(), &[]);
+        let resolved =
+            TemplateDiscovery::resolve_template_package(&unordered, "note", None).unwrap();
+        assert_eq!(resolved.metadata.name, "alpha-notes");
+    }
+
+    #[test]
+    fn test_template_conflicts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = config_with_packages(dir.path(), &["alpha-notes"]);
+        let configs = TemplateDiscovery::load_preferred_configs(&config).unwrap();
+
+        let conflicts = TemplateDiscovery::find_template_conflicts(&configs);
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].0, "note");
+        assert_eq!(conflicts[0].1, vec!["alpha-notes", "beta-notes"]);
+    }
+
+    #[test]
+    fn test_auto_selected_variant() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = config_with_packages(dir.path(), &[]);
+        let package = TemplateDiscovery::resolve_template_package(&config, "note", None).unwrap();
+
+        let (course_type, variant) =
+            TemplateDiscovery::auto_selected_variant(&package, "note", "01005");
+        assert_eq!(course_type, "math");
+        assert_eq!(variant.unwrap().name, "math");
+
+        let (course_type, variant) =
+            TemplateDiscovery::auto_selected_variant(&package, "note", "42000");
+        assert_eq!(course_type, "general");
+        assert!(variant.is_none());
+        assert!(
+            TemplateDiscovery::auto_selected_variant(&package, "assignment", "01005")
+                .1
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_pattern_matching() {
         assert!(TemplateDiscovery::matches_course_pattern("01005", "01xxx"));
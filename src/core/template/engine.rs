@@ -3,34 +3,16 @@
 //! The main TemplateEngine that handles template generation,
 //! discovery, and rendering.
 
-use super::config::{TemplateConfig, TemplateDefinition, TemplateVariant};
+use super::config::{
+    ParameterType, TemplateConfig, TemplateDefinition, TemplateParameter, TemplateVariant,
+};
 use super::context::TemplateContext;
-use super::discovery::TemplateDiscovery;
-use crate::config::Config;
 use anyhow::{Result, anyhow};
 use chrono::{Datelike, Local};
 
 pub struct TemplateEngine;
 
 impl TemplateEngine {
-    /// Generate a lecture template
-    pub fn generate_lecture_template(
-        course_id: &str,
-        config: &Config,
-        custom_title: Option<&str>,
-    ) -> Result<String> {
-        let template_config = TemplateDiscovery::load_template_config(config)?;
-        let context = TemplateContext::build_lecture_context(
-            course_id,
-            config,
-            &template_config,
-            custom_title,
-        )?;
-
-        let template_ref = TemplateReference::lecture();
-        Self::render_template(&context, &template_ref)
-    }
-
     /// Main template rendering function
     pub fn render_template(
         context: &TemplateContext,
@@ -67,6 +49,7 @@ impl TemplateEngine {
             document.push_str(&Self::generate_sections_from_context(context)?);
         } else {
             document.push_str(&Self::generate_sections_from_template(
+                context,
                 template_def,
                 variant,
             )?);
@@ -80,7 +63,10 @@ impl TemplateEngine {
         let mut sections = String::new();
 
         for section in &context.sections {
-            sections.push_str(&format!("= {}\n\n", section));
+            sections.push_str(&format!(
+                "= {}\n\n",
+                context.substitute_variables_with(section, typst_content)?
+            ));
         }
 
         Ok(sections)
@@ -121,16 +107,35 @@ impl TemplateEngine {
             now.day()
         );
 
-        // Build the standard parameters that all templates expect
-        let params = [
-            format!("course: \"{}\"", context.course_id),
-            format!("course-name: \"{}\"", context.course_name),
-            format!("title: \"{}\"", context.title),
+        // Build the standard parameters that all templates expect, taking values
+        // from the (possibly transformed) variables with the raw context as fallback
+        let variable = |key: &str, fallback: &str| {
+            typst_string(context.get_variable(key).map_or(fallback, String::as_str))
+        };
+
+        let mut params = vec![
+            format!("course: {}", variable("course_id", &context.course_id)),
+            format!(
+                "course-name: {}",
+                variable("course_name", &context.course_name)
+            ),
+            format!("title: {}", variable("title", &context.title)),
             date_str,
-            format!("author: \"{}\"", context.author),
-            format!("semester: \"{}\"", context.semester),
+            format!("author: {}", variable("author", &context.author)),
+            format!("semester: {}", variable("semester", &context.semester)),
         ];
 
+        if template_def.supports_authors {
+            params.push(format!("authors: {}", Self::typst_authors(context)));
+        }
+
+        // Append extra parameters declared by the template package
+        for parameter in &template_def.parameters {
+            if let Some(value) = Self::parameter_expression(context, parameter)? {
+                params.push(format!("{}: {}", parameter.name, value));
+            }
+        }
+
         let params_str = params.join(",\n  ");
 
         Ok(format!(
@@ -139,8 +144,118 @@ impl TemplateEngine {
         ))
     }
 
+    /// The Typst expression for a declared template parameter
+    ///
+    /// Values come from context variables first, then the parameter's default.
+    /// Parameters without either are filled with `undefined_default` (or left
+    /// out) when the engine allows undefined variables, and are an error otherwise.
+    fn parameter_expression(
+        context: &TemplateContext,
+        parameter: &TemplateParameter,
+    ) -> Result<Option<String>> {
+        if let Some(value) = context.get_variable(&parameter.name) {
+            return Self::format_parameter_value(parameter, value).map(Some);
+        }
+
+        if let Some(default) = &parameter.default {
+            // A content default is markup from the template; only the values
+            // substituted into it are escaped
+            if matches!(parameter.parameter_type, ParameterType::Content) {
+                let markup = context.substitute_variables_with(default, typst_content)?;
+                return Ok(Some(format!("[{}]", markup)));
+            }
+            let value = context.substitute_variables(default)?;
+            return Self::format_parameter_value(parameter, &value).map(Some);
+        }
+
+        let variable_config = &context.engine_config.variables;
+        if variable_config.allow_undefined {
+            return variable_config
+                .undefined_default
+                .as_deref()
+                .map(|value| Self::format_parameter_value(parameter, value))
+                .transpose();
+        }
+
+        Err(anyhow!(
+            "Template parameter '{}' has no value. Provide it with --var {}=<value>",
+            parameter.name,
+            parameter.name
+        ))
+    }
+
+    /// Format a parameter value as a Typst expression according to its type
+    fn format_parameter_value(parameter: &TemplateParameter, value: &str) -> Result<String> {
+        let invalid = |expected: &str| {
+            anyhow!(
+                "Template parameter '{}' expects {}, got '{}'",
+                parameter.name,
+                expected,
+                value
+            )
+        };
+
+        let formatted = match parameter.parameter_type {
+            ParameterType::String => typst_string(value),
+            ParameterType::Content => format!("[{}]", typst_content(value)),
+            ParameterType::Integer => {
+                value
+                    .trim()
+                    .parse::<i64>()
+                    .map_err(|_| invalid("an integer"))?;
+                value.trim().to_string()
+            }
+            ParameterType::Float => {
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| invalid("a number"))?;
+                value.trim().to_string()
+            }
+            ParameterType::Boolean => match value.trim().to_lowercase().as_str() {
+                "true" | "yes" | "1" => "true".to_string(),
+                "false" | "no" | "0" => "false".to_string(),
+                _ => return Err(invalid("a boolean")),
+            },
+            ParameterType::Date => {
+                let date = chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+                    .map_err(|_| invalid("a date (YYYY-MM-DD)"))?;
+                format!(
+                    "datetime(year: {}, month: {}, day: {})",
+                    date.year(),
+                    date.month(),
+                    date.day()
+                )
+            }
+        };
+
+        Ok(formatted)
+    }
+
+    /// Format the authors as a Typst array of `(name: .., student-id: ..)` dictionaries
+    fn typst_authors(context: &TemplateContext) -> String {
+        let authors: Vec<String> = context
+            .authors
+            .iter()
+            .map(|author| {
+                format!(
+                    "(name: {}, student-id: {})",
+                    typst_string(&author.name),
+                    author
+                        .student_id
+                        .as_deref()
+                        .map_or("none".to_string(), typst_string)
+                )
+            })
+            .collect();
+
+        // A trailing comma keeps a single element an array
+        format!("({},)", authors.join(", "))
+    }
+
     /// Generate sections based on template configuration
     fn generate_sections_from_template(
+        context: &TemplateContext,
         template_def: &TemplateDefinition,
         variant: Option<&TemplateVariant>,
     ) -> Result<String> {
@@ -155,14 +270,17 @@ impl TemplateEngine {
             }
 
             // Generate section header with empty content for user to fill
-            content.push_str(&format!("= {}\n\n", section));
+            content.push_str(&format!(
+                "= {}\n\n",
+                context.substitute_variables_with(section, typst_content)?
+            ));
         }
 
         Ok(content)
     }
 
     /// Get sections that should be created based on template and variant
-    fn get_template_sections(
+    pub fn get_template_sections(
         template_def: &TemplateDefinition,
         variant: Option<&TemplateVariant>,
     ) -> Vec<String> {
@@ -207,30 +325,34 @@ impl TemplateEngine {
         template_def: &TemplateDefinition,
         template_ref: &TemplateReference,
     ) -> Result<Option<TemplateVariant>> {
-        // If a specific variant is requested, try to find it
-        if let Some(variant_name) = &template_ref.variant {
+        // If a specific variant is requested (or set for the course), try to find it
+        if let Some(variant_name) = template_ref
+            .variant
+            .as_ref()
+            .or(context.metadata.default_variant.as_ref())
+        {
             return Self::find_specific_variant(context, template_def, variant_name);
         }
 
         // Only look for variants that belong to this template
-        if let Some(template_config) = &context.template_config {
-            if let Some(variants) = &template_config.variants {
-                let course_type = Self::resolve_course_type(context, template_config);
-
-                // Filter variants to only those belonging to the current template
-                let matching_variants: Vec<_> = variants
-                    .iter()
-                    .filter(|variant| variant.template == template_def.name) // THIS IS KEY!
-                    .filter(|variant| {
-                        variant.course_types.contains(&course_type)
-                            || variant.course_types.contains(&"all".to_string())
-                    })
-                    .collect();
-
-                // Return the best matching variant for this template, or None
-                if let Some(best_variant) = matching_variants.first() {
-                    return Ok(Some((*best_variant).clone()));
-                }
+        if let Some(template_config) = &context.template_config
+            && let Some(variants) = &template_config.variants
+        {
+            let course_type = Self::resolve_course_type(context, template_config);
+
+            // Filter variants to only those belonging to the current template
+            let matching_variants: Vec<_> = variants
+                .iter()
+                .filter(|variant| variant.template == template_def.name) // THIS IS KEY!
+                .filter(|variant| {
+                    variant.course_types.contains(&course_type)
+                        || variant.course_types.contains(&"all".to_string())
+                })
+                .collect();
+
+            // Return the best matching variant for this template, or None
+            if let Some(best_variant) = matching_variants.first() {
+                return Ok(Some((*best_variant).clone()));
             }
         }
 
@@ -243,13 +365,13 @@ impl TemplateEngine {
         template_def: &TemplateDefinition,
         variant_name: &str,
     ) -> Result<Option<TemplateVariant>> {
-        if let Some(template_config) = &context.template_config {
-            if let Some(variants) = &template_config.variants {
-                for variant in variants {
-                    // Must match both the template AND the variant name
-                    if variant.template == template_def.name && variant.name == variant_name {
-                        return Ok(Some(variant.clone()));
-                    }
+        if let Some(template_config) = &context.template_config
+            && let Some(variants) = &template_config.variants
+        {
+            for variant in variants {
+                // Must match both the template AND the variant name
+                if variant.template == template_def.name && variant.name == variant_name {
+                    return Ok(Some(variant.clone()));
                 }
             }
         }
@@ -342,3 +464,67 @@ impl TemplateReference {
         Self::new("thesis")
     }
 }
+
+/// Quote a value as a Typst string literal
+fn typst_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Escape a value for Typst markup, so it is shown as plain text
+///
+/// Characters with a meaning in markup (brackets, emphasis, code, math,
+/// labels, references, comments, headings and lists) get a backslash, and
+/// line breaks become spaces since they would end a heading.
+fn typst_content(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' | '#' | '[' | ']' | '*' | '_' | '`' | '$' | '<' | '>' | '@' | '~' | '/' | '='
+            | '-' | '+' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typst_string() {
+        assert_eq!(typst_string("Notes"), "\"Notes\"");
+        assert_eq!(
+            typst_string("The \"best\" C:\\ path\nnext"),
+            "\"The \\\"best\\\" C:\\\\ path\\nnext\""
+        );
+    }
+
+    #[test]
+    fn test_typst_content() {
+        assert_eq!(typst_content("Week 3: Graphs"), "Week 3: Graphs");
+        assert_eq!(typst_content("a] #panic()"), "a\\] \\#panic()");
+        assert_eq!(
+            typst_content("*bold* = $x$ // note"),
+            "\\*bold\\* \\= \\$x\\$ \\/\\/ note"
+        );
+        assert_eq!(typst_content("two\nlines"), "two lines");
+    }
+}
@@ -1,44 +1,43 @@
-//! GitHub template fetcher
+//! Template fetcher
 //!
-//! Handles downloading templates from multiple GitHub repositories with fallback support
-
-// TOOD: Rewrite this to also accept gitlab. and other hosting services.
-// Seems pretty big scope, so not sure how feasible this is.
-
-use crate::config::{Config, Metadata, ObsidianIntegrationConfig, TemplateRepository};
-use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
+//! Handles downloading templates from multiple repositories with fallback support.
+//! Releases are looked up through the repository's configured `ReleaseProvider`,
+//! or in the download cache when running offline.
+
+use super::cache::TemplateCache;
+use super::lockfile::{LockFile, LockedPackage, sha256_hex};
+use super::providers::{self, GitHubProvider, Release, ReleaseProvider, short_sha};
+use super::versions::{ImportRewriter, VersionHistory};
+use crate::config::{
+    Config, InstallSource, Metadata, ObsidianIntegrationConfig, TemplateRepository,
+};
+use anyhow::{Context, Result, anyhow};
 use std::fs;
 use std::path::{Path, PathBuf};
 
 const DEFAULT_TEMPLATE_REPO: &str = "HollowNumber/dtu-note-template";
-const GITHUB_API_BASE: &str = "https://api.github.com";
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct GitHubAsset {
-    pub name: String,
-    pub browser_download_url: String,
-    pub content_type: String,
-    pub size: u64,
-}
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct GitHubRelease {
-    pub tag_name: String,
-    pub name: String,
-    pub published_at: String,
-    pub tarball_url: String,
-    pub zipball_url: String,
-    pub body: Option<String>,
-    pub prerelease: bool,
-    pub assets: Vec<GitHubAsset>,
-}
+/// Records the commit of a package installed from a branch or commit
+const REVISION_MARKER: &str = ".noter-revision";
 
 #[derive(Debug)]
 pub struct TemplateDownloadResult {
     pub version: String,
     pub installed_path: PathBuf,
     pub is_cached: bool,
+    /// Nothing was installed because the installed revision is current
+    pub up_to_date: bool,
+    /// Lockfile entry for the installed archive (None if no archive was at hand)
+    pub lock_entry: Option<LockedPackage>,
+}
+
+/// Result of installing a package from a local directory or archive
+#[derive(Debug)]
+pub struct LocalInstallResult {
+    pub name: String,
+    pub version: String,
+    pub installed_path: PathBuf,
+    pub linked: bool,
 }
 
 pub struct Fetcher;
@@ -46,33 +45,14 @@ pub struct Fetcher;
 #[allow(dead_code)]
 impl Fetcher {
     /// Get the latest release information from a specific GitHub repository
-    pub fn get_latest_release(repo: &str) -> Result<GitHubRelease> {
-        let url = format!("{GITHUB_API_BASE}/repos/{repo}/releases/latest");
-
-        let mut response = ureq::get(&url)
-            .header("User-Agent", "dtu-notes-cli")
-            .call()
-            .context("Failed to fetch latest release information")?;
-
-        if response.status() != 200 {
-            return Err(anyhow::anyhow!(
-                "GitHub API request failed with status: {}",
-                response.status()
-            ));
-        }
-
-        let body_str = response
-            .body_mut()
-            .read_to_string()
-            .context("Failed to read response body")?;
-
-        let release: GitHubRelease =
-            serde_json::from_str(&body_str).context("Failed to parse GitHub API response")?;
-
-        Ok(release)
+    pub fn get_latest_release(repo: &str) -> Result<Release> {
+        GitHubProvider::default().latest_release(repo)
     }
 
-    /// Download and install templates from configured repositories with fallback
+    /// Download and install templates from all enabled repositories
+    ///
+    /// The official repository is only used as a fallback when no custom
+    /// repository could be installed.
     pub fn download_and_install_templates(
         config: &Config,
         force_update: bool,
@@ -80,17 +60,18 @@ impl Fetcher {
         let mut results = Vec::new();
         let mut success = false;
 
-        // Try custom repositories first
+        // Install all enabled custom repositories
         for repo_config in &config.templates.custom_repositories {
             if !repo_config.enabled {
                 continue;
             }
 
-            match Self::download_from_repository(config, repo_config, force_update) {
+            // Install every enabled repository; templates are resolved
+            // between packages by `preference_order`
+            match Self::download_from_repository(config, repo_config, force_update, None) {
                 Ok(result) => {
                     results.push(result);
                     success = true;
-                    break; // Use first successful repository
                 }
                 Err(e) => {
                     eprintln!("Failed to download from {}: {}", repo_config.name, e);
@@ -101,16 +82,9 @@ impl Fetcher {
 
         // Fallback to official repository if no custom repos succeeded
         if !success && config.templates.use_official_fallback {
-            let official_repo = TemplateRepository {
-                name: "dtu_template".to_string(),
-                repository: DEFAULT_TEMPLATE_REPO.to_string(),
-                version: None,
-                branch: None,
-                template_path: None,
-                enabled: true,
-            };
+            let official_repo = Self::official_repository();
 
-            match Self::download_from_repository(config, &official_repo, force_update) {
+            match Self::download_from_repository(config, &official_repo, force_update, None) {
                 Ok(result) => {
                     results.push(result);
                     success = true;
@@ -133,21 +107,116 @@ impl Fetcher {
         Ok(results)
     }
 
+    /// The official template repository used as a fallback
+    fn official_repository() -> TemplateRepository {
+        TemplateRepository {
+            name: "dtu_template".to_string(),
+            repository: DEFAULT_TEMPLATE_REPO.to_string(),
+            ..TemplateRepository::default()
+        }
+    }
+
+    /// Repository configuration behind a name reported by `check_template_status`
+    pub fn repository_for(config: &Config, name: &str) -> Option<TemplateRepository> {
+        config
+            .templates
+            .custom_repositories
+            .iter()
+            .find(|repo| repo.name == name)
+            .cloned()
+            .or_else(|| (name == "dtu_template").then(Self::official_repository))
+    }
+
+    /// Directory a configured repository's package versions are installed to
+    pub fn package_dir_for(config: &Config, name: &str) -> Option<PathBuf> {
+        Self::repository_for(config, name)
+            .map(|repo| Self::package_install_dir(&config.paths.typst_packages_dir, &repo))
+    }
+
     /// Download from a specific repository configuration
+    ///
+    /// With a `locked` entry, exactly the locked archive is installed and its
+    /// checksum must match. The entry must belong to `repo_config` and its
+    /// archive must be on the repository's host.
     fn download_from_repository(
         config: &Config,
         repo_config: &TemplateRepository,
         force_update: bool,
+        locked: Option<&LockedPackage>,
     ) -> Result<TemplateDownloadResult> {
-        let release = Self::get_latest_release(&repo_config.repository)?;
+        if let Some(entry) = locked {
+            entry.check_source(repo_config)?;
+        }
+
+        let provider = providers::provider_for(repo_config);
+        let cache = TemplateCache::open()?;
+        let offline = config.templates.is_offline();
+        let git_ref = repo_config.git_ref();
+
+        // Offline, the release is resolved from the cache index instead of the provider
+        let cached_entry = if offline {
+            let tag = locked
+                .map(|entry| entry.tag.as_str())
+                .or(repo_config.rev.as_deref())
+                .or(repo_config.version.as_deref());
+            let entry = cache.find(&repo_config.name, tag).ok_or_else(|| {
+                anyhow!(
+                    "No cached archive of {}{} (offline mode)",
+                    repo_config.name,
+                    tag.map(|tag| format!(" {}", tag)).unwrap_or_default()
+                )
+            })?;
+            Some(entry)
+        } else {
+            None
+        };
+
+        let release = match (&cached_entry, locked, git_ref, &repo_config.version) {
+            (Some(entry), _, _, _) => Release {
+                tag_name: entry.tag.clone(),
+                archive_url: entry.url.clone(),
+                prerelease: false,
+                assets: Vec::new(),
+                mutable: false,
+            },
+            (None, Some(entry), _, _) => entry.release(),
+            (None, None, Some(git_ref), _) => {
+                provider.commit_release(&repo_config.repository, git_ref)?
+            }
+            (None, None, None, Some(tag)) => provider.release(&repo_config.repository, tag)?,
+            (None, None, None, None) => provider.latest_release(&repo_config.repository)?,
+        };
+        let download_url = release.download_url().to_string();
+
+        // Branch and commit installs are versioned by their short commit SHA
+        let (version, cache_key) = match git_ref {
+            Some(git_ref) => {
+                let short = short_sha(&release.tag_name);
+                (format!("{}@{}", git_ref, short), short.to_string())
+            }
+            None => (release.tag_name.clone(), release.tag_name.clone()),
+        };
+        let install_dir = Self::package_install_dir(&config.paths.typst_packages_dir, repo_config);
 
         // Check if we already have this version cached
-        let cache_path = Self::get_cache_path(&repo_config.name, &release.tag_name)?;
+        let cache_path = match &cached_entry {
+            Some(entry) => cache.path_of(entry),
+            None => {
+                let mut cache_path = Self::get_cache_path(&repo_config.name, &cache_key)?;
+                if download_url.to_lowercase().ends_with(".zip") {
+                    cache_path.set_extension("zip");
+                }
+                cache_path
+            }
+        };
         let template_installed_marker = Path::new(&config.paths.templates_dir)
             .join(&repo_config.name)
             .join(".template_version");
 
-        let is_already_installed = if template_installed_marker.exists() {
+        let is_already_installed = if git_ref.is_some() {
+            // Compare the installed commit with the branch head
+            Self::installed_revision(&install_dir).as_deref() == Some(release.tag_name.as_str())
+        } else if template_installed_marker.exists() {
             if let Ok(installed_version) = fs::read_to_string(&template_installed_marker) {
                 installed_version.trim() == release.tag_name
             } else {
@@ -157,18 +226,102 @@ impl Fetcher {
             false
         };
 
-        if is_already_installed && !force_update {
+        // An archive that can change behind the same tag is compared by checksum
+        // after downloading it again (see below)
+        if is_already_installed && !force_update && !release.mutable {
+            let lock_entry = fs::read(&cache_path).ok().map(|archive| {
+                LockedPackage::new(repo_config, &release.tag_name, &download_url, &archive)
+            });
+
             return Ok(TemplateDownloadResult {
-                version: release.tag_name,
+                version,
                 installed_path: PathBuf::from(&config.paths.templates_dir).join(&repo_config.name),
                 is_cached: true,
+                up_to_date: true,
+                lock_entry,
             });
         }
 
-        // Download if not cached or force update
-        if !cache_path.exists() || force_update {
-            Self::download_release(&release, &cache_path)?;
-        }
+        // Download if not cached or force update; a cached archive that doesn't
+        // match the lock is downloaded again. Offline, the cached archive is
+        // always reinstalled.
+        let cached = if (force_update || release.mutable) && !offline {
+            None
+        } else {
+            fs::read(&cache_path)
+                .ok()
+                .filter(|archive| locked.is_none_or(|entry| entry.verify(archive).is_ok()))
+        };
+
+        let archive = match (cached, &cached_entry) {
+            (Some(archive), Some(entry)) => {
+                if sha256_hex(&archive) != entry.sha256 {
+                    return Err(anyhow!(
+                        "Cached archive {} is corrupt; run 'noter template cache verify'",
+                        cache_path.display()
+                    ));
+                }
+                archive
+            }
+            (Some(archive), None) => {
+                // Index archives cached before the index existed
+                if cache
+                    .find(&repo_config.name, Some(&release.tag_name))
+                    .is_none()
+                {
+                    cache.record(
+                        &repo_config.name,
+                        &release.tag_name,
+                        &download_url,
+                        &cache_path,
+                        &archive,
+                    )?;
+                }
+                archive
+            }
+            (None, Some(_)) => {
+                return Err(anyhow!(
+                    "Cached archive {} doesn't match {} (offline mode)",
+                    cache_path.display(),
+                    super::lockfile::LOCK_FILE_NAME
+                ));
+            }
+            (None, None) => {
+                let previous = cache
+                    .find(&repo_config.name, Some(&release.tag_name))
+                    .map(|entry| entry.sha256);
+                let archive =
+                    Self::download_release(provider.as_ref(), &download_url, locked, &cache_path)?;
+                cache.record(
+                    &repo_config.name,
+                    &release.tag_name,
+                    &download_url,
+                    &cache_path,
+                    &archive,
+                )?;
+
+                if release.mutable
+                    && is_already_installed
+                    && !force_update
+                    && previous.is_some_and(|sha256| sha256 == sha256_hex(&archive))
+                {
+                    return Ok(TemplateDownloadResult {
+                        lock_entry: Some(LockedPackage::new(
+                            repo_config,
+                            &release.tag_name,
+                            &download_url,
+                            &archive,
+                        )),
+                        version,
+                        installed_path: PathBuf::from(&config.paths.templates_dir)
+                            .join(&repo_config.name),
+                        is_cached: true,
+                        up_to_date: true,
+                    });
+                }
+                archive
+            }
+        };
 
         // Extract and install template
         Self::extract_and_install(
@@ -177,78 +330,86 @@ impl Fetcher {
             &config.paths.typst_packages_dir,
             &release.tag_name,
             repo_config,
+            config.templates.keep_versions,
+            Path::new(&config.paths.notes_dir),
         )?;
 
+        let revision_marker = install_dir.join(REVISION_MARKER);
+        if git_ref.is_some() {
+            fs::write(&revision_marker, format!("{}\n", release.tag_name))
+                .context("Failed to record installed revision")?;
+        } else if revision_marker.exists() {
+            fs::remove_file(&revision_marker)?;
+        }
+
         Ok(TemplateDownloadResult {
-            version: release.tag_name,
+            lock_entry: Some(LockedPackage::new(
+                repo_config,
+                &release.tag_name,
+                &download_url,
+                &archive,
+            )),
+            version,
             installed_path: PathBuf::from(&config.paths.templates_dir).join(&repo_config.name),
             is_cached: cache_path.exists(),
+            up_to_date: false,
         })
     }
 
     /// Get cache directory path for templates
     fn get_cache_path(repo_name: &str, version: &str) -> Result<PathBuf> {
-        let cache_dir = dirs::cache_dir()
-            .or_else(|| dirs::home_dir().map(|h| h.join(".cache")))
-            .context("Could not determine cache directory")?
-            .join("dtu-notes")
-            .join("templates");
+        let cache_dir = TemplateCache::default_dir()?;
 
         fs::create_dir_all(&cache_dir)?;
         Ok(cache_dir.join(format!("{}-{}.tar.gz", repo_name, version)))
     }
 
-    /// Download the release asset (not tarball)
-    fn download_release(release: &GitHubRelease, cache_path: &Path) -> Result<()> {
-        // Look for a release asset that looks like a template (zip or tar.gz)
-        let template_asset = release
-            .assets
-            .iter()
-            .find(|asset| {
-                let name = asset.name.to_lowercase();
-                name.contains("dtu-template") || name.contains("template")
-            })
-            .or_else(|| {
-                // Fallback: look for any zip or tar.gz file
-                release.assets.iter().find(|asset| {
-                    let name = asset.name.to_lowercase();
-                    name.ends_with(".zip") || name.ends_with(".tar.gz")
-                })
-            });
-
-        let download_url = if let Some(asset) = template_asset {
-            &asset.browser_download_url
-        } else {
-            // Fallback to tarball if no assets found
-            &release.tarball_url
-        };
-
-        let response = ureq::get(download_url)
-            .header("User-Agent", "dtu-notes-cli")
-            .call()
+    /// Download a release archive into the cache
+    ///
+    /// A locked archive is verified before it is cached, so a tampered
+    /// download never replaces a good cached copy.
+    fn download_release(
+        provider: &dyn ReleaseProvider,
+        download_url: &str,
+        locked: Option<&LockedPackage>,
+        cache_path: &Path,
+    ) -> Result<Vec<u8>> {
+        let bytes = provider
+            .download(download_url)
             .context("Failed to download template release")?;
 
-        if response.status() != 200 {
-            return Err(anyhow::anyhow!(
-                "Failed to download template: HTTP {}",
-                response.status()
-            ));
+        if let Some(entry) = locked {
+            entry.verify(&bytes)?;
         }
 
-        // Read response body using ureq's read_to_vec method
-        let bytes = response
-            .into_body()
-            .read_to_vec()
-            .context("Failed to read response body")?;
-
         // Ensure parent directory exists
         if let Some(parent) = cache_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        fs::write(cache_path, bytes).context("Failed to write downloaded template to cache")?;
+        fs::write(cache_path, &bytes).context("Failed to write downloaded template to cache")?;
 
-        Ok(())
+        Ok(bytes)
+    }
+
+    /// Directory under `typst_packages_dir` a repository's package versions are installed to
+    fn package_install_dir(typst_packages_dir: &str, repo_config: &TemplateRepository) -> PathBuf {
+        let is_official_template =
+            repo_config.repository == DEFAULT_TEMPLATE_REPO || repo_config.name == "dtu_template";
+
+        if is_official_template {
+            Path::new(typst_packages_dir).join("dtu-template")
+        } else {
+            Path::new(typst_packages_dir).join(&repo_config.name)
+        }
+    }
+
+    /// Commit SHA of a package installed from a branch or commit
+    fn installed_revision(install_dir: &Path) -> Option<String> {
+        fs::read_to_string(install_dir.join(REVISION_MARKER))
+            .ok()
+            .map(|sha| sha.trim().to_string())
+            .filter(|sha| !sha.is_empty())
     }
 
     /// Extract and install template files
@@ -258,6 +419,8 @@ impl Fetcher {
         typst_packages_dir: &str,
         _version: &str,
         repo_config: &TemplateRepository,
+        keep_versions: usize,
+        notes_dir: &Path,
     ) -> Result<()> {
         let target_dir = Path::new(typst_packages_dir);
         fs::create_dir_all(target_dir)?;
@@ -277,16 +440,22 @@ impl Fetcher {
         }
 
         // Install from temp directory to final location
-        let is_official_template = repo_config.repository == "HollowNumber/dtu-note-template"
-            || repo_config.name == "dtu_template";
+        let final_dir = Self::package_install_dir(typst_packages_dir, repo_config);
 
-        let final_dir = if is_official_template {
-            target_dir.join("dtu-template")
-        } else {
-            target_dir.join(&repo_config.name)
-        };
+        let installed_versions =
+            Self::install_template_versions(&temp_dir, &final_dir, &repo_config.name)?;
 
-        Self::install_template_versions(&temp_dir, &final_dir, &repo_config.name)?;
+        // Track installed versions so older ones can be rolled back to
+        let mut history = VersionHistory::load(&final_dir);
+        history.record_install(&installed_versions);
+        history.source = Some(InstallSource::of(repo_config));
+        let package_name = final_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let in_use = ImportRewriter::imported_versions(&package_name, notes_dir)?;
+        history.prune(&final_dir, keep_versions, &in_use)?;
+        history.save(&final_dir)?;
 
         // Clean up temp directory
         if temp_dir.exists() {
@@ -344,9 +513,11 @@ impl Fetcher {
         temp_dir: &Path,
         target_dir: &Path,
         template_name: &str,
-    ) -> Result<()> {
+    ) -> Result<Vec<String>> {
         use crate::core::files::FileOperations;
 
+        let mut installed_versions = Vec::new();
+
         // Ensure target directory exists
         fs::create_dir_all(target_dir)?;
 
@@ -374,6 +545,7 @@ impl Fetcher {
 
                 if source_path.is_dir() {
                     let version_name = entry.file_name();
+                    Self::check_path_component(&version_name.to_string_lossy())?;
                     let dest_path = target_dir.join(&version_name);
 
                     // Remove existing version if it exists (for force updates)
@@ -386,6 +558,7 @@ impl Fetcher {
                     if fs::rename(&source_path, &dest_path).is_err() {
                         FileOperations::copy_dir_recursive(&source_path, &dest_path)?;
                     }
+                    installed_versions.push(version_name.to_string_lossy().into_owned());
                 }
             }
         } else {
@@ -406,13 +579,16 @@ impl Fetcher {
                     if fs::rename(&source_path, &dest_path).is_err() {
                         FileOperations::copy_dir_recursive(&source_path, &dest_path)?;
                     }
+                    if Self::extract_version_from_directory(&dest_path).is_some() {
+                        installed_versions.push(entry.file_name().to_string_lossy().into_owned());
+                    }
                 } else if source_path.is_file() {
                     fs::copy(&source_path, &dest_path)?;
                 }
             }
         }
 
-        Ok(())
+        Ok(installed_versions)
     }
 
     /// Copy template structure preserving directory layout
@@ -448,7 +624,20 @@ impl Fetcher {
             .iter()
             .filter(|r| r.enabled)
         {
-            let version = Self::get_custom_template_version(config, &repo_config.name)?;
+            let install_dir =
+                Self::package_install_dir(&config.paths.typst_packages_dir, repo_config);
+
+            let version = match Self::installed_revision(&install_dir) {
+                Some(sha) => Some(format!(
+                    "{}@{}",
+                    repo_config.git_ref().unwrap_or("commit"),
+                    short_sha(&sha)
+                )),
+                None => match Self::find_template_version_in_directory(&install_dir) {
+                    Some(version) => Some(version),
+                    None => Self::get_custom_template_version(config, &repo_config.name)?,
+                },
+            };
             statuses.push((repo_config.name.clone(), version));
         }
 
@@ -497,6 +686,11 @@ impl Fetcher {
 
     /// Find the template version by scanning version directories
     fn find_template_version_in_directory(template_dir: &Path) -> Option<String> {
+        // The version in use, which after a rollback isn't the newest one
+        if let Some(current) = VersionHistory::load(template_dir).current() {
+            return Some(current.to_string());
+        }
+
         let entries = fs::read_dir(template_dir).ok()?;
 
         let versions: Vec<String> = entries
@@ -532,10 +726,10 @@ impl Fetcher {
 
         // Try to read version from typst.toml
         let typst_toml = path.join("typst.toml");
-        if let Ok(toml_content) = fs::read_to_string(&typst_toml) {
-            if let Some(version) = Self::parse_version_from_toml(&toml_content) {
-                return Some(version);
-            }
+        if let Ok(toml_content) = fs::read_to_string(&typst_toml)
+            && let Some(version) = Self::parse_version_from_toml(&toml_content)
+        {
+            return Some(version);
         }
 
         // Fallback to directory name
@@ -563,6 +757,7 @@ impl Fetcher {
 
         let config = Config {
             author: "Unknown".to_string(),
+            student_id: None,
             preferred_editor: None,
             template_version: "0.1.0".to_string(),
             semester_format: crate::config::SemesterFormat::YearSeason,
@@ -577,7 +772,10 @@ impl Fetcher {
             typst: crate::config::TypstConfig::default(),
             search: crate::config::SearchConfig::default(),
             courses: std::collections::HashMap::new(),
+            course_overrides: std::collections::HashMap::new(),
+            groups: std::collections::HashMap::new(),
             obsidian_integration: ObsidianIntegrationConfig::default(),
+            profiles: std::collections::HashMap::new(),
             metadata: Metadata::default(),
         };
 
@@ -589,9 +787,343 @@ impl Fetcher {
             .ok_or_else(|| anyhow::anyhow!("No templates were downloaded"))
     }
 
-    /// Update templates to latest versions
+    /// Install a template package from a local directory, `.zip` or `.tar.gz`
+    ///
+    /// The source is either a single package (a directory with `typst.toml`,
+    /// possibly wrapped in one top-level directory) or a repository-style
+    /// layout with `<name>/<version>/` folders. With `link`, a package
+    /// directory is symlinked instead of copied so edits show up immediately.
+    pub fn install_from_path(
+        config: &Config,
+        source: &Path,
+        link: bool,
+    ) -> Result<LocalInstallResult> {
+        if !source.exists() {
+            return Err(anyhow::anyhow!(
+                "Template source not found: {}",
+                source.display()
+            ));
+        }
+
+        let source = source
+            .canonicalize()
+            .context("Failed to resolve template source path")?;
+        let packages_dir = Path::new(&config.paths.typst_packages_dir);
+        fs::create_dir_all(packages_dir)?;
+
+        let result = if link {
+            Self::link_package(packages_dir, &source)
+        } else {
+            Self::stage_in_temp_dir(packages_dir, &source)
+        }?;
+
+        // A local package isn't from a repository, even if it replaces one
+        VersionHistory::forget_source(&packages_dir.join(&result.name))?;
+        Ok(result)
+    }
+
+    fn stage_in_temp_dir(packages_dir: &Path, source: &Path) -> Result<LocalInstallResult> {
+        // Stage everything in a temp directory so the source is never modified
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let temp_dir =
+            std::env::temp_dir().join(format!("noter-install-{}-{}", std::process::id(), nanos));
+        let result = Self::stage_and_install(packages_dir, source, &temp_dir);
+
+        if temp_dir.exists() {
+            let _ = fs::remove_dir_all(&temp_dir);
+        }
+
+        result
+    }
+
+    fn stage_and_install(
+        packages_dir: &Path,
+        source: &Path,
+        temp_dir: &Path,
+    ) -> Result<LocalInstallResult> {
+        let file_name = source
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        if source.is_dir() {
+            if temp_dir.exists() {
+                fs::remove_dir_all(temp_dir)?;
+            }
+            let wrapper = source.file_name().unwrap_or_else(|| "package".as_ref());
+            crate::core::files::FileOperations::copy_dir_recursive(
+                source,
+                &temp_dir.join(wrapper),
+            )?;
+        } else if file_name.ends_with(".zip") {
+            Self::extract_zip_to_temp(source, temp_dir)?;
+        } else if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") {
+            Self::extract_targz_to_temp(source, temp_dir)?;
+        } else {
+            return Err(anyhow::anyhow!(
+                "Unsupported template source '{}'. Use a directory, .zip or .tar.gz",
+                source.display()
+            ));
+        }
+
+        // A single package: copy it to <packages>/<name>/<version>
+        if let Some(package_root) = Self::find_package_root(temp_dir) {
+            let (name, version) = Self::read_package_manifest(&package_root)?;
+            let installed_path = packages_dir.join(&name).join(&version);
+
+            if installed_path.exists() || installed_path.is_symlink() {
+                Self::remove_installed_version(&installed_path)?;
+            }
+            crate::core::files::FileOperations::copy_dir_recursive(&package_root, &installed_path)?;
+
+            return Ok(LocalInstallResult {
+                name,
+                version,
+                installed_path,
+                linked: false,
+            });
+        }
+
+        // Repository layout: <wrapper>/<name>/<version>/
+        let name = Self::detect_repository_package_name(temp_dir).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No typst.toml found in {}. Expected a package directory or <name>/<version>/ folders",
+                source.display()
+            )
+        })?;
+
+        Self::check_path_component(&name)?;
+        let target_dir = packages_dir.join(&name);
+        Self::install_template_versions(temp_dir, &target_dir, &name)?;
+
+        let version = Self::find_template_version_in_directory(&target_dir)
+            .ok_or_else(|| anyhow::anyhow!("No package versions were installed for '{}'", name))?;
+
+        Ok(LocalInstallResult {
+            installed_path: target_dir.join(&version),
+            name,
+            version,
+            linked: false,
+        })
+    }
+
+    /// Symlink a package directory into the packages directory
+    fn link_package(packages_dir: &Path, source: &Path) -> Result<LocalInstallResult> {
+        if !source.is_dir() || !source.join("typst.toml").exists() {
+            return Err(anyhow::anyhow!(
+                "--link needs a package directory containing typst.toml: {}",
+                source.display()
+            ));
+        }
+
+        let (name, version) = Self::read_package_manifest(source)?;
+        let installed_path = packages_dir.join(&name).join(&version);
+
+        if installed_path.exists() || installed_path.is_symlink() {
+            Self::remove_installed_version(&installed_path)?;
+        }
+        fs::create_dir_all(packages_dir.join(&name))?;
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(source, &installed_path)
+            .context("Failed to create package symlink")?;
+        #[cfg(windows)]
+        std::os::windows::fs::symlink_dir(source, &installed_path)
+            .context("Failed to create package symlink (symlinks may need developer mode)")?;
+
+        Ok(LocalInstallResult {
+            name,
+            version,
+            installed_path,
+            linked: true,
+        })
+    }
+
+    /// Remove an installed version, unlinking symlinks instead of following them
+    fn remove_installed_version(path: &Path) -> Result<()> {
+        if path
+            .components()
+            .any(|component| matches!(component, std::path::Component::ParentDir))
+        {
+            return Err(anyhow!("Refusing to remove {}", path.display()));
+        }
+
+        if path.is_symlink() {
+            #[cfg(windows)]
+            fs::remove_dir(path)?;
+            #[cfg(not(windows))]
+            fs::remove_file(path)?;
+        } else {
+            fs::remove_dir_all(path)?;
+        }
+        Ok(())
+    }
+
+    /// Find the directory holding `typst.toml`: the directory itself or its only subdirectory
+    fn find_package_root(dir: &Path) -> Option<PathBuf> {
+        if dir.join("typst.toml").exists() {
+            return Some(dir.to_path_buf());
+        }
+
+        let subdirs: Vec<PathBuf> = fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+
+        match subdirs.as_slice() {
+            [only] if only.join("typst.toml").exists() => Some(only.clone()),
+            _ => None,
+        }
+    }
+
+    /// Find `<name>` in a `<wrapper>/<name>/<version>/typst.toml` layout
+    fn detect_repository_package_name(temp_dir: &Path) -> Option<String> {
+        let wrapper = fs::read_dir(temp_dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .find(|entry| entry.path().is_dir())?
+            .path();
+
+        fs::read_dir(wrapper)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .find(|entry| {
+                fs::read_dir(entry.path()).is_ok_and(|mut versions| {
+                    versions.any(|v| v.is_ok_and(|v| v.path().join("typst.toml").exists()))
+                })
+            })
+            .and_then(|entry| entry.file_name().to_str().map(str::to_string))
+    }
+
+    /// Read the package name and version from `typst.toml`
+    ///
+    /// Both become directories under the packages directory, so the name
+    /// must be a plain identifier and the version a semantic version.
+    fn read_package_manifest(package_dir: &Path) -> Result<(String, String)> {
+        let content = fs::read_to_string(package_dir.join("typst.toml"))
+            .context("Failed to read typst.toml")?;
+        let manifest: toml::Table =
+            toml::from_str(&content).context("Failed to parse typst.toml")?;
+
+        let field = |key: &str| {
+            manifest
+                .get("package")
+                .and_then(|package| package.get(key))
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+                .ok_or_else(|| anyhow::anyhow!("typst.toml is missing package.{}", key))
+        };
+
+        let name = field("name")?;
+        let is_identifier = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !is_identifier {
+            return Err(anyhow!(
+                "Invalid package name '{}' in typst.toml: use letters, digits, '-' and '_'",
+                name
+            ));
+        }
+
+        let version = field("version")?;
+        semver::Version::parse(&version).with_context(|| {
+            format!(
+                "Invalid package version '{}' in typst.toml: expected e.g. 1.0.0",
+                version
+            )
+        })?;
+
+        Self::check_path_component(&name)?;
+        Self::check_path_component(&version)?;
+        Ok((name, version))
+    }
+
+    /// Refuse names that would leave the directory they are joined to
+    fn check_path_component(component: &str) -> Result<()> {
+        if component.is_empty()
+            || component.contains(['/', '\\'])
+            || component.contains("..")
+            || Path::new(component).is_absolute()
+        {
+            return Err(anyhow!(
+                "'{}' can't be used as a package directory name",
+                component
+            ));
+        }
+        Ok(())
+    }
+
+    /// Update templates to latest versions and record them in the lockfile
+    ///
+    /// Cached archives of an unchanged release are reused, and packages
+    /// tracking a branch are only reinstalled when the branch head moved.
     pub fn update_templates(config: &Config) -> Result<Vec<TemplateDownloadResult>> {
-        Self::download_and_install_templates(config, true)
+        Self::install_and_lock(config, false)
+    }
+
+    /// Download and reinstall all templates, ignoring the cache
+    pub fn reinstall_templates(config: &Config) -> Result<Vec<TemplateDownloadResult>> {
+        Self::install_and_lock(config, true)
+    }
+
+    fn install_and_lock(
+        config: &Config,
+        force_update: bool,
+    ) -> Result<Vec<TemplateDownloadResult>> {
+        let results = Self::download_and_install_templates(config, force_update)?;
+
+        let lock_path = LockFile::path_for(config);
+        let mut lock = LockFile::load(&lock_path)?.unwrap_or_default();
+        for entry in results
+            .iter()
+            .filter_map(|result| result.lock_entry.clone())
+        {
+            lock.upsert(entry);
+        }
+        lock.save(&lock_path)?;
+
+        Ok(results)
+    }
+
+    /// Install exactly the packages pinned in the lockfile
+    ///
+    /// Locked repositories that aren't configured locally are installed from
+    /// the repository recorded in the lock.
+    pub fn sync_templates(config: &Config) -> Result<Vec<TemplateDownloadResult>> {
+        let lock_path = LockFile::path_for(config);
+        let lock = LockFile::load(&lock_path)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "No lockfile found at {}. Run 'noter template update' to create one",
+                lock_path.display()
+            )
+        })?;
+
+        lock.packages
+            .iter()
+            .map(|entry| {
+                let repo_config = config
+                    .templates
+                    .custom_repositories
+                    .iter()
+                    .find(|repo| repo.name == entry.name)
+                    .cloned()
+                    .unwrap_or_else(|| entry.repository_config());
+
+                Self::download_from_repository(config, &repo_config, false, Some(entry))
+                    .with_context(|| format!("Failed to install locked package {}", entry.name))
+            })
+            .collect()
     }
 }
 
@@ -607,7 +1139,7 @@ mod tests {
 
         let release = result.unwrap();
         assert!(!release.tag_name.is_empty());
-        assert!(!release.tarball_url.is_empty());
+        assert!(!release.archive_url.is_empty());
     }
 
     #[test]
@@ -625,6 +1157,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let config = Config {
             author: "Test".to_string(),
+            student_id: None,
             preferred_editor: None,
             template_version: "0.1.0".to_string(),
             semester_format: crate::config::SemesterFormat::YearSeason,
@@ -639,11 +1172,110 @@ mod tests {
             typst: crate::config::TypstConfig::default(),
             search: crate::config::SearchConfig::default(),
             courses: std::collections::HashMap::new(),
+            course_overrides: std::collections::HashMap::new(),
+            groups: std::collections::HashMap::new(),
             obsidian_integration: ObsidianIntegrationConfig::default(),
+            profiles: std::collections::HashMap::new(),
             metadata: Metadata::default(),
         };
 
         let status = Fetcher::check_template_status(&config).unwrap();
         assert_eq!(status, vec![("dtu_template".to_string(), None)]);
     }
+
+    fn write_package(dir: &Path, name: &str, version: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(
+            dir.join("typst.toml"),
+            format!(
+                "[package]\nname = \"{name}\"\nversion = \"{version}\"\nentrypoint = \"lib.typ\"\n"
+            ),
+        )
+        .unwrap();
+        fs::write(dir.join("lib.typ"), "#let note(body) = body\n").unwrap();
+    }
+
+    fn config_with_packages_dir(dir: &Path) -> Config {
+        let mut config = Config::default();
+        config.paths.typst_packages_dir = dir.to_string_lossy().to_string();
+        config
+    }
+
+    #[test]
+    fn test_install_from_directory() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("my-notes");
+        write_package(&source, "my-notes", "0.2.0");
+        let config = config_with_packages_dir(&temp.path().join("packages"));
+
+        let result = Fetcher::install_from_path(&config, &source, false).unwrap();
+
+        assert_eq!(result.name, "my-notes");
+        assert_eq!(result.version, "0.2.0");
+        assert!(result.installed_path.join("lib.typ").exists());
+        // The source is copied, never moved
+        assert!(source.join("lib.typ").exists());
+    }
+
+    #[test]
+    fn test_install_from_repository_archive() {
+        use flate2::Compression;
+        use flate2::write::GzEncoder;
+
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        write_package(&repo.join("my-notes").join("1.0.0"), "my-notes", "1.0.0");
+
+        let archive_path = temp.path().join("my-notes.tar.gz");
+        let encoder = GzEncoder::new(
+            fs::File::create(&archive_path).unwrap(),
+            Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        builder.append_dir_all("my-notes-main", &repo).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let config = config_with_packages_dir(&temp.path().join("packages"));
+        let result = Fetcher::install_from_path(&config, &archive_path, false).unwrap();
+
+        assert_eq!(result.name, "my-notes");
+        assert_eq!(result.version, "1.0.0");
+        assert!(result.installed_path.join("typst.toml").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_with_link() {
+        let temp = TempDir::new().unwrap();
+        let source = temp.path().join("dev");
+        write_package(&source, "dev-notes", "0.1.0");
+        let config = config_with_packages_dir(&temp.path().join("packages"));
+
+        let result = Fetcher::install_from_path(&config, &source, true).unwrap();
+        assert!(result.linked);
+        assert!(result.installed_path.is_symlink());
+
+        fs::write(source.join("extra.typ"), "").unwrap();
+        assert!(result.installed_path.join("extra.typ").exists());
+    }
+
+    #[test]
+    fn test_install_rejects_unsafe_manifest() {
+        let temp = TempDir::new().unwrap();
+        let config = config_with_packages_dir(&temp.path().join("packages"));
+
+        for (name, version) in [
+            ("../escape", "1.0.0"),
+            ("notes/evil", "1.0.0"),
+            ("my-notes", "../../1.0.0"),
+            ("my-notes", "latest"),
+        ] {
+            let source = temp.path().join("source");
+            let _ = fs::remove_dir_all(&source);
+            write_package(&source, name, version);
+
+            assert!(Fetcher::install_from_path(&config, &source, false).is_err());
+        }
+        assert!(!temp.path().join("escape").exists());
+    }
 }
@@ -0,0 +1,306 @@
+//! Template lockfile (`noter.lock`)
+//!
+//! Records the exact release, archive URL and SHA-256 checksum of every
+//! installed template package so that a group sharing a notes repository
+//! renders with identical templates. `noter template update` rewrites the
+//! lock, `noter template sync` installs exactly what it pins.
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::providers::{self, Release};
+use crate::config::{Config, InstallSource, TemplateProvider, TemplateRepository};
+
+pub const LOCK_FILE_NAME: &str = "noter.lock";
+const LOCK_FILE_VERSION: u32 = 1;
+const LOCK_FILE_HEADER: &str =
+    "# Generated by noter. Commit this file to share exact template versions.\n\n";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LockFile {
+    pub version: u32,
+
+    #[serde(rename = "package", default)]
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A template repository pinned to one release archive
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LockedPackage {
+    /// Repository name from the configuration
+    pub name: String,
+    pub repository: String,
+    #[serde(default)]
+    pub provider: TemplateProvider,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Resolved release tag
+    pub tag: String,
+    /// Archive that was downloaded
+    pub url: String,
+    pub sha256: String,
+}
+
+impl Default for LockFile {
+    fn default() -> Self {
+        Self {
+            version: LOCK_FILE_VERSION,
+            packages: Vec::new(),
+        }
+    }
+}
+
+impl LockFile {
+    /// Location of the lockfile: next to the notes directory
+    pub fn path_for(config: &Config) -> PathBuf {
+        Path::new(&config.paths.notes_dir)
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."))
+            .join(LOCK_FILE_NAME)
+    }
+
+    /// Load a lockfile, returning None if it doesn't exist
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let lock: LockFile = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        if lock.version > LOCK_FILE_VERSION {
+            return Err(anyhow!(
+                "{} was written by a newer version of noter (lockfile version {})",
+                path.display(),
+                lock.version
+            ));
+        }
+
+        Ok(Some(lock))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self).context("Failed to serialize lockfile")?;
+        fs::write(path, format!("{}{}", LOCK_FILE_HEADER, content))
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn find(&self, name: &str) -> Option<&LockedPackage> {
+        self.packages.iter().find(|package| package.name == name)
+    }
+
+    /// Add or replace the entry for a repository
+    pub fn upsert(&mut self, package: LockedPackage) {
+        match self.packages.iter_mut().find(|p| p.name == package.name) {
+            Some(existing) => *existing = package,
+            None => self.packages.push(package),
+        }
+        self.packages.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
+
+impl LockedPackage {
+    /// Pin a downloaded archive of a repository
+    pub fn new(repo: &TemplateRepository, tag: &str, url: &str, archive: &[u8]) -> Self {
+        Self {
+            name: repo.name.clone(),
+            repository: repo.repository.clone(),
+            provider: repo.provider,
+            base_url: repo.base_url.clone(),
+            tag: tag.to_string(),
+            url: url.to_string(),
+            sha256: sha256_hex(archive),
+        }
+    }
+
+    /// The pinned release, downloading exactly the locked archive
+    pub fn release(&self) -> Release {
+        Release {
+            tag_name: self.tag.clone(),
+            archive_url: self.url.clone(),
+            prerelease: false,
+            assets: Vec::new(),
+            // Pinned by its checksum
+            mutable: false,
+        }
+    }
+
+    /// Repository configuration for installing a package that isn't configured locally
+    pub fn repository_config(&self) -> TemplateRepository {
+        TemplateRepository {
+            name: self.name.clone(),
+            repository: self.repository.clone(),
+            provider: self.provider,
+            base_url: self.base_url.clone(),
+            version: Some(self.tag.clone()),
+            ..TemplateRepository::default()
+        }
+    }
+
+    /// Fail unless the entry pins an archive of `repo`, downloaded from its host
+    ///
+    /// The lockfile is shared, so an entry must not be able to point a
+    /// configured (and possibly trusted) repository at another archive.
+    pub fn check_source(&self, repo: &TemplateRepository) -> Result<()> {
+        let source = InstallSource {
+            repository: self.repository.clone(),
+            provider: self.provider,
+            base_url: self.base_url.clone(),
+        };
+        if !source.matches(repo) {
+            return Err(anyhow!(
+                "{} pins '{}' to {} ({}), but it is configured as {} ({})",
+                LOCK_FILE_NAME,
+                self.name,
+                self.repository,
+                self.provider,
+                repo.repository,
+                repo.provider
+            ));
+        }
+
+        let hosts = providers::download_hosts(repo);
+        if !providers::url_host(&self.url).is_some_and(|host| hosts.contains(&host)) {
+            return Err(anyhow!(
+                "{} pins '{}' to {}, which isn't on {}",
+                LOCK_FILE_NAME,
+                self.name,
+                self.url,
+                hosts.join(" or ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fail unless the archive matches the locked checksum
+    pub fn verify(&self, archive: &[u8]) -> Result<()> {
+        let actual = sha256_hex(archive);
+        if !actual.eq_ignore_ascii_case(&self.sha256) {
+            return Err(anyhow!(
+                "Checksum mismatch for {} {} ({}): expected {}, got {}",
+                self.name,
+                self.tag,
+                self.url,
+                self.sha256,
+                actual
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Lowercase hex SHA-256 of some bytes
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn repository() -> TemplateRepository {
+        TemplateRepository {
+            name: "notes".to_string(),
+            repository: "group/notes".to_string(),
+            provider: TemplateProvider::GitLab,
+            ..TemplateRepository::default()
+        }
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_lockfile_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(LOCK_FILE_NAME);
+
+        let mut lock = LockFile::default();
+        lock.upsert(LockedPackage::new(
+            &repository(),
+            "v1.0.0",
+            "https://example.com/v1.tar.gz",
+            b"one",
+        ));
+        lock.upsert(LockedPackage::new(
+            &repository(),
+            "v1.1.0",
+            "https://example.com/v1.1.tar.gz",
+            b"two",
+        ));
+        lock.save(&path).unwrap();
+
+        let loaded = LockFile::load(&path).unwrap().unwrap();
+        assert_eq!(loaded, lock);
+        assert_eq!(loaded.packages.len(), 1);
+        assert_eq!(loaded.find("notes").unwrap().tag, "v1.1.0");
+        assert_eq!(
+            loaded.find("notes").unwrap().provider,
+            TemplateProvider::GitLab
+        );
+    }
+
+    #[test]
+    fn test_check_source_rejects_tampered_entry() {
+        let repo = TemplateRepository {
+            base_url: Some("https://gitlab.example.com".to_string()),
+            ..repository()
+        };
+        let locked = LockedPackage::new(
+            &repo,
+            "v1.0.0",
+            "https://gitlab.example.com/group/notes/-/archive/v1.0.0/notes.tar.gz",
+            b"one",
+        );
+        assert!(locked.check_source(&repo).is_ok());
+
+        // Another repository under the configured name
+        let other = LockedPackage {
+            repository: "attacker/notes".to_string(),
+            ..locked.clone()
+        };
+        assert!(other.check_source(&repo).is_err());
+
+        // The same repository on another host
+        let other = LockedPackage {
+            base_url: Some("https://gitlab.evil.com".to_string()),
+            ..locked.clone()
+        };
+        assert!(other.check_source(&repo).is_err());
+
+        // An archive on another host
+        let other = LockedPackage {
+            url: "https://evil.com/notes.tar.gz".to_string(),
+            ..locked.clone()
+        };
+        let error = other.check_source(&repo).unwrap_err();
+        assert!(error.to_string().contains("isn't on gitlab.example.com"));
+
+        // Entries for unconfigured repositories are checked against themselves
+        assert!(other.check_source(&other.repository_config()).is_err());
+    }
+
+    #[test]
+    fn test_verify_rejects_modified_archive() {
+        let locked = LockedPackage::new(&repository(), "v1.0.0", "https://example.com", b"one");
+
+        assert!(locked.verify(b"one").is_ok());
+        let error = locked.verify(b"tampered").unwrap_err();
+        assert!(error.to_string().contains("Checksum mismatch"));
+    }
+}
@@ -4,10 +4,17 @@
 //! dynamic content, variants, and configuration-driven behaviour.
 
 pub mod builder;
+pub mod cache;
 pub mod config;
 mod constants;
 pub mod context;
 pub mod discovery;
 pub mod engine;
 pub mod fetcher;
+pub mod lockfile;
+pub mod processing;
+pub mod providers;
+pub mod scaffold;
+pub mod updates;
 pub mod validation;
+pub mod versions;
@@ -0,0 +1,374 @@
+//! Document post-processing and processing hooks
+//!
+//! Applies a template package's `ProcessingConfig` to a rendered document:
+//! pre/post processing hooks, output minification and line-ending
+//! normalization. Hooks are either built-in transforms (`builtin:<name>`) or
+//! external commands. External commands only run when the user has opted in
+//! for the repository the package came from, and are executed without a shell,
+//! with a cleared environment, in an empty temporary working directory and
+//! with a timeout. That limits what a hook inherits, but it isn't a sandbox:
+//! the command runs with the user's permissions.
+
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use super::config::{EngineConfig, LineEndingStyle, ProcessingConfig};
+
+/// Prefix marking a hook as a built-in transform instead of an external command
+pub const BUILTIN_HOOK_PREFIX: &str = "builtin:";
+
+/// Processing stage a hook runs in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HookStage {
+    PreProcess,
+    PostProcess,
+    OnError,
+}
+
+impl HookStage {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HookStage::PreProcess => "pre_process",
+            HookStage::PostProcess => "post_process",
+            HookStage::OnError => "on_error",
+        }
+    }
+}
+
+/// Applies processing configuration and hooks to rendered documents
+pub struct DocumentProcessor<'a> {
+    processing: &'a ProcessingConfig,
+    timeout: Duration,
+    allow_external_hooks: bool,
+}
+
+impl<'a> DocumentProcessor<'a> {
+    /// Create a processor for the given engine configuration
+    ///
+    /// `allow_external_hooks` must only be set when the user has trusted the
+    /// repository the template package was installed from.
+    pub fn new(engine_config: &'a EngineConfig, allow_external_hooks: bool) -> Self {
+        Self {
+            processing: &engine_config.processing,
+            timeout: Duration::from_secs(engine_config.rendering.timeout_seconds.max(1)),
+            allow_external_hooks,
+        }
+    }
+
+    /// Run the full processing pipeline on a rendered document
+    ///
+    /// Order: `pre_process` hooks, minification, `post_process` hooks and
+    /// finally line-ending normalization. If a hook fails, the `on_error`
+    /// hooks receive the error message and the error is returned.
+    pub fn process(&self, document: &str) -> Result<String> {
+        match self.run_pipeline(document) {
+            Ok(processed) => Ok(processed),
+            Err(e) => {
+                self.run_error_hooks(&e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// Run the `on_error` hooks with the given error message on stdin
+    ///
+    /// Failures in error hooks are reported but never replace the original error.
+    pub fn run_error_hooks(&self, message: &str) {
+        for hook in &self.processing.hooks.on_error {
+            if let Err(e) = self.run_hook(hook, message, HookStage::OnError) {
+                eprintln!("Warning: on_error hook '{}' failed: {}", hook, e);
+            }
+        }
+    }
+
+    /// External hooks that will be skipped because the repository isn't trusted
+    pub fn skipped_hooks(&self) -> Vec<&str> {
+        if self.allow_external_hooks {
+            return Vec::new();
+        }
+
+        let hooks = &self.processing.hooks;
+        hooks
+            .pre_process
+            .iter()
+            .chain(&hooks.post_process)
+            .chain(&hooks.on_error)
+            .filter(|hook| !hook.starts_with(BUILTIN_HOOK_PREFIX))
+            .map(String::as_str)
+            .collect()
+    }
+
+    fn run_pipeline(&self, document: &str) -> Result<String> {
+        if !self.processing.encoding.eq_ignore_ascii_case("utf-8")
+            && !self.processing.encoding.eq_ignore_ascii_case("utf8")
+        {
+            return Err(anyhow!(
+                "Unsupported template encoding '{}' (only utf-8 is supported)",
+                self.processing.encoding
+            ));
+        }
+
+        let mut output = document.to_string();
+
+        for hook in &self.processing.hooks.pre_process {
+            output = self.run_hook(hook, &output, HookStage::PreProcess)?;
+        }
+
+        if self.processing.minify_output {
+            output = Self::minify(&output);
+        }
+
+        for hook in &self.processing.hooks.post_process {
+            output = self.run_hook(hook, &output, HookStage::PostProcess)?;
+        }
+
+        Ok(Self::normalize_line_endings(
+            &output,
+            &self.processing.line_endings,
+        ))
+    }
+
+    fn run_hook(&self, hook: &str, input: &str, stage: HookStage) -> Result<String> {
+        if let Some(name) = hook.strip_prefix(BUILTIN_HOOK_PREFIX) {
+            return Self::run_builtin_hook(name.trim(), input);
+        }
+
+        if !self.allow_external_hooks {
+            // Untrusted packages never get to execute commands
+            return Ok(input.to_string());
+        }
+
+        self.run_external_hook(hook, input, stage)
+            .with_context(|| format!("{} hook '{}' failed", stage.as_str(), hook))
+    }
+
+    fn run_builtin_hook(name: &str, input: &str) -> Result<String> {
+        let output = match name {
+            "trim-trailing-whitespace" => input
+                .lines()
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            "collapse-blank-lines" => Self::minify(input),
+            "ensure-final-newline" => {
+                if input.ends_with('\n') {
+                    input.to_string()
+                } else {
+                    format!("{}\n", input)
+                }
+            }
+            other => return Err(anyhow!("Unknown built-in hook '{}'", other)),
+        };
+
+        Ok(output)
+    }
+
+    fn run_external_hook(&self, hook: &str, input: &str, stage: HookStage) -> Result<String> {
+        let mut parts = hook.split_whitespace();
+        let program = parts.next().ok_or_else(|| anyhow!("Empty hook command"))?;
+
+        let work_dir = Self::create_work_dir()?;
+
+        let mut command = Command::new(program);
+        command
+            .args(parts)
+            .current_dir(&work_dir)
+            .env_clear()
+            .env("NOTER_HOOK_STAGE", stage.as_str())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        // Keep just enough environment for the program to be found and started
+        for key in ["PATH", "SystemRoot"] {
+            if let Ok(value) = std::env::var(key) {
+                command.env(key, value);
+            }
+        }
+
+        let result = self.run_with_timeout(command, input);
+        let _ = fs::remove_dir_all(&work_dir);
+        result
+    }
+
+    fn run_with_timeout(&self, mut command: Command, input: &str) -> Result<String> {
+        let mut child = command.spawn().context("Failed to start hook")?;
+
+        // Feed stdin and drain stdout/stderr on separate threads so that large
+        // documents can't deadlock against full pipes
+        let mut stdin = child.stdin.take().context("Failed to open hook stdin")?;
+        let input = input.to_string();
+        let writer = std::thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+
+        let mut stdout = child.stdout.take().context("Failed to open hook stdout")?;
+        let reader = std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            stdout.read_to_end(&mut buffer).map(|_| buffer)
+        });
+
+        let mut stderr = child.stderr.take().context("Failed to open hook stderr")?;
+        let error_reader = std::thread::spawn(move || {
+            let mut buffer = String::new();
+            let _ = stderr.read_to_string(&mut buffer);
+            buffer
+        });
+
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(anyhow!(
+                    "Timed out after {} seconds",
+                    self.timeout.as_secs()
+                ));
+            }
+
+            std::thread::sleep(Duration::from_millis(20));
+        };
+
+        let _ = writer.join();
+        let output = reader
+            .join()
+            .map_err(|_| anyhow!("Failed to read hook output"))??;
+        let error_output = error_reader.join().unwrap_or_default();
+
+        if !status.success() {
+            return Err(anyhow!("Exited with {}: {}", status, error_output.trim()));
+        }
+
+        String::from_utf8(output).context("Hook output is not valid UTF-8")
+    }
+
+    fn create_work_dir() -> Result<PathBuf> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("noter-hook-{}-{}", std::process::id(), nanos));
+        fs::create_dir_all(&dir).context("Failed to create hook working directory")?;
+        Ok(dir)
+    }
+
+    /// Strip trailing whitespace and collapse runs of blank lines
+    fn minify(input: &str) -> String {
+        let mut output = Vec::new();
+        let mut previous_blank = false;
+
+        for line in input.lines() {
+            let line = line.trim_end();
+            let is_blank = line.is_empty();
+
+            if !(is_blank && previous_blank) {
+                output.push(line);
+            }
+            previous_blank = is_blank;
+        }
+
+        let mut result = output.join("\n");
+        if input.ends_with('\n') {
+            result.push('\n');
+        }
+        result
+    }
+
+    /// Convert all line endings to the configured style
+    pub fn normalize_line_endings(input: &str, style: &LineEndingStyle) -> String {
+        let unix = input.replace("\r\n", "\n").replace('\r', "\n");
+
+        let ending = match style {
+            LineEndingStyle::Unix => "\n",
+            LineEndingStyle::Windows => "\r\n",
+            LineEndingStyle::Mac => "\r",
+            LineEndingStyle::Auto => {
+                if cfg!(windows) {
+                    "\r\n"
+                } else {
+                    "\n"
+                }
+            }
+        };
+
+        if ending == "\n" {
+            unix
+        } else {
+            unix.replace('\n', ending)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine_with_hooks(pre: &[&str], post: &[&str]) -> EngineConfig {
+        let mut engine = EngineConfig::default();
+        engine.processing.line_endings = LineEndingStyle::Unix;
+        engine.processing.hooks.pre_process = pre.iter().map(|s| s.to_string()).collect();
+        engine.processing.hooks.post_process = post.iter().map(|s| s.to_string()).collect();
+        engine.rendering.timeout_seconds = 2;
+        engine
+    }
+
+    #[test]
+    fn test_normalize_line_endings() {
+        let input = "a\r\nb\nc\rd";
+        assert_eq!(
+            DocumentProcessor::normalize_line_endings(input, &LineEndingStyle::Unix),
+            "a\nb\nc\nd"
+        );
+        assert_eq!(
+            DocumentProcessor::normalize_line_endings(input, &LineEndingStyle::Windows),
+            "a\r\nb\r\nc\r\nd"
+        );
+    }
+
+    #[test]
+    fn test_builtin_hooks_and_minify() {
+        let mut engine = engine_with_hooks(&["builtin:trim-trailing-whitespace"], &[]);
+        engine.processing.minify_output = true;
+        let processor = DocumentProcessor::new(&engine, false);
+
+        let output = processor.process("= A   \n\n\n\n= B").unwrap();
+        assert_eq!(output, "= A\n\n= B");
+    }
+
+    #[test]
+    fn test_external_hooks_require_opt_in() {
+        let engine = engine_with_hooks(&[], &["tr a-z A-Z"]);
+
+        let untrusted = DocumentProcessor::new(&engine, false);
+        assert_eq!(untrusted.process("hello").unwrap(), "hello");
+        assert_eq!(untrusted.skipped_hooks(), vec!["tr a-z A-Z"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_hook_receives_document() {
+        let engine = engine_with_hooks(&[], &["tr a-z A-Z"]);
+        let processor = DocumentProcessor::new(&engine, true);
+
+        assert_eq!(processor.process("hello\n").unwrap(), "HELLO\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_hook_timeout() {
+        let mut engine = engine_with_hooks(&["sleep 5"], &[]);
+        engine.rendering.timeout_seconds = 1;
+        let processor = DocumentProcessor::new(&engine, true);
+
+        let error = processor.process("hello").unwrap_err();
+        assert!(format!("{:#}", error).contains("Timed out"));
+    }
+}
@@ -0,0 +1,756 @@
+//! Release providers for template repositories
+//!
+//! A `ReleaseProvider` knows how to list the releases of a repository on one
+//! kind of hosting service and how to download release assets. GitHub, GitLab,
+//! Gitea/Forgejo and plain HTTPS URLs are supported; self-hosted instances are
+//! reached through the repository's `base_url`, the instance's web address
+//! (e.g. `https://gitlab.example.com`) for every provider.
+
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::config::{TemplateProvider, TemplateRepository};
+
+const USER_AGENT: &str = "dtu-notes-cli";
+pub const GITHUB_BASE: &str = "https://github.com";
+pub const GITHUB_API_BASE: &str = "https://api.github.com";
+pub const GITLAB_BASE: &str = "https://gitlab.com";
+pub const GITEA_BASE: &str = "https://codeberg.org";
+
+/// Placeholder replaced by the release tag in generic URL repositories
+pub const VERSION_PLACEHOLDER: &str = "{version}";
+
+/// A release, independent of the hosting service
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub tag_name: String,
+    /// Source archive of the release (tarball)
+    pub archive_url: String,
+    pub prerelease: bool,
+    pub assets: Vec<ReleaseAsset>,
+    /// The archive behind the URL can change without the tag changing (a
+    /// plain URL without a version), so it must be downloaded to be compared
+    pub mutable: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub download_url: String,
+}
+
+impl Release {
+    /// A source archive of a single commit; the "tag" is the full commit SHA
+    fn commit(sha: &str, archive_url: String) -> Self {
+        Self {
+            tag_name: sha.to_string(),
+            archive_url,
+            prerelease: false,
+            assets: Vec::new(),
+            mutable: false,
+        }
+    }
+
+    /// URL of the archive to install: a template asset, any archive asset, or the source tarball
+    pub fn download_url(&self) -> &str {
+        self.assets
+            .iter()
+            .find(|asset| asset.name.to_lowercase().contains("template"))
+            .or_else(|| {
+                self.assets.iter().find(|asset| {
+                    let name = asset.name.to_lowercase();
+                    name.ends_with(".zip") || name.ends_with(".tar.gz")
+                })
+            })
+            .map_or(self.archive_url.as_str(), |asset| &asset.download_url)
+    }
+}
+
+/// Lists releases and downloads assets from a hosting service
+pub trait ReleaseProvider {
+    /// All releases of a repository, newest first
+    fn list_releases(&self, repository: &str) -> Result<Vec<Release>>;
+
+    /// The newest release that isn't a pre-release
+    fn latest_release(&self, repository: &str) -> Result<Release> {
+        self.list_releases(repository)?
+            .into_iter()
+            .find(|release| !release.prerelease)
+            .ok_or_else(|| anyhow!("No releases found for {}", repository))
+    }
+
+    /// The release with the given tag
+    fn release(&self, repository: &str, tag: &str) -> Result<Release>;
+
+    /// The source archive of a branch head or commit, resolved to a full SHA
+    fn commit_release(&self, repository: &str, _git_ref: &str) -> Result<Release> {
+        Err(anyhow!(
+            "{} doesn't support installing from a branch or commit",
+            repository
+        ))
+    }
+
+    /// Download a release asset
+    fn download(&self, url: &str) -> Result<Vec<u8>> {
+        get_bytes(url)
+    }
+}
+
+/// Abbreviated commit SHA used in versions and cache file names
+pub fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+/// Percent-encode a tag or branch name for use as one URL path segment or
+/// query value (`feature/x` -> `feature%2Fx`)
+fn encode_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// API base of a GitHub instance: api.github.com for github.com, and
+/// `<base_url>/api/v3` for GitHub Enterprise Server
+fn github_api_base(base_url: &str) -> String {
+    if base_url.eq_ignore_ascii_case(GITHUB_BASE) {
+        GITHUB_API_BASE.to_string()
+    } else {
+        format!("{}/api/v3", base_url)
+    }
+}
+
+/// Error for a release that couldn't be fetched by its tag
+fn release_not_found(tag: &str, repository: &str) -> String {
+    format!("Release '{}' not found for {}", tag, repository)
+}
+
+/// Create the provider configured for a repository
+pub fn provider_for(repo: &TemplateRepository) -> Box<dyn ReleaseProvider> {
+    let base_url = |default: &str| {
+        repo.base_url
+            .as_deref()
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    };
+
+    match repo.provider {
+        TemplateProvider::GitHub => Box::new(GitHubProvider {
+            api_base: github_api_base(&base_url(GITHUB_BASE)),
+        }),
+        TemplateProvider::GitLab => Box::new(GitLabProvider {
+            base_url: base_url(GITLAB_BASE),
+        }),
+        TemplateProvider::Gitea => Box::new(GiteaProvider {
+            base_url: base_url(GITEA_BASE),
+        }),
+        TemplateProvider::Url => Box::new(UrlProvider),
+    }
+}
+
+/// Lowercase host of a URL
+pub fn url_host(url: &str) -> Option<String> {
+    let uri: ureq::http::Uri = url.parse().ok()?;
+    uri.host().map(str::to_lowercase)
+}
+
+/// Hosts a repository's archives are downloaded from
+pub fn download_hosts(repo: &TemplateRepository) -> Vec<String> {
+    let base_url = |default: &str| repo.base_url.as_deref().unwrap_or(default).to_string();
+
+    let hosts = match repo.provider {
+        TemplateProvider::GitHub => {
+            let base_url = base_url(GITHUB_BASE);
+            vec![url_host(&base_url), url_host(&github_api_base(&base_url))]
+        }
+        TemplateProvider::GitLab => vec![url_host(&base_url(GITLAB_BASE))],
+        TemplateProvider::Gitea => vec![url_host(&base_url(GITEA_BASE))],
+        TemplateProvider::Url => vec![url_host(&repo.repository)],
+    };
+    hosts.into_iter().flatten().collect()
+}
+
+fn get_json<T: DeserializeOwned>(url: &str) -> Result<T> {
+    let mut response = ureq::get(url)
+        .header("User-Agent", USER_AGENT)
+        .call()
+        .with_context(|| format!("Request to {} failed", url))?;
+
+    let body = response
+        .body_mut()
+        .read_to_string()
+        .context("Failed to read response body")?;
+
+    serde_json::from_str(&body).with_context(|| format!("Unexpected response from {}", url))
+}
+
+fn get_bytes(url: &str) -> Result<Vec<u8>> {
+    let response = ureq::get(url)
+        .header("User-Agent", USER_AGENT)
+        .call()
+        .with_context(|| format!("Failed to download {}", url))?;
+
+    response
+        .into_body()
+        .with_config()
+        .limit(u64::MAX)
+        .read_to_vec()
+        .context("Failed to read response body")
+}
+
+/// Release JSON shared by GitHub and Gitea/Forgejo
+#[derive(Debug, Deserialize)]
+struct ReleaseJson {
+    tag_name: String,
+    tarball_url: String,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<AssetJson>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetJson {
+    name: String,
+    browser_download_url: String,
+}
+
+impl From<ReleaseJson> for Release {
+    fn from(json: ReleaseJson) -> Self {
+        Self {
+            tag_name: json.tag_name,
+            archive_url: json.tarball_url,
+            prerelease: json.prerelease,
+            assets: json
+                .assets
+                .into_iter()
+                .map(|asset| ReleaseAsset {
+                    name: asset.name,
+                    download_url: asset.browser_download_url,
+                })
+                .collect(),
+            mutable: false,
+        }
+    }
+}
+
+/// GitHub releases (github.com or GitHub Enterprise Server via `base_url`)
+pub struct GitHubProvider {
+    pub api_base: String,
+}
+
+impl Default for GitHubProvider {
+    fn default() -> Self {
+        Self {
+            api_base: GITHUB_API_BASE.to_string(),
+        }
+    }
+}
+
+impl ReleaseProvider for GitHubProvider {
+    fn list_releases(&self, repository: &str) -> Result<Vec<Release>> {
+        let url = format!("{}/repos/{}/releases", self.api_base, repository);
+        let releases: Vec<ReleaseJson> = get_json(&url)?;
+        Ok(releases.into_iter().map(Release::from).collect())
+    }
+
+    fn latest_release(&self, repository: &str) -> Result<Release> {
+        let url = format!("{}/repos/{}/releases/latest", self.api_base, repository);
+        let release: ReleaseJson =
+            get_json(&url).context("Failed to fetch latest release information")?;
+        Ok(release.into())
+    }
+
+    fn release(&self, repository: &str, tag: &str) -> Result<Release> {
+        let url = format!(
+            "{}/repos/{}/releases/tags/{}",
+            self.api_base,
+            repository,
+            encode_component(tag)
+        );
+        let release: ReleaseJson = get_json(&url).context(release_not_found(tag, repository))?;
+        Ok(release.into())
+    }
+
+    fn commit_release(&self, repository: &str, git_ref: &str) -> Result<Release> {
+        let url = format!(
+            "{}/repos/{}/commits/{}",
+            self.api_base,
+            repository,
+            encode_component(git_ref)
+        );
+        let commit: CommitJson = get_json(&url)?;
+        let archive_url = format!(
+            "{}/repos/{}/tarball/{}",
+            self.api_base, repository, commit.sha
+        );
+        Ok(Release::commit(&commit.sha, archive_url))
+    }
+}
+
+/// Commit JSON of GitHub and Gitea/Forgejo
+#[derive(Debug, Deserialize)]
+struct CommitJson {
+    sha: String,
+}
+
+/// Gitea and Forgejo releases (codeberg.org by default)
+pub struct GiteaProvider {
+    pub base_url: String,
+}
+
+impl ReleaseProvider for GiteaProvider {
+    fn list_releases(&self, repository: &str) -> Result<Vec<Release>> {
+        let url = format!("{}/api/v1/repos/{}/releases", self.base_url, repository);
+        let releases: Vec<ReleaseJson> = get_json(&url)?;
+        Ok(releases.into_iter().map(Release::from).collect())
+    }
+
+    fn release(&self, repository: &str, tag: &str) -> Result<Release> {
+        let url = format!(
+            "{}/api/v1/repos/{}/releases/tags/{}",
+            self.base_url,
+            repository,
+            encode_component(tag)
+        );
+        let release: ReleaseJson = get_json(&url).context(release_not_found(tag, repository))?;
+        Ok(release.into())
+    }
+
+    fn commit_release(&self, repository: &str, git_ref: &str) -> Result<Release> {
+        let url = format!(
+            "{}/api/v1/repos/{}/commits?sha={}&limit=1",
+            self.base_url,
+            repository,
+            encode_component(git_ref)
+        );
+        let commits: Vec<CommitJson> = get_json(&url)?;
+        let commit = commits
+            .first()
+            .ok_or_else(|| anyhow!("No commit found for '{}' in {}", git_ref, repository))?;
+
+        let archive_url = format!(
+            "{}/api/v1/repos/{}/archive/{}.tar.gz",
+            self.base_url, repository, commit.sha
+        );
+        Ok(Release::commit(&commit.sha, archive_url))
+    }
+}
+
+/// GitLab releases (gitlab.com or self-hosted)
+pub struct GitLabProvider {
+    pub base_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabReleaseJson {
+    tag_name: String,
+    #[serde(default)]
+    upcoming_release: bool,
+    #[serde(default)]
+    assets: GitLabAssetsJson,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GitLabAssetsJson {
+    #[serde(default)]
+    sources: Vec<GitLabSourceJson>,
+    #[serde(default)]
+    links: Vec<GitLabLinkJson>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabSourceJson {
+    format: String,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabLinkJson {
+    name: String,
+    url: String,
+    #[serde(default)]
+    direct_asset_url: Option<String>,
+}
+
+impl GitLabProvider {
+    /// Project paths are passed URL-encoded (`group/project` -> `group%2Fproject`)
+    fn project_id(repository: &str) -> String {
+        repository.replace('/', "%2F")
+    }
+}
+
+impl TryFrom<GitLabReleaseJson> for Release {
+    type Error = anyhow::Error;
+
+    fn try_from(json: GitLabReleaseJson) -> Result<Self> {
+        let archive_url = json
+            .assets
+            .sources
+            .iter()
+            .find(|source| source.format == "tar.gz")
+            .map(|source| source.url.clone())
+            .ok_or_else(|| anyhow!("Release {} has no tar.gz source", json.tag_name))?;
+
+        Ok(Release {
+            tag_name: json.tag_name,
+            archive_url,
+            prerelease: json.upcoming_release,
+            assets: json
+                .assets
+                .links
+                .into_iter()
+                .map(|link| ReleaseAsset {
+                    name: link.name,
+                    download_url: link.direct_asset_url.unwrap_or(link.url),
+                })
+                .collect(),
+            mutable: false,
+        })
+    }
+}
+
+impl ReleaseProvider for GitLabProvider {
+    fn list_releases(&self, repository: &str) -> Result<Vec<Release>> {
+        let url = format!(
+            "{}/api/v4/projects/{}/releases",
+            self.base_url,
+            Self::project_id(repository)
+        );
+        let releases: Vec<GitLabReleaseJson> = get_json(&url)?;
+        releases.into_iter().map(Release::try_from).collect()
+    }
+
+    fn release(&self, repository: &str, tag: &str) -> Result<Release> {
+        let url = format!(
+            "{}/api/v4/projects/{}/releases/{}",
+            self.base_url,
+            Self::project_id(repository),
+            encode_component(tag)
+        );
+        let release: GitLabReleaseJson =
+            get_json(&url).context(release_not_found(tag, repository))?;
+        release.try_into()
+    }
+
+    fn commit_release(&self, repository: &str, git_ref: &str) -> Result<Release> {
+        let project = Self::project_id(repository);
+        let url = format!(
+            "{}/api/v4/projects/{}/repository/commits/{}",
+            self.base_url,
+            project,
+            encode_component(git_ref)
+        );
+        let commit: GitLabCommitJson = get_json(&url)?;
+
+        let archive_url = format!(
+            "{}/api/v4/projects/{}/repository/archive.tar.gz?sha={}",
+            self.base_url, project, commit.id
+        );
+        Ok(Release::commit(&commit.id, archive_url))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabCommitJson {
+    id: String,
+}
+
+/// A plain archive URL, optionally containing a `{version}` placeholder
+///
+/// There is no release listing: the repository's `version` (or "latest" for
+/// URLs without a placeholder) is the only release. Without a placeholder the
+/// URL always names the same archive, whose contents can change.
+pub struct UrlProvider;
+
+impl UrlProvider {
+    fn release_for(url: &str, tag: &str) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            archive_url: url.replace(VERSION_PLACEHOLDER, tag),
+            prerelease: false,
+            assets: Vec::new(),
+            mutable: !url.contains(VERSION_PLACEHOLDER),
+        }
+    }
+}
+
+impl ReleaseProvider for UrlProvider {
+    fn list_releases(&self, repository: &str) -> Result<Vec<Release>> {
+        Ok(vec![self.latest_release(repository)?])
+    }
+
+    fn latest_release(&self, repository: &str) -> Result<Release> {
+        if repository.contains(VERSION_PLACEHOLDER) {
+            return Err(anyhow!(
+                "URL '{}' contains {}; set a version for this repository",
+                repository,
+                VERSION_PLACEHOLDER
+            ));
+        }
+        Ok(Self::release_for(repository, "latest"))
+    }
+
+    fn release(&self, repository: &str, tag: &str) -> Result<Release> {
+        Ok(Self::release_for(repository, tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    /// Minimal HTTP server answering GET requests from a fixed route table
+    fn serve(routes: Vec<(String, Vec<u8>)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                let _ = reader.read_line(&mut request_line);
+                loop {
+                    let mut header = String::new();
+                    if reader.read_line(&mut header).unwrap_or(0) == 0 || header == "\r\n" {
+                        break;
+                    }
+                }
+
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                let (status, body) = routes
+                    .iter()
+                    .find(|(route, _)| route == path)
+                    .map_or(("404 Not Found", &[][..]), |(_, body)| {
+                        ("200 OK", body.as_slice())
+                    });
+
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                let _ = stream.write_all(body);
+            }
+        });
+
+        format!("http://{}", address)
+    }
+
+    fn route(path: &str, body: &str) -> (String, Vec<u8>) {
+        (path.to_string(), body.as_bytes().to_vec())
+    }
+
+    fn repository(
+        provider: TemplateProvider,
+        repository: &str,
+        base_url: &str,
+    ) -> TemplateRepository {
+        TemplateRepository {
+            name: "test".to_string(),
+            repository: repository.to_string(),
+            provider,
+            base_url: Some(base_url.to_string()),
+            ..TemplateRepository::default()
+        }
+    }
+
+    #[test]
+    fn test_github_latest_release_prefers_template_asset() {
+        let base = serve(vec![route(
+            "/api/v3/repos/owner/repo/releases/latest",
+            r#"{"tag_name": "v1.2.0", "name": "1.2", "published_at": "2025-01-01T00:00:00Z",
+                "tarball_url": "https://example.com/tarball", "prerelease": false,
+                "assets": [{"name": "dtu-template.zip", "browser_download_url": "https://example.com/t.zip",
+                            "content_type": "application/zip", "size": 10}]}"#,
+        )]);
+
+        let provider = provider_for(&repository(TemplateProvider::GitHub, "owner/repo", &base));
+        let release = provider.latest_release("owner/repo").unwrap();
+
+        assert_eq!(release.tag_name, "v1.2.0");
+        assert_eq!(release.download_url(), "https://example.com/t.zip");
+    }
+
+    #[test]
+    fn test_gitlab_skips_upcoming_releases() {
+        let base = serve(vec![route(
+            "/api/v4/projects/group%2Fproject/releases",
+            r#"[{"tag_name": "v2.0.0", "upcoming_release": true,
+                 "assets": {"sources": [{"format": "tar.gz", "url": "https://example.com/v2.tar.gz"}], "links": []}},
+                {"tag_name": "v1.0.0", "released_at": "2025-01-01T00:00:00Z",
+                 "assets": {"sources": [{"format": "zip", "url": "https://example.com/v1.zip"},
+                                        {"format": "tar.gz", "url": "https://example.com/v1.tar.gz"}],
+                            "links": []}}]"#,
+        )]);
+
+        let provider = provider_for(&repository(
+            TemplateProvider::GitLab,
+            "group/project",
+            &base,
+        ));
+        let release = provider.latest_release("group/project").unwrap();
+
+        assert_eq!(release.tag_name, "v1.0.0");
+        assert_eq!(release.download_url(), "https://example.com/v1.tar.gz");
+    }
+
+    #[test]
+    fn test_release_by_tag() {
+        let base = serve(vec![
+            route(
+                "/api/v1/repos/owner/repo/releases/tags/v0.1.0",
+                r#"{"tag_name": "v0.1.0", "tarball_url": "https://example.com/v0.1.0.tar.gz", "assets": []}"#,
+            ),
+            route(
+                "/api/v3/repos/owner/repo/releases/tags/release%2B1",
+                r#"{"tag_name": "release+1", "tarball_url": "https://example.com/r1.tar.gz", "assets": []}"#,
+            ),
+            route(
+                "/api/v4/projects/group%2Fproject/releases/v1.0.0",
+                r#"{"tag_name": "v1.0.0",
+                    "assets": {"sources": [{"format": "tar.gz", "url": "https://example.com/v1.tar.gz"}], "links": []}}"#,
+            ),
+        ]);
+
+        // Old releases are fetched by their tag, not found in a listing
+        let gitea = provider_for(&repository(TemplateProvider::Gitea, "owner/repo", &base));
+        let release = gitea.release("owner/repo", "v0.1.0").unwrap();
+        assert_eq!(release.archive_url, "https://example.com/v0.1.0.tar.gz");
+        let error = gitea.release("owner/repo", "v9.9.9").unwrap_err();
+        assert!(error.to_string().contains("Release 'v9.9.9' not found"));
+
+        let github = provider_for(&repository(TemplateProvider::GitHub, "owner/repo", &base));
+        let release = github.release("owner/repo", "release+1").unwrap();
+        assert_eq!(release.archive_url, "https://example.com/r1.tar.gz");
+
+        let gitlab = provider_for(&repository(
+            TemplateProvider::GitLab,
+            "group/project",
+            &base,
+        ));
+        let release = gitlab.release("group/project", "v1.0.0").unwrap();
+        assert_eq!(release.download_url(), "https://example.com/v1.tar.gz");
+    }
+
+    #[test]
+    fn test_download_hosts() {
+        let github = TemplateRepository {
+            repository: "owner/repo".to_string(),
+            ..TemplateRepository::default()
+        };
+        assert_eq!(
+            download_hosts(&github),
+            vec!["github.com", "api.github.com"]
+        );
+
+        let gitlab = repository(
+            TemplateProvider::GitLab,
+            "group/project",
+            "https://GitLab.example.com:8443/",
+        );
+        assert_eq!(download_hosts(&gitlab), vec!["gitlab.example.com"]);
+
+        let url = repository(
+            TemplateProvider::Url,
+            "https://example.com/notes-{version}.tar.gz",
+            "",
+        );
+        assert_eq!(download_hosts(&url), vec!["example.com"]);
+        assert_eq!(url_host("not a url"), None);
+    }
+
+    #[test]
+    fn test_base_url_is_the_web_address() {
+        assert_eq!(github_api_base(GITHUB_BASE), GITHUB_API_BASE);
+        assert_eq!(
+            github_api_base("https://github.example.com"),
+            "https://github.example.com/api/v3"
+        );
+        assert_eq!(encode_component("feature/x y+1"), "feature%2Fx%20y%2B1");
+    }
+
+    #[test]
+    fn test_commit_release_resolves_branch_head() {
+        let base = serve(vec![
+            route(
+                "/api/v3/repos/owner/repo/commits/feature%2Fdev",
+                r#"{"sha": "0123456789abcdef0123456789abcdef01234567"}"#,
+            ),
+            route(
+                "/api/v4/projects/group%2Fproject/repository/commits/feature%2Fnew-layout",
+                r#"{"id": "fedcba9876543210fedcba9876543210fedcba98"}"#,
+            ),
+        ]);
+
+        let github = provider_for(&repository(TemplateProvider::GitHub, "owner/repo", &base));
+        let release = github.commit_release("owner/repo", "feature/dev").unwrap();
+        assert_eq!(short_sha(&release.tag_name), "0123456");
+        assert!(
+            release
+                .download_url()
+                .ends_with("/tarball/0123456789abcdef0123456789abcdef01234567")
+        );
+
+        let gitlab = provider_for(&repository(
+            TemplateProvider::GitLab,
+            "group/project",
+            &base,
+        ));
+        let release = gitlab
+            .commit_release("group/project", "feature/new-layout")
+            .unwrap();
+        assert!(
+            release
+                .download_url()
+                .ends_with("archive.tar.gz?sha=fedcba9876543210fedcba9876543210fedcba98")
+        );
+
+        let url = provider_for(&repository(
+            TemplateProvider::Url,
+            "https://example.com/t.tar.gz",
+            "",
+        ));
+        assert!(
+            url.commit_release("https://example.com/t.tar.gz", "main")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_url_provider_downloads_versioned_archive() {
+        let base = serve(vec![(
+            "/templates/notes-1.0.0.tar.gz".to_string(),
+            vec![1, 2, 3],
+        )]);
+        let url = format!("{}/templates/notes-{{version}}.tar.gz", base);
+
+        let provider = provider_for(&repository(TemplateProvider::Url, &url, ""));
+        assert!(provider.latest_release(&url).is_err());
+        assert!(
+            provider
+                .latest_release("https://example.com/t.tar.gz")
+                .unwrap()
+                .mutable
+        );
+
+        let release = provider.release(&url, "1.0.0").unwrap();
+        assert!(!release.mutable);
+        assert_eq!(
+            provider.download(release.download_url()).unwrap(),
+            vec![1, 2, 3]
+        );
+    }
+}
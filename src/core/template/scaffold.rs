@@ -0,0 +1,300 @@
+//! Template package scaffolding
+//!
+//! Generates the files of a new, working template package: the Typst package
+//! manifest, the template functions and a `.noter.config.toml` describing
+//! lecture and assignment templates, a sample variant and a course mapping.
+
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::path::Path;
+
+use super::config::TemplateConfig;
+use super::constants::TOML_FILE_NAME;
+
+/// Version given to newly scaffolded packages
+pub const INITIAL_PACKAGE_VERSION: &str = "0.1.0";
+
+/// A template package to be generated
+pub struct PackageScaffold {
+    pub name: String,
+    pub version: String,
+    pub author: String,
+}
+
+impl PackageScaffold {
+    /// Create a scaffold for a package with the given name
+    ///
+    /// Names follow Typst's package rules: lowercase letters, digits and
+    /// hyphens, starting with a letter.
+    pub fn new(name: &str, author: &str) -> Result<Self> {
+        let valid = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+
+        if !valid {
+            return Err(anyhow!(
+                "Invalid package name '{}'. Use lowercase letters, digits and hyphens, starting with a letter (e.g. 'my-notes')",
+                name
+            ));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            version: INITIAL_PACKAGE_VERSION.to_string(),
+            author: author.to_string(),
+        })
+    }
+
+    /// All files of the package as (relative path, content) pairs
+    pub fn files(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("typst.toml", self.typst_manifest()),
+            ("lib.typ", Self::library()),
+            (TOML_FILE_NAME, self.noter_config()),
+            ("README.md", self.readme()),
+        ]
+    }
+
+    /// Parse the generated `.noter.config.toml`
+    pub fn template_config(&self) -> Result<TemplateConfig> {
+        toml::from_str(&self.noter_config()).context("Generated package configuration is invalid")
+    }
+
+    /// Write all package files into `dir`, which must not exist or be empty
+    pub fn write_to(&self, dir: &Path) -> Result<()> {
+        if dir.exists() && fs::read_dir(dir)?.next().is_some() {
+            return Err(anyhow!(
+                "Directory {} already exists and is not empty",
+                dir.display()
+            ));
+        }
+
+        fs::create_dir_all(dir)?;
+        for (file, content) in self.files() {
+            fs::write(dir.join(file), content)
+                .with_context(|| format!("Failed to write {}", file))?;
+        }
+
+        Ok(())
+    }
+
+    fn typst_manifest(&self) -> String {
+        let authors = if self.author.trim().is_empty() {
+            String::new()
+        } else {
+            format!("authors = [\"{}\"]\n", self.author.replace('"', "\\\""))
+        };
+
+        format!(
+            r#"[package]
+name = "{name}"
+version = "{version}"
+entrypoint = "lib.typ"
+{authors}description = "Note templates for noter"
+"#,
+            name = self.name,
+            version = self.version,
+            authors = authors,
+        )
+    }
+
+    fn noter_config(&self) -> String {
+        let author = if self.author.trim().is_empty() {
+            String::new()
+        } else {
+            format!("author = \"{}\"\n", self.author.replace('"', "\\\""))
+        };
+
+        format!(
+            r#"# noter template package configuration
+# See docs/example.noter.config.toml in the noter repository for all options,
+# including the optional [engine] section.
+
+[metadata]
+name = "{name}"
+version = "{version}"
+description = "Note templates for noter"
+{author}license = "MIT"
+
+# Lecture notes, used by `noter note`
+[[templates]]
+name = "note"
+display_name = "Lecture Notes"
+description = "Template for lecture notes"
+file = "lib.typ"
+function = "lecture-template"
+supports_variants = true
+course_types = ["all"]
+default_sections = ["Key Concepts", "Examples", "Questions"]
+
+# Extra parameter, set with `noter note <course> --var week=3`
+[[templates.parameters]]
+name = "week"
+parameter_type = "Integer"
+default = "1"
+description = "Lecture week"
+
+# Assignments, used by `noter assignment`
+[[templates]]
+name = "assignment"
+display_name = "Assignment"
+description = "Template for assignments"
+file = "lib.typ"
+function = "assignment-template"
+supports_variants = true
+course_types = ["all"]
+default_sections = ["Problem 1", "Problem 2", "Conclusion"]
+# Pass the group members with study numbers as `authors`
+supports_authors = true
+
+# Used automatically for courses mapped to "math" below
+[[variants]]
+template = "note"
+name = "math"
+display_name = "Math Lecture Notes"
+course_types = ["math"]
+file = "lib.typ"
+function = "math-lecture-template"
+additional_sections = ["Definitions", "Theorems and Proofs"]
+
+[course_mapping]
+"01xxx" = "math"
+"02xxx" = "programming"
+"#,
+            name = self.name,
+            version = self.version,
+            author = author,
+        )
+    }
+
+    fn library() -> String {
+        r#"// Template functions called by noter through `#show: <function>.with(...)`.
+// noter always passes course, course-name, title, date, author and semester,
+// plus any [[templates.parameters]] declared in .noter.config.toml.
+// Templates with `supports_authors = true` also get `authors`, an array of
+// (name: .., student-id: ..) dictionaries, for group work.
+
+#let header(course, course-name, title, date, author, semester) = {
+  align(center)[
+    #text(size: 10pt)[#course - #course-name]
+
+    #text(size: 18pt, weight: "bold")[#title]
+
+    #text(size: 10pt)[#author | #semester | #date.display("[day]/[month]/[year]")]
+  ]
+  v(1em)
+}
+
+#let lecture-template(
+  course: "",
+  course-name: "",
+  title: "",
+  date: datetime.today(),
+  author: "",
+  semester: "",
+  week: 1,
+  body,
+) = {
+  set document(title: title, author: author)
+  set page(margin: 2cm, numbering: "1")
+  set heading(numbering: "1.1")
+
+  header(course, course-name, title, date, author, semester)
+  text(size: 9pt, fill: gray)[Week #week]
+
+  body
+}
+
+#let math-lecture-template(..args, body) = {
+  set math.equation(numbering: "(1)")
+  lecture-template(..args, body)
+}
+
+#let assignment-template(
+  course: "",
+  course-name: "",
+  title: "",
+  date: datetime.today(),
+  author: "",
+  semester: "",
+  authors: (),
+  body,
+) = {
+  set document(title: title, author: author)
+  set page(margin: 2.5cm, numbering: "1")
+  set heading(numbering: "Problem 1.a")
+
+  header(course, course-name, title, date, author, semester)
+  if authors.len() > 1 or authors.any(a => a.student-id != none) {
+    align(center, table(
+      columns: 2,
+      stroke: none,
+      ..authors.map(a => (a.name, if a.student-id != none { a.student-id } else { "" })).flatten()
+    ))
+  }
+
+  body
+}
+"#
+        .to_string()
+    }
+
+    fn readme(&self) -> String {
+        format!(
+            r#"# {name}
+
+Note templates for [noter](https://github.com/HollowNumber/noter).
+
+## Development
+
+Install the package locally to try it out. With `--link`, edits to this
+directory are used immediately:
+
+```bash
+noter template install . --link
+noter note 02101 -t "Test note"
+```
+
+Templates are defined in `{config}`, the Typst functions live in `lib.typ`.
+"#,
+            name = self.name,
+            config = TOML_FILE_NAME,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::template::validation::{TemplateValidator, ValidationSeverity};
+
+    #[test]
+    fn test_scaffold_is_valid_package() {
+        let scaffold = PackageScaffold::new("my-notes", "Jane Doe").unwrap();
+        let config = scaffold.template_config().unwrap();
+
+        assert_eq!(config.metadata.name, "my-notes");
+        assert!(config.templates.iter().any(|t| t.name == "note"));
+        assert!(
+            config
+                .templates
+                .iter()
+                .any(|t| t.name == "assignment" && t.supports_authors)
+        );
+
+        let issues = TemplateValidator::validate_template_config(&config).unwrap();
+        assert!(
+            !issues
+                .iter()
+                .any(|i| i.severity == ValidationSeverity::Error)
+        );
+    }
+
+    #[test]
+    fn test_scaffold_rejects_invalid_names() {
+        assert!(PackageScaffold::new("My Notes", "").is_err());
+        assert!(PackageScaffold::new("1notes", "").is_err());
+        assert!(PackageScaffold::new("", "").is_err());
+    }
+}
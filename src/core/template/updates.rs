@@ -0,0 +1,193 @@
+//! Automatic template update checks
+//!
+//! When `templates.auto_update` is enabled, noter compares the installed
+//! template versions with the latest releases at most once per
+//! `templates.update_check_interval_hours`. The time of the last check is
+//! kept in a small state file in the cache directory. Checks run on a
+//! background thread with a short deadline so a slow or missing network
+//! never holds up the command, and updates are only installed after it.
+//! Packages rolled back with `noter template rollback` are left alone.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use super::fetcher::Fetcher;
+use super::providers;
+use super::versions::VersionHistory;
+use crate::config::Config;
+
+const STATE_FILE_NAME: &str = "update-check.json";
+
+/// How long a command waits for the update check before giving up
+const CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// A template repository with a newer release than the one installed
+#[derive(Debug, Clone, PartialEq)]
+pub struct AvailableUpdate {
+    pub name: String,
+    pub installed: String,
+    pub latest: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateCheckState {
+    pub last_checked: Option<DateTime<Utc>>,
+}
+
+impl UpdateCheckState {
+    fn path() -> Result<PathBuf> {
+        Ok(dirs::cache_dir()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".cache")))
+            .context("Could not determine cache directory")?
+            .join("dtu-notes")
+            .join(STATE_FILE_NAME))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Whether the interval since the last check has passed
+    pub fn is_due(&self, interval_hours: u64, now: DateTime<Utc>) -> bool {
+        let interval = i64::try_from(interval_hours)
+            .ok()
+            .and_then(Duration::try_hours);
+
+        match (self.last_checked, interval) {
+            (None, _) => true,
+            (Some(last_checked), Some(interval)) => now - last_checked >= interval,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+pub struct UpdateChecker;
+
+impl UpdateChecker {
+    /// Run the throttled check if it is enabled and due
+    ///
+    /// Returns None when no check ran, or when it didn't finish in time.
+    pub fn check_if_due(config: &Config) -> Option<Vec<AvailableUpdate>> {
+        if !config.templates.auto_update || config.templates.is_offline() {
+            return None;
+        }
+
+        let mut state = UpdateCheckState::load();
+        let now = Utc::now();
+        if !state.is_due(config.templates.update_check_interval_hours, now) {
+            return None;
+        }
+
+        // Record the attempt up front so an unreachable network is only
+        // retried after the next interval
+        state.last_checked = Some(now);
+        state.save().ok()?;
+
+        let (sender, receiver) = mpsc::channel();
+        let config = config.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(Self::find_updates(&config));
+        });
+
+        receiver.recv_timeout(CHECK_TIMEOUT).ok()?.ok()
+    }
+
+    /// Compare every installed repository that tracks its latest release
+    /// with that release
+    pub fn find_updates(config: &Config) -> Result<Vec<AvailableUpdate>> {
+        let mut updates = Vec::new();
+
+        for (name, installed) in Fetcher::check_template_status(config)? {
+            let Some(installed) = installed else {
+                continue;
+            };
+            let Some(repo) = Fetcher::repository_for(config, &name) else {
+                continue;
+            };
+
+            // Pinned versions, branches and commits don't have newer releases
+            if repo.version.is_some() || repo.git_ref().is_some() {
+                continue;
+            }
+
+            // A rolled-back package stays on the version the user chose
+            let package_dir = Fetcher::package_dir_for(config, &name);
+            if package_dir.is_some_and(|dir| VersionHistory::load(&dir).is_rolled_back()) {
+                continue;
+            }
+
+            let Ok(release) = providers::provider_for(&repo).latest_release(&repo.repository)
+            else {
+                continue;
+            };
+
+            if Self::is_newer(&installed, &release.tag_name) {
+                updates.push(AvailableUpdate {
+                    name,
+                    installed,
+                    latest: release.tag_name,
+                });
+            }
+        }
+
+        Ok(updates)
+    }
+
+    /// Whether `latest` is a higher semantic version than `installed`
+    ///
+    /// A leading `v` is ignored; versions that don't parse are never newer.
+    pub fn is_newer(installed: &str, latest: &str) -> bool {
+        let parse = |version: &str| Version::parse(version.trim().trim_start_matches('v')).ok();
+
+        match (parse(installed), parse(latest)) {
+            (Some(installed), Some(latest)) => latest > installed,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(UpdateChecker::is_newer("0.4.0", "v0.5.0"));
+        assert!(UpdateChecker::is_newer("v1.2.3", "1.10.0"));
+        assert!(!UpdateChecker::is_newer("1.0.0", "v1.0.0"));
+        assert!(!UpdateChecker::is_newer("1.1.0", "1.0.9"));
+        assert!(!UpdateChecker::is_newer("unknown", "1.0.0"));
+        assert!(!UpdateChecker::is_newer("dev@1a2b3c4", "1.0.0"));
+    }
+
+    #[test]
+    fn test_check_is_throttled() {
+        let now = Utc::now();
+        assert!(UpdateCheckState::default().is_due(24, now));
+
+        let state = UpdateCheckState {
+            last_checked: Some(now - Duration::hours(2)),
+        };
+        assert!(!state.is_due(24, now));
+        assert!(state.is_due(2, now));
+        assert!(state.is_due(0, now));
+    }
+}
@@ -179,14 +179,313 @@ impl TemplateValidator {
         for rule in &context.engine_config.validation.custom_rules {
             issues.extend(Self::apply_custom_validation_rule(
                 rule,
-                context,
-                template_def,
+                &context.variables,
+                None,
             )?);
         }
 
         Ok(issues)
     }
 
+    /// Validate a rendered document against the package's document-level rules
+    ///
+    /// Covers `validate_syntax`, `validate_references` and `MaxFileSize` rules.
+    /// Relative imports are only checked when `base_dir` is known.
+    pub fn validate_rendered_document(
+        content: &str,
+        engine: &EngineConfig,
+        config: Option<&Config>,
+        base_dir: Option<&Path>,
+    ) -> Result<Vec<ValidationIssue>> {
+        let mut issues = Vec::new();
+
+        if engine.validation.validate_syntax {
+            issues.extend(Self::validate_document_syntax(content));
+        }
+
+        if engine.validation.validate_references {
+            issues.extend(Self::validate_document_references(
+                content, config, base_dir,
+            ));
+        }
+
+        for rule in &engine.validation.custom_rules {
+            if matches!(rule.rule_type, ValidationRuleType::MaxFileSize) {
+                issues.extend(Self::apply_custom_validation_rule(
+                    rule,
+                    &HashMap::new(),
+                    Some(content),
+                )?);
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Check an existing note against all of the package's validation rules
+    ///
+    /// Variable rules are checked against the arguments of the document's
+    /// `#show: ....with(...)` rule.
+    pub fn lint_document(
+        content: &str,
+        engine: &EngineConfig,
+        config: Option<&Config>,
+        base_dir: Option<&Path>,
+    ) -> Result<Vec<ValidationIssue>> {
+        let variables = Self::extract_document_variables(content);
+        let mut issues = Vec::new();
+
+        if !content.contains("#show:") {
+            issues.push(ValidationIssue {
+                severity: ValidationSeverity::Warning,
+                category: "document".to_string(),
+                message: "No template show rule found".to_string(),
+                suggestion: Some(
+                    "Notes created by noter start with `#show: <template>.with(...)`".to_string(),
+                ),
+                location: None,
+            });
+        }
+
+        for rule in &engine.validation.custom_rules {
+            if !matches!(rule.rule_type, ValidationRuleType::MaxFileSize) {
+                issues.extend(Self::apply_custom_validation_rule(rule, &variables, None)?);
+            }
+        }
+
+        issues.extend(Self::validate_rendered_document(
+            content, engine, config, base_dir,
+        )?);
+
+        Ok(issues)
+    }
+
+    /// Extract `key: value` arguments from the document's show rule
+    ///
+    /// Keys are normalized to variable names (`course-name` becomes
+    /// `course_name`, `course` becomes `course_id`) and string quotes removed.
+    fn extract_document_variables(content: &str) -> HashMap<String, String> {
+        let mut variables = HashMap::new();
+        let argument = Regex::new(r#"^\s*([A-Za-z][\w-]*)\s*:\s*(.+?),?\s*$"#).unwrap();
+
+        let Some(start) = content.find("#show:") else {
+            return variables;
+        };
+
+        for line in content[start..].lines().skip(1) {
+            if line.trim_start().starts_with(')') {
+                break;
+            }
+
+            if let Some(captures) = argument.captures(line) {
+                let key = match &captures[1] {
+                    "course" => "course_id".to_string(),
+                    key => key.replace('-', "_"),
+                };
+                let value = captures[2]
+                    .trim()
+                    .trim_matches('"')
+                    .replace("\\\"", "\"")
+                    .replace("\\\\", "\\");
+                variables.insert(key, value);
+            }
+        }
+
+        variables
+    }
+
+    /// Check for unterminated strings, comments and unbalanced delimiters
+    ///
+    /// Strings only exist in code mode: in `#` expressions and `{}` blocks. In
+    /// markup, including `[]` content blocks, a `"` is just a quote.
+    fn validate_document_syntax(content: &str) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        // Open delimiters: the character, its line and whether its content is code
+        let mut stack: Vec<(char, usize, bool)> = Vec::new();
+        // The `#` expression being read: the stack depth it started at and
+        // whether it runs to the end of the line (`#let`, `#show`, ...)
+        let mut embedded: Option<(usize, bool)> = None;
+        let chars: Vec<char> = content.chars().collect();
+        let mut i = 0;
+        let mut line = 1;
+
+        let issue = |message: String, line: usize| ValidationIssue {
+            severity: ValidationSeverity::Warning,
+            category: "syntax".to_string(),
+            message,
+            suggestion: None,
+            location: Some(format!("line {}", line)),
+        };
+
+        while i < chars.len() {
+            let c = chars[i];
+            let next = chars.get(i + 1).copied();
+            i += 1;
+
+            if let Some((depth, statement)) = embedded {
+                let ended = if statement {
+                    c == '\n'
+                } else {
+                    c.is_whitespace()
+                };
+                if stack.len() < depth || (stack.len() == depth && ended) {
+                    embedded = None;
+                }
+            }
+            let in_code = match embedded {
+                Some((depth, _)) if stack.len() == depth => true,
+                _ => stack.last().is_some_and(|&(_, _, code)| code),
+            };
+
+            match c {
+                '\n' => line += 1,
+                // Escaped character, never a delimiter
+                '\\' => {
+                    line += usize::from(next == Some('\n'));
+                    i += 1;
+                }
+                '/' if next == Some('/') => {
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    }
+                }
+                '/' if next == Some('*') => {
+                    let start = line;
+                    i += 1;
+                    let mut closed = false;
+                    while i < chars.len() {
+                        i += 1;
+                        match chars[i - 1] {
+                            '\n' => line += 1,
+                            '*' if chars.get(i) == Some(&'/') => {
+                                i += 1;
+                                closed = true;
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    if !closed {
+                        issues.push(issue("Unterminated block comment".to_string(), start));
+                    }
+                }
+                '#' if !in_code
+                    && next.is_some_and(|n| n.is_alphabetic() || "_({[\"".contains(n)) =>
+                {
+                    let keyword: String = chars[i..]
+                        .iter()
+                        .take_while(|n| n.is_alphanumeric() || **n == '_')
+                        .collect();
+                    let statement = matches!(
+                        keyword.as_str(),
+                        "let" | "set" | "show" | "import" | "include" | "if" | "for" | "while"
+                    );
+                    embedded = Some((stack.len(), statement));
+                }
+                '"' if !in_code => {}
+                '"' | '`' => {
+                    let start = line;
+                    let mut closed = false;
+                    while i < chars.len() {
+                        i += 1;
+                        match chars[i - 1] {
+                            '\n' => line += 1,
+                            '\\' if c == '"' => i += 1,
+                            next if next == c => {
+                                closed = true;
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    if !closed {
+                        let kind = if c == '"' { "string" } else { "raw block" };
+                        issues.push(issue(format!("Unterminated {}", kind), start));
+                    }
+                }
+                '(' | '{' => stack.push((c, line, in_code)),
+                '[' => stack.push((c, line, false)),
+                ')' | ']' | '}' => {
+                    let expected = match c {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    match stack.pop() {
+                        Some((open, _, _)) if open == expected => {}
+                        Some((open, open_line, _)) => {
+                            issues.push(issue(
+                                format!(
+                                    "'{}' does not match '{}' opened on line {}",
+                                    c, open, open_line
+                                ),
+                                line,
+                            ));
+                        }
+                        None => issues.push(issue(format!("Unmatched '{}'", c), line)),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for (open, open_line, _) in stack {
+            issues.push(issue(format!("Unclosed '{}'", open), open_line));
+        }
+
+        issues
+    }
+
+    /// Check that imported packages and files exist
+    fn validate_document_references(
+        content: &str,
+        config: Option<&Config>,
+        base_dir: Option<&Path>,
+    ) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        let reference = Regex::new(r#"#(?:import|include)\s+"([^"]+)""#).unwrap();
+
+        for (index, line) in content.lines().enumerate() {
+            for captures in reference.captures_iter(line) {
+                let target = &captures[1];
+
+                let missing = if let Some(package) = target.strip_prefix("@local/") {
+                    let Some(config) = config else { continue };
+                    let (name, version) = package.split_once(':').unwrap_or((package, ""));
+                    !Path::new(&config.paths.typst_packages_dir)
+                        .join(name)
+                        .join(version)
+                        .exists()
+                } else if target.starts_with('@') {
+                    // Packages from the Typst registry are resolved by the compiler
+                    continue;
+                } else {
+                    let Some(base_dir) = base_dir else { continue };
+                    !base_dir.join(target).exists()
+                };
+
+                if missing {
+                    issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Error,
+                        category: "references".to_string(),
+                        message: format!("Referenced '{}' not found", target),
+                        suggestion: if target.starts_with("@local/") {
+                            Some(
+                                "Install it with `noter template update` or fix the import version"
+                                    .to_string(),
+                            )
+                        } else {
+                            None
+                        },
+                        location: Some(format!("line {}", index + 1)),
+                    });
+                }
+            }
+        }
+
+        issues
+    }
+
     #[allow(dead_code)]
     /// Validate available template accessibility
     pub fn validate_available_template(template: &AvailableTemplate) -> Vec<ValidationIssue> {
@@ -323,6 +622,16 @@ impl TemplateValidator {
             });
         }
 
+        if let Err(e) = template.output_directory() {
+            issues.push(ValidationIssue {
+                severity: ValidationSeverity::Error,
+                category: "template".to_string(),
+                message: e.to_string(),
+                suggestion: Some("Use a directory name like \"labs\"".to_string()),
+                location: Some("output_dir".to_string()),
+            });
+        }
+
         // Validate course types format
         if let Some(course_types) = &template.course_types {
             for course_type in course_types {
@@ -531,26 +840,25 @@ impl TemplateValidator {
 
         if let Ok(configs) = TemplateDiscovery::load_template_configs(config) {
             for template_config in &configs {
-                if let Some(engine) = &template_config.engine {
-                    if let (Ok(current), Ok(required)) = (
+                if let Some(engine) = &template_config.engine
+                    && let (Ok(current), Ok(required)) = (
                         semver::Version::parse(current_version),
                         semver::Version::parse(&engine.compatibility.minimum_noter_version),
-                    ) {
-                        if current < required {
-                            issues.push(ValidationIssue {
-                                severity: ValidationSeverity::Error,
-                                category: "compatibility".to_string(),
-                                message: format!(
-                                    "Template '{}' requires noter version {} but current is {}",
-                                    template_config.metadata.name,
-                                    engine.compatibility.minimum_noter_version,
-                                    current_version
-                                ),
-                                suggestion: Some("Update noter to the latest version".to_string()),
-                                location: None,
-                            });
-                        }
-                    }
+                    )
+                    && current < required
+                {
+                    issues.push(ValidationIssue {
+                        severity: ValidationSeverity::Error,
+                        category: "compatibility".to_string(),
+                        message: format!(
+                            "Template '{}' requires noter version {} but current is {}",
+                            template_config.metadata.name,
+                            engine.compatibility.minimum_noter_version,
+                            current_version
+                        ),
+                        suggestion: Some("Update noter to the latest version".to_string()),
+                        location: None,
+                    });
                 }
             }
         }
@@ -602,20 +910,17 @@ impl TemplateValidator {
         }
 
         // Validate variant-specific sections
-        if let Some(variant) = variant {
-            if let Some(override_sections) = &variant.override_sections {
-                if override_sections.is_empty() {
-                    issues.push(ValidationIssue {
-                        severity: ValidationSeverity::Warning,
-                        category: "sections".to_string(),
-                        message: format!("Variant '{}' has empty override_sections", variant.name),
-                        suggestion: Some(
-                            "Remove override_sections or provide sections".to_string(),
-                        ),
-                        location: Some("variant.override_sections".to_string()),
-                    });
-                }
-            }
+        if let Some(variant) = variant
+            && let Some(override_sections) = &variant.override_sections
+            && override_sections.is_empty()
+        {
+            issues.push(ValidationIssue {
+                severity: ValidationSeverity::Warning,
+                category: "sections".to_string(),
+                message: format!("Variant '{}' has empty override_sections", variant.name),
+                suggestion: Some("Remove override_sections or provide sections".to_string()),
+                location: Some("variant.override_sections".to_string()),
+            });
         }
 
         issues
@@ -623,8 +928,8 @@ impl TemplateValidator {
 
     fn apply_custom_validation_rule(
         rule: &ValidationRule,
-        context: &TemplateContext,
-        _template_def: &TemplateDefinition,
+        variables: &HashMap<String, String>,
+        document: Option<&str>,
     ) -> Result<Vec<ValidationIssue>> {
         let mut issues = Vec::new();
 
@@ -633,7 +938,10 @@ impl TemplateValidator {
                 if let Some(required_vars) = rule.parameters.get("variables") {
                     let vars: Vec<&str> = required_vars.split(',').collect();
                     for var in vars {
-                        if !context.variables.contains_key(var.trim()) {
+                        let present = variables
+                            .get(var.trim())
+                            .is_some_and(|value| !value.trim().is_empty());
+                        if !present {
                             issues.push(ValidationIssue {
                                 severity: ValidationSeverity::Error,
                                 category: "custom_rule".to_string(),
@@ -649,38 +957,91 @@ impl TemplateValidator {
                 if let (Some(variable), Some(pattern)) = (
                     rule.parameters.get("variable"),
                     rule.parameters.get("pattern"),
-                ) {
-                    if let Some(value) = context.variables.get(variable) {
-                        if let Ok(regex) = Regex::new(pattern) {
-                            if !regex.is_match(value) {
-                                issues.push(ValidationIssue {
-                                    severity: ValidationSeverity::Warning,
-                                    category: "custom_rule".to_string(),
-                                    message: rule.error_message.clone(),
-                                    suggestion: Some(format!(
-                                        "Variable '{}' should match pattern '{}'",
-                                        variable, pattern
-                                    )),
-                                    location: Some(format!("variables.{}", variable)),
-                                });
-                            }
-                        }
+                ) && let Some(value) = variables.get(variable)
+                {
+                    let regex = Regex::new(pattern).map_err(|e| {
+                        anyhow::anyhow!("Invalid pattern in validation rule '{}': {}", rule.name, e)
+                    })?;
+                    if !regex.is_match(value) {
+                        issues.push(ValidationIssue {
+                            severity: ValidationSeverity::Warning,
+                            category: "custom_rule".to_string(),
+                            message: rule.error_message.clone(),
+                            suggestion: Some(format!(
+                                "Variable '{}' should match pattern '{}'",
+                                variable, pattern
+                            )),
+                            location: Some(format!("variables.{}", variable)),
+                        });
                     }
                 }
             }
             ValidationRuleType::MaxFileSize => {
-                // File size validation would be implemented here
-                // Currently not applicable for template context validation
+                if let Some(document) = document {
+                    let max_bytes = Self::max_file_size(rule)?;
+                    if document.len() as u64 > max_bytes {
+                        issues.push(ValidationIssue {
+                            severity: ValidationSeverity::Error,
+                            category: "custom_rule".to_string(),
+                            message: rule.error_message.clone(),
+                            suggestion: Some(format!(
+                                "Document is {} bytes, the limit is {} bytes",
+                                document.len(),
+                                max_bytes
+                            )),
+                            location: None,
+                        });
+                    }
+                }
             }
-            ValidationRuleType::Custom(_) => {
-                // Custom validation logic would be implemented here
-                // This could involve calling external validators or scripts
+            ValidationRuleType::Custom(name) => {
+                issues.push(ValidationIssue {
+                    severity: ValidationSeverity::Info,
+                    category: "custom_rule".to_string(),
+                    message: format!(
+                        "Custom validation rule '{}' ({}) is not supported and was skipped",
+                        rule.name, name
+                    ),
+                    suggestion: None,
+                    location: None,
+                });
             }
         }
 
         Ok(issues)
     }
 
+    /// Size limit of a `MaxFileSize` rule, from `max_bytes` or `max_kb`
+    fn max_file_size(rule: &ValidationRule) -> Result<u64> {
+        let parse = |key: &str| -> Result<Option<u64>> {
+            rule.parameters
+                .get(key)
+                .map(|value| {
+                    value.trim().parse::<u64>().map_err(|_| {
+                        anyhow::anyhow!(
+                            "Validation rule '{}': '{}' must be a whole number, got '{}'",
+                            rule.name,
+                            key,
+                            value
+                        )
+                    })
+                })
+                .transpose()
+        };
+
+        if let Some(bytes) = parse("max_bytes")? {
+            return Ok(bytes);
+        }
+        if let Some(kb) = parse("max_kb")? {
+            return Ok(kb * 1024);
+        }
+
+        Err(anyhow::anyhow!(
+            "Validation rule '{}' needs a 'max_bytes' or 'max_kb' parameter",
+            rule.name
+        ))
+    }
+
     /// Format validation issues for display
     pub fn format_validation_report(issues: &[ValidationIssue]) -> String {
         if issues.is_empty() {
@@ -775,4 +1136,93 @@ mod tests {
         assert!(report.contains("❌"));
         assert!(report.contains("⚠️"));
     }
+
+    fn rule(rule_type: ValidationRuleType, parameters: &[(&str, &str)]) -> ValidationRule {
+        ValidationRule {
+            name: "rule".to_string(),
+            rule_type,
+            parameters: parameters
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            error_message: "Rule failed".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_document_syntax_validation() {
+        let mut engine = EngineConfig::default();
+        engine.validation.validate_syntax = true;
+
+        let clean = "#show: note.with(title: \"a (b\")\n= Intro // (comment\n";
+        let issues =
+            TemplateValidator::validate_rendered_document(clean, &engine, None, None).unwrap();
+        assert!(issues.is_empty());
+
+        let broken = "#show: note.with(\n  title: \"a\",\n";
+        let issues =
+            TemplateValidator::validate_rendered_document(broken, &engine, None, None).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].location.as_deref(), Some("line 1"));
+
+        // Quotes in markup aren't strings, only those in code are
+        let markup = "He said \"hi\n#emph[a \"quote] and #strong(\"b\")\n#let x = {\n  \"c\"\n}\n";
+        let issues =
+            TemplateValidator::validate_rendered_document(markup, &engine, None, None).unwrap();
+        assert!(issues.is_empty());
+
+        let unterminated = "Text \"quoted\n#let title = \"a\n";
+        let issues =
+            TemplateValidator::validate_rendered_document(unterminated, &engine, None, None)
+                .unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].message, "Unterminated string");
+        assert_eq!(issues[0].location.as_deref(), Some("line 2"));
+    }
+
+    #[test]
+    fn test_max_file_size_rule() {
+        let mut engine = EngineConfig::default();
+        engine.validation.custom_rules.push(rule(
+            ValidationRuleType::MaxFileSize,
+            &[("max_bytes", "10")],
+        ));
+
+        let issues =
+            TemplateValidator::validate_rendered_document("short", &engine, None, None).unwrap();
+        assert!(issues.is_empty());
+
+        let issues =
+            TemplateValidator::validate_rendered_document("far too long", &engine, None, None)
+                .unwrap();
+        assert_eq!(issues[0].severity, ValidationSeverity::Error);
+    }
+
+    #[test]
+    fn test_lint_document_uses_show_rule_arguments() {
+        let mut engine = EngineConfig::default();
+        engine.validation.custom_rules.push(rule(
+            ValidationRuleType::RequiredVariables,
+            &[("variables", "author,course_name")],
+        ));
+        engine.validation.custom_rules.push(rule(
+            ValidationRuleType::VariablePattern,
+            &[("variable", "course_id"), ("pattern", "^[0-9]{5}$")],
+        ));
+
+        let document = "#show: note.with(\n  course: \"021\",\n  course-name: \"Programming\",\n  author: \"\",\n)\n";
+        let issues = TemplateValidator::lint_document(document, &engine, None, None).unwrap();
+
+        assert_eq!(issues.len(), 2);
+        assert!(
+            issues
+                .iter()
+                .any(|i| i.location.as_deref() == Some("variables.author"))
+        );
+        assert!(
+            issues
+                .iter()
+                .any(|i| i.location.as_deref() == Some("variables.course_id"))
+        );
+    }
 }
@@ -0,0 +1,411 @@
+//! Installed template package versions
+//!
+//! Every repository install records the package versions it added in
+//! `.noter-versions.json` inside the package directory. Only the newest
+//! `templates.keep_versions` are kept on disk, plus older ones that notes
+//! still import. `noter template rollback` marks an older version as active,
+//! and new notes then use that version.
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::InstallSource;
+use crate::core::files::FileOperations;
+
+const HISTORY_FILE_NAME: &str = ".noter-versions.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct InstalledVersion {
+    pub version: String,
+    pub installed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VersionHistory {
+    /// Version used for new notes (None means the newest installed version)
+    pub active: Option<String>,
+    /// Installed versions, oldest install first
+    pub versions: Vec<InstalledVersion>,
+    /// Repository the package was installed from, recorded by the installer.
+    /// None for local installs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<InstallSource>,
+}
+
+impl VersionHistory {
+    pub fn load(package_dir: &Path) -> Self {
+        fs::read_to_string(package_dir.join(HISTORY_FILE_NAME))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, package_dir: &Path) -> Result<()> {
+        let path = package_dir.join(HISTORY_FILE_NAME);
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Forget the repository a package was installed from
+    ///
+    /// Called when a local install writes into the package directory, so it
+    /// doesn't inherit the repository's trust.
+    pub fn forget_source(package_dir: &Path) -> Result<()> {
+        let mut history = Self::load(package_dir);
+        if history.source.take().is_some() {
+            history.save(package_dir)?;
+        }
+        Ok(())
+    }
+
+    /// Whether a package directory tracks its installed versions
+    pub fn is_tracked(package_dir: &Path) -> bool {
+        package_dir.join(HISTORY_FILE_NAME).exists()
+    }
+
+    /// Record versions added by an install and make the newest of them active
+    pub fn record_install(&mut self, versions: &[String]) {
+        let mut versions = versions.to_vec();
+        versions.sort_by(|a, b| compare_versions(a, b));
+
+        let now = Utc::now();
+        for version in versions {
+            self.versions
+                .retain(|installed| installed.version != version);
+            self.versions.push(InstalledVersion {
+                version: version.clone(),
+                installed_at: now,
+            });
+            self.active = Some(version);
+        }
+    }
+
+    /// Delete the oldest installed versions beyond `keep`
+    ///
+    /// The active version and the versions in `in_use` (imported by notes) are
+    /// never deleted. Returns the deleted versions.
+    pub fn prune(
+        &mut self,
+        package_dir: &Path,
+        keep: usize,
+        in_use: &[String],
+    ) -> Result<Vec<String>> {
+        let keep = keep.max(1);
+        let mut removed = Vec::new();
+
+        while self.versions.len() > keep {
+            let Some(index) = self.versions.iter().position(|installed| {
+                Some(&installed.version) != self.active.as_ref()
+                    && !in_use.contains(&installed.version)
+            }) else {
+                break;
+            };
+
+            let version = self.versions.remove(index).version;
+            let version_dir = package_dir.join(&version);
+            if version_dir.exists() {
+                fs::remove_dir_all(&version_dir)
+                    .with_context(|| format!("Failed to delete {}", version_dir.display()))?;
+            }
+            removed.push(version);
+        }
+
+        Ok(removed)
+    }
+
+    /// The version in use: the active one, or the most recently installed
+    pub fn current(&self) -> Option<&str> {
+        self.active.as_deref().or_else(|| {
+            self.versions
+                .last()
+                .map(|installed| installed.version.as_str())
+        })
+    }
+
+    /// Whether an older version than the newest installed one is active, i.e.
+    /// the package was rolled back
+    pub fn is_rolled_back(&self) -> bool {
+        let newest = self
+            .versions
+            .iter()
+            .map(|installed| installed.version.as_str())
+            .max_by(|a, b| compare_versions(a, b));
+
+        match (self.active.as_deref(), newest) {
+            (Some(active), Some(newest)) => active != newest,
+            _ => false,
+        }
+    }
+
+    /// The version installed before the current one
+    pub fn previous(&self) -> Option<&str> {
+        let current = self.current()?;
+        let index = self
+            .versions
+            .iter()
+            .position(|installed| installed.version == current)?;
+
+        index
+            .checked_sub(1)
+            .map(|previous| self.versions[previous].version.as_str())
+    }
+
+    /// Make an installed version the one new notes use
+    pub fn activate(&mut self, package_dir: &Path, version: &str) -> Result<()> {
+        let tracked = self
+            .versions
+            .iter()
+            .any(|installed| installed.version == version);
+
+        if !tracked || !package_dir.join(version).is_dir() {
+            let available: Vec<&str> = self
+                .versions
+                .iter()
+                .map(|installed| installed.version.as_str())
+                .collect();
+            return Err(anyhow!(
+                "Version {} is not installed (available: {})",
+                version,
+                if available.is_empty() {
+                    "none".to_string()
+                } else {
+                    available.join(", ")
+                }
+            ));
+        }
+
+        self.active = Some(version.to_string());
+        Ok(())
+    }
+}
+
+/// A note whose `@local` package imports point at other versions
+#[derive(Debug)]
+pub struct ImportRewrite {
+    pub path: PathBuf,
+    pub changes: Vec<ImportChange>,
+    /// Note content before the rewrite
+    pub original: String,
+    pub content: String,
+}
+
+/// Versions of one package a note imported before a rewrite
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportChange {
+    pub package: String,
+    pub from: Vec<String>,
+    pub to: String,
+}
+
+/// Rewrites `#import "@local/<package>:<version>"` lines to one version
+pub struct ImportRewriter {
+    package: String,
+    pattern: Regex,
+    version: String,
+}
+
+impl ImportRewriter {
+    pub fn new(package: &str, version: &str) -> Result<Self> {
+        let pattern = Regex::new(&format!(
+            r#"(#import\s+"@local/{}:)([^"]+)(")"#,
+            regex::escape(package)
+        ))?;
+
+        Ok(Self {
+            package: package.to_string(),
+            pattern,
+            version: version.to_string(),
+        })
+    }
+
+    /// Rewrite one document, returning None if it needs no changes
+    pub fn rewrite(&self, content: &str) -> Option<(String, ImportChange)> {
+        let mut from = Vec::new();
+
+        for captures in self.pattern.captures_iter(content) {
+            let version = &captures[2];
+            if version != self.version && !from.iter().any(|v| v == version) {
+                from.push(version.to_string());
+            }
+        }
+
+        if from.is_empty() {
+            return None;
+        }
+
+        let replacement = format!("${{1}}{}${{3}}", self.version);
+        let rewritten = self.pattern.replace_all(content, replacement.as_str());
+        let change = ImportChange {
+            package: self.package.clone(),
+            from,
+            to: self.version.clone(),
+        };
+        Some((rewritten.into_owned(), change))
+    }
+
+    /// Versions of a package imported by the notes under a directory
+    pub fn imported_versions(package: &str, notes_dir: &Path) -> Result<Vec<String>> {
+        let pattern = Self::new(package, "")?.pattern;
+        let mut versions = Vec::new();
+
+        for path in FileOperations::list_files_recursive(notes_dir, &["typ"])? {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            for captures in pattern.captures_iter(&content) {
+                if !versions.iter().any(|v| v == &captures[2]) {
+                    versions.push(captures[2].to_string());
+                }
+            }
+        }
+
+        Ok(versions)
+    }
+
+    /// Notes under a directory that import another version of any of the packages
+    pub fn plan(rewriters: &[ImportRewriter], notes_dir: &Path) -> Result<Vec<ImportRewrite>> {
+        let mut rewrites = Vec::new();
+
+        for path in FileOperations::list_files_recursive(notes_dir, &["typ"])? {
+            let original = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+
+            let mut content = original.clone();
+            let mut changes = Vec::new();
+            for rewriter in rewriters {
+                if let Some((rewritten, change)) = rewriter.rewrite(&content) {
+                    content = rewritten;
+                    changes.push(change);
+                }
+            }
+
+            if !changes.is_empty() {
+                rewrites.push(ImportRewrite {
+                    path,
+                    changes,
+                    original,
+                    content,
+                });
+            }
+        }
+
+        Ok(rewrites)
+    }
+}
+
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |version: &str| Version::parse(version.trim_start_matches('v')).ok();
+
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn install(dir: &Path, history: &mut VersionHistory, versions: &[&str]) {
+        for version in versions {
+            fs::create_dir_all(dir.join(version)).unwrap();
+        }
+        let versions: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        history.record_install(&versions);
+    }
+
+    #[test]
+    fn test_keeps_newest_versions_and_rolls_back() {
+        let dir = TempDir::new().unwrap();
+        let mut history = VersionHistory::default();
+
+        install(dir.path(), &mut history, &["0.10.0", "0.9.0"]);
+        install(dir.path(), &mut history, &["0.11.0"]);
+        assert_eq!(history.current(), Some("0.11.0"));
+        assert_eq!(history.previous(), Some("0.10.0"));
+
+        assert!(!history.is_rolled_back());
+        history.activate(dir.path(), "0.9.0").unwrap();
+        assert!(history.is_rolled_back());
+        install(dir.path(), &mut history, &["0.12.0"]);
+        assert!(!history.is_rolled_back());
+
+        // The newest install becomes active again and the oldest is deleted
+        let removed = history.prune(dir.path(), 3, &[]).unwrap();
+        assert_eq!(removed, vec!["0.9.0".to_string()]);
+        assert!(!dir.path().join("0.9.0").exists());
+        assert_eq!(history.current(), Some("0.12.0"));
+
+        assert!(history.activate(dir.path(), "0.9.0").is_err());
+        history.activate(dir.path(), "0.10.0").unwrap();
+        assert!(history.prune(dir.path(), 1, &[]).unwrap().len() == 2);
+        assert!(dir.path().join("0.10.0").exists());
+    }
+
+    #[test]
+    fn test_prune_keeps_imported_versions() {
+        let dir = TempDir::new().unwrap();
+        let packages = dir.path().join("dtu-template");
+        let notes = dir.path().join("notes");
+        fs::create_dir_all(&notes).unwrap();
+        fs::write(
+            notes.join("week01.typ"),
+            "#import \"@local/dtu-template:0.1.0\": *\n",
+        )
+        .unwrap();
+
+        let mut history = VersionHistory::default();
+        install(&packages, &mut history, &["0.1.0", "0.2.0", "0.3.0"]);
+
+        let in_use = ImportRewriter::imported_versions("dtu-template", &notes).unwrap();
+        assert_eq!(in_use, vec!["0.1.0".to_string()]);
+
+        let removed = history.prune(&packages, 1, &in_use).unwrap();
+        assert_eq!(removed, vec!["0.2.0".to_string()]);
+        assert!(packages.join("0.1.0").exists());
+        assert_eq!(history.current(), Some("0.3.0"));
+    }
+
+    #[test]
+    fn test_rewrite_imports() {
+        let rewriter = ImportRewriter::new("dtu-template", "0.4.0").unwrap();
+        let note = "#import \"@local/dtu-template:0.5.0\": *\n#import \"@local/other:0.5.0\": *\n";
+
+        let (content, change) = rewriter.rewrite(note).unwrap();
+        assert_eq!(change.from, vec!["0.5.0".to_string()]);
+        assert!(content.contains("@local/dtu-template:0.4.0"));
+        assert!(content.contains("@local/other:0.5.0"));
+        assert!(rewriter.rewrite(&content).is_none());
+    }
+
+    #[test]
+    fn test_plan_rewrites_across_notes() {
+        let dir = TempDir::new().unwrap();
+        let lectures = dir.path().join("02101").join("lectures");
+        fs::create_dir_all(&lectures).unwrap();
+        fs::write(
+            lectures.join("a.typ"),
+            "#import \"@local/notes:0.1.0\":*\n#import \"@local/extra:1.0.0\":*\n",
+        )
+        .unwrap();
+        fs::write(lectures.join("b.typ"), "#import \"@local/notes:0.2.0\":*\n").unwrap();
+        fs::write(lectures.join("c.md"), "#import \"@local/notes:0.1.0\":*\n").unwrap();
+
+        let rewriters = [
+            ImportRewriter::new("notes", "0.2.0").unwrap(),
+            ImportRewriter::new("extra", "1.1.0").unwrap(),
+        ];
+        let rewrites = ImportRewriter::plan(&rewriters, dir.path()).unwrap();
+
+        assert_eq!(rewrites.len(), 1);
+        assert!(rewrites[0].path.ends_with("a.typ"));
+        assert_eq!(rewrites[0].changes.len(), 2);
+        assert!(rewrites[0].content.contains("@local/notes:0.2.0"));
+        assert!(rewrites[0].content.contains("@local/extra:1.1.0"));
+    }
+}
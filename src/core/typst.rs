@@ -18,10 +18,10 @@ impl TypstCompiler {
         let output_path = Self::determine_output_path(&input_path, config)?;
 
         // Clean before compiling if configured
-        if config.typst.clean_before_compile {
-            if let Some(parent) = output_path.parent() {
-                Self::clean_directory(parent)?;
-            }
+        if config.typst.clean_before_compile
+            && let Some(parent) = output_path.parent()
+        {
+            Self::clean_directory(parent)?;
         }
 
         // Convert paths to strings once to avoid temporary value issues
@@ -157,20 +157,20 @@ impl TypstCompiler {
         let mut output_path = input_path.with_extension("pdf");
 
         // Use custom output directory if configured
-        if let Some(ref output_dir) = config.typst.output_dir {
-            if let Some(filename) = output_path.file_name() {
-                let custom_dir = if Path::new(output_dir).is_absolute() {
-                    PathBuf::from(output_dir)
-                } else {
-                    input_path
-                        .parent()
-                        .unwrap_or_else(|| Path::new("."))
-                        .join(output_dir)
-                };
-
-                fs::create_dir_all(&custom_dir)?;
-                output_path = custom_dir.join(filename);
-            }
+        if let Some(ref output_dir) = config.typst.output_dir
+            && let Some(filename) = output_path.file_name()
+        {
+            let custom_dir = if Path::new(output_dir).is_absolute() {
+                PathBuf::from(output_dir)
+            } else {
+                input_path
+                    .parent()
+                    .unwrap_or_else(|| Path::new("."))
+                    .join(output_dir)
+            };
+
+            fs::create_dir_all(&custom_dir)?;
+            output_path = custom_dir.join(filename);
         }
 
         Ok(output_path)
@@ -20,6 +20,20 @@ impl Validator {
         Ok(())
     }
 
+    /// Check a DTU study number: "s" followed by six digits (e.g. s234567)
+    pub fn validate_student_id(student_id: &str) -> Result<()> {
+        let digits = student_id.strip_prefix('s').unwrap_or_default();
+
+        if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_digit()) {
+            anyhow::bail!(
+                "Invalid study number '{}': expected 's' followed by 6 digits (e.g., s234567)",
+                student_id
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn sanitize_filename(input: &str) -> String {
         input
             .chars()
@@ -43,6 +57,20 @@ impl Validator {
             .to_string()
     }
 
+    /// Parse a `KEY=VALUE` template variable assignment
+    pub fn parse_variable_assignment(input: &str) -> Result<(String, String)> {
+        let (key, value) = input
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid variable '{}': expected KEY=VALUE", input))?;
+
+        let key = key.trim();
+        if key.is_empty() {
+            anyhow::bail!("Invalid variable '{}': key cannot be empty", input);
+        }
+
+        Ok((key.to_string(), value.to_string()))
+    }
+
     pub fn validate_file_path(path: &str) -> Result<()> {
         if path.is_empty() {
             anyhow::bail!("File path cannot be empty");
@@ -52,3 +80,17 @@ impl Validator {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_student_id() {
+        assert!(Validator::validate_student_id("s234567").is_ok());
+        assert!(Validator::validate_student_id("234567").is_err());
+        assert!(Validator::validate_student_id("s23456").is_err());
+        assert!(Validator::validate_student_id("s23456a").is_err());
+        assert!(Validator::validate_student_id("S234567").is_err());
+    }
+}
@@ -89,7 +89,14 @@ impl DevDataGenerator {
         }
 
         // Save updated config
-        config.save()?;
+        Config::update(|saved| {
+            saved.courses.extend(
+                courses
+                    .iter()
+                    .map(|course| (course.code.clone(), course.name.clone())),
+            );
+            Ok(())
+        })?;
 
         for course in &courses {
             let course_dir = notes_dir.join(&course.code);
@@ -174,7 +181,14 @@ impl DevDataGenerator {
         }
 
         // Save updated config
-        config.save()?;
+        Config::update(|saved| {
+            saved.courses.extend(
+                courses
+                    .iter()
+                    .map(|course| (course.code.clone(), course.name.clone())),
+            );
+            Ok(())
+        })?;
 
         for course in &courses {
             let course_dir = notes_dir.join(&course.code);
@@ -253,7 +267,12 @@ impl DevDataGenerator {
 
         // Save updated config if courses were removed
         if courses_removed_from_config > 0 {
-            config.save()?;
+            Config::update(|saved| {
+                saved
+                    .courses
+                    .retain(|code, _| !all_courses_to_remove.contains(code));
+                Ok(())
+            })?;
             OutputManager::print_status(
                 Status::Info,
                 &format!(
@@ -500,7 +519,12 @@ impl DevDataGenerator {
             name: name.to_string(),
             description: description.to_string(),
             credits: [5.0, 7.5, 10.0][index % 3],
-            semester: if index % 2 == 0 { "Fall" } else { "Spring" }.to_string(),
+            semester: if index.is_multiple_of(2) {
+                "Fall"
+            } else {
+                "Spring"
+            }
+            .to_string(),
         }
     }
 }
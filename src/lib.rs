@@ -18,14 +18,17 @@
 //! ### Basic Template Generation
 //!
 //! ```no_run
-//! use noter::core::template::engine::{TemplateEngine, TemplateReference};
+//! use noter::core::template::builder::TemplateBuilder;
+//! use noter::core::template::engine::TemplateReference;
 //! use noter::config::Config;
 //!
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
 //! let config = Config::default();
 //!
 //! // Generate a lecture template
-//! let lecture = TemplateEngine::generate_lecture_template("02101", &config, None)?;
+//! let lecture = TemplateBuilder::new("02101", &config)?
+//!     .with_reference(TemplateReference::lecture())
+//!     .build()?;
 //! # Ok(())
 //! # }
 //! ```
@@ -81,8 +84,11 @@
 //! ## Thread Safety
 //!
 //! Most operations are designed to be thread-safe, though file operations should
-//! be coordinated to avoid conflicts. The configuration system uses atomic writes
-//! to prevent corruption during concurrent access.
+//! be coordinated to avoid conflicts. The config file is written atomically
+//! (to a temporary file that replaces it), and changes go through
+//! [`config::Config::update`], which holds an advisory lock on the config from
+//! loading until saving, so concurrent `noter` processes don't overwrite each
+//! other's changes.
 
 pub mod commands;
 pub mod config;
@@ -117,6 +123,14 @@ pub enum Commands {
         /// Skip auto opening for file
         #[arg(long)]
         no_open: bool,
+
+        /// Template variable (repeatable, e.g. --var week=3)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+
+        /// Template package to use instead of the preferred one
+        #[arg(long)]
+        package: Option<String>,
     },
     /// Create a new assignment
     #[command(alias = "a")]
@@ -125,6 +139,49 @@ pub enum Commands {
         course_id: String,
         /// Assignment title
         title: String,
+
+        /// Group from the config whose members are the authors
+        #[arg(short, long)]
+        group: Option<String>,
+
+        /// Template variable (repeatable, e.g. --var due=2025-03-01)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+
+        /// Template package to use instead of the preferred one
+        #[arg(long)]
+        package: Option<String>,
+    },
+    /// Create a document from any template of the installed packages
+    New {
+        /// Template name (e.g. lab-report, thesis)
+        template: String,
+
+        /// Course code (e.g., 02101)
+        course_id: String,
+
+        /// Custom title for the document (optional)
+        title: Option<String>,
+
+        /// Template variant to use
+        #[arg(short, long)]
+        variant: Option<String>,
+
+        /// Custom sections (comma-separated)
+        #[arg(short, long)]
+        sections: Option<String>,
+
+        /// Skip auto opening for file
+        #[arg(long)]
+        no_open: bool,
+
+        /// Template variable (repeatable, e.g. --var group=4)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+
+        /// Template package to use instead of the preferred one
+        #[arg(long)]
+        package: Option<String>,
     },
     /// Compile a Typst file to PDF
     #[command(alias = "c")]
@@ -272,6 +329,11 @@ pub enum CourseAction {
         /// Course code to remove
         course_id: String,
     },
+    /// Show the effective template settings for a course
+    Show {
+        /// Course code (e.g., 02101)
+        course_id: String,
+    },
     /// Show common DTU course codes
     #[command(alias = "common")]
     Browse,
@@ -280,7 +342,11 @@ pub enum CourseAction {
 #[derive(Subcommand)]
 pub enum ConfigAction {
     /// Show current configuration
-    Show,
+    Show {
+        /// Show which layer (default, global, workspace) each value comes from
+        #[arg(long)]
+        origin: bool,
+    },
     /// Get a specific configuration value using dot notation (e.g., "author" or "paths.notes_dir")
     Get {
         /// Configuration key path (e.g., "author", "paths.notes_dir", "templates.auto_update")
@@ -297,6 +363,12 @@ pub enum ConfigAction {
     Edit,
     /// List all available configuration keys
     ListKeys,
+    /// Print a JSON Schema of the config file for editor completion
+    Schema {
+        /// Write the schema to a file instead of printing it
+        #[arg(short, long)]
+        output: Option<String>,
+    },
     /// Interactive configuration wizard
     Interactive,
     /// Set author name
@@ -313,14 +385,26 @@ pub enum ConfigAction {
     AddTemplateRepo {
         /// Repository name
         name: String,
-        /// GitHub repository (owner/repo)
+        /// Repository (owner/repo), or an archive URL for the url provider
         repository: String,
         /// Specific version (optional)
         #[arg(long)]
         version: Option<String>,
+        /// Track a branch instead of releases
+        #[arg(long, conflicts_with_all = ["version", "rev"])]
+        branch: Option<String>,
+        /// Install a specific commit instead of releases
+        #[arg(long, conflicts_with = "version")]
+        rev: Option<String>,
         /// Template subdirectory path (optional)
         #[arg(long)]
         template_path: Option<String>,
+        /// Hosting provider: github, gitlab, gitea or url
+        #[arg(long, default_value = "github")]
+        provider: String,
+        /// Web address of a self-hosted instance, e.g. https://gitlab.example.com (optional)
+        #[arg(long)]
+        base_url: Option<String>,
     },
     /// Remove a template repository
     RemoveTemplateRepo {
@@ -332,14 +416,30 @@ pub enum ConfigAction {
         /// Repository name
         name: String,
         /// Whether to enable (true) or disable (false)
+        #[arg(action = clap::ArgAction::Set)]
         enabled: bool,
     },
+    /// Allow/deny external processing hooks for a template repository
+    AllowTemplateHooks {
+        /// Repository name
+        name: String,
+        /// Whether packages from this repository may run hook commands
+        #[arg(action = clap::ArgAction::Set)]
+        allowed: bool,
+    },
     /// List all template repositories
     ListTemplateRepos,
     /// Enable/disable template auto-update
     SetTemplateAutoUpdate {
         /// Enable auto-update
+        #[arg(action = clap::ArgAction::Set)]
         enabled: bool,
+        /// What to do when an update is found (notify or install)
+        #[arg(long)]
+        policy: Option<String>,
+        /// Minimum hours between update checks
+        #[arg(long)]
+        interval_hours: Option<u64>,
     },
     /// Reset configuration to defaults
     Reset,
@@ -354,16 +454,101 @@ pub enum ConfigAction {
     Path,
     /// Validate current configuration
     Check,
+    /// Manage profiles (e.g. per study program or machine) applied with --profile
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
     /// Migrate configuration to latest format (usually happens automatically)
-    Migrate,
+    Migrate {
+        /// Show the changes without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Export courses, template repositories, preferences and groups to share with others
+    Export {
+        /// Write the bundle to a file (JSON or TOML) instead of printing it
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Leave out all paths (notes directory, vault, templates, ...)
+        #[arg(long)]
+        redact_paths: bool,
+    },
+    /// Import a bundle written by 'noter config export'
+    Import {
+        /// Bundle file (JSON or TOML)
+        file: String,
+        /// Add the bundle's courses, repositories and settings to yours (default)
+        #[arg(long, conflicts_with = "replace")]
+        merge: bool,
+        /// Replace your courses, repositories and settings with the bundle's
+        #[arg(long)]
+        replace: bool,
+        /// Apply the changes without asking
+        #[arg(long, short)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ProfileAction {
+    /// Create a profile
+    Create {
+        /// Profile name (e.g. "bsc" or "lab-machine")
+        name: String,
+        /// Copy the settings of an existing profile
+        #[arg(long)]
+        from: Option<String>,
+    },
+    /// Use a profile by default; without a name, use the base config only
+    Switch {
+        /// Profile name
+        name: Option<String>,
+    },
+    /// List profiles and what they override
+    List,
+    /// Show how a profile differs from the base config or another profile
+    Diff {
+        /// Profile name
+        profile: String,
+        /// Profile to compare with (defaults to the base config)
+        other: Option<String>,
+    },
+    /// Delete a profile
+    Delete {
+        /// Profile name
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
 pub enum TemplateAction {
     /// Check template status and version
     Status,
-    /// Update to the latest template version
+    /// List the templates of the installed packages
+    List {
+        /// Only show templates and variants for this course type (e.g. math)
+        #[arg(long)]
+        course_type: Option<String>,
+        /// Only show templates from this package
+        #[arg(long)]
+        package: Option<String>,
+    },
+    /// Show the details of a template
+    Show {
+        /// Template name (e.g. note, assignment)
+        template: String,
+        /// Course code to show the auto-selected variant for
+        #[arg(long)]
+        course: Option<String>,
+        /// Template package to use
+        #[arg(long)]
+        package: Option<String>,
+    },
+    /// Update to the latest template version and record it in noter.lock
     Update,
+    /// Install exactly the template versions pinned in noter.lock
+    Sync,
     /// Force reinstall templates
     Reinstall,
     /// Create a custom template file
@@ -379,6 +564,96 @@ pub enum TemplateAction {
         #[arg(short, long)]
         sections: Option<String>,
     },
+    /// Install a template package from a local directory, .zip or .tar.gz
+    Install {
+        /// Path to the package directory or archive
+        source: String,
+        /// Symlink the directory instead of copying (for template development)
+        #[arg(long)]
+        link: bool,
+    },
+    /// Scaffold a new template package
+    NewPackage {
+        /// Package name (lowercase letters, digits and hyphens)
+        name: String,
+        /// Directory to create the package in (defaults to ./<name>)
+        #[arg(long)]
+        path: Option<String>,
+        /// Also install the package locally for testing
+        #[arg(long)]
+        install: bool,
+    },
+    /// Check a note against the template package's validation rules
+    Lint {
+        /// Typst file to check
+        file: String,
+    },
+    /// Switch a template package back to a previously installed version
+    Rollback {
+        /// Version to activate (defaults to the previously installed one)
+        version: Option<String>,
+        /// Package or repository name (required if several are installed)
+        #[arg(long)]
+        package: Option<String>,
+        /// Rewrite imports in existing notes without asking
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Point the template imports of existing notes at the installed package versions
+    MigrateNotes {
+        /// Version to migrate to (defaults to the active installed version)
+        #[arg(long)]
+        to: Option<String>,
+        /// Only migrate imports of this package
+        #[arg(long)]
+        package: Option<String>,
+        /// Show the changes without writing them
+        #[arg(long)]
+        dry_run: bool,
+        /// Compile migrated notes and restore any that fail
+        #[arg(long)]
+        compile: bool,
+    },
+    /// Render a template with sample data to PDF
+    Preview {
+        /// Template name (e.g. note, assignment)
+        #[arg(required_unless_present = "all")]
+        template: Option<String>,
+        /// Template variant (e.g. math)
+        #[arg(long)]
+        variant: Option<String>,
+        /// Course code used for the sample data
+        #[arg(long, default_value = "01005")]
+        course: String,
+        /// Template package to use
+        #[arg(long)]
+        package: Option<String>,
+        /// Render every template and variant
+        #[arg(long, conflicts_with_all = ["template", "variant"])]
+        all: bool,
+        /// Don't open the rendered PDF
+        #[arg(long)]
+        no_open: bool,
+    },
+    /// Inspect and maintain the template download cache
+    Cache {
+        #[command(subcommand)]
+        action: TemplateCacheAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TemplateCacheAction {
+    /// List cached template archives and their sizes
+    List,
+    /// Delete old cached versions
+    Prune {
+        /// Number of versions to keep per repository
+        #[arg(long, default_value = "1")]
+        keep: usize,
+    },
+    /// Check cached archives against their checksums and that they extract
+    Verify,
 }
 
 #[cfg(feature = "dev-tools")]
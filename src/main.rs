@@ -38,9 +38,12 @@ mod data;
 mod dev;
 mod display;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use noter::{AssignmentAction, Commands, ConfigAction, CourseAction, SetupAction, TemplateAction};
+use noter::{
+    AssignmentAction, Commands, ConfigAction, CourseAction, ProfileAction, SetupAction,
+    TemplateAction, TemplateCacheAction,
+};
 
 #[cfg(feature = "dev-tools")]
 use noter::DevAction;
@@ -54,6 +57,18 @@ use noter::DevAction;
 #[command(about = "DTU note-taking CLI with official branding")]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 struct Cli {
+    /// Don't access the network; install templates from the download cache
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Use a config profile for this run (see 'noter config profile')
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Override a config key for this run (repeatable, e.g. --set search.max_results=10)
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE")]
+    set: Vec<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -70,6 +85,15 @@ struct Cli {
 /// if any command fails.
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    config::UserTemplateConfig::set_offline_override(cli.offline);
+    config::layers::set_profile_override(cli.profile);
+    config::layers::set_command_line_overrides(
+        cli.set
+            .iter()
+            .map(|set| core::validation::Validator::parse_variable_assignment(set))
+            .collect::<Result<_>>()
+            .context("Invalid --set option")?,
+    );
     commands::execute_command(&cli.command)?;
     Ok(())
 }